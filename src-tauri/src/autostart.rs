@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tauri::{AppHandle, Manager};
+
+use crate::config::pod_state::PodStateConfig;
+use crate::error::{NookError, NookResult};
+use crate::state::AppState;
+use crate::types::{DockerConnectionStatus, PodStatus};
+
+/// Command-line flag that starts Nook with its window minimized, as the
+/// login entry does.
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+/// Pods started at the same time on launch.
+const MAX_PARALLEL_STARTS: usize = 2;
+
+/// How long launch waits for Docker endpoints to answer before starting
+/// the pods it has found so far.
+const CONNECT_WAIT: Duration = Duration::from_secs(30);
+
+/// Start every stopped pod whose config has `autostart` set, through
+/// `start_pod` and at most `MAX_PARALLEL_STARTS` at a time. Waits for the
+/// Docker endpoints to connect first, since pods are only known once their
+/// endpoint has been listed. Failures end up on the pod like any other
+/// failed start, via `pod-status-changed`.
+pub async fn start_flagged_pods(state: &AppState, app: &AppHandle) {
+    wait_for_endpoints(state).await;
+
+    let candidates: Vec<(String, String)> = state
+        .lock()
+        .await
+        .pods
+        .values()
+        .filter(|p| matches!(p.status, PodStatus::Stopped | PodStatus::Error))
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .collect();
+    let flagged: Vec<String> = candidates
+        .into_iter()
+        .filter(|(_, name)| {
+            PodStateConfig::load(name)
+                .ok()
+                .flatten()
+                .is_some_and(|c| c.autostart)
+        })
+        .map(|(id, _)| id)
+        .collect();
+    if flagged.is_empty() {
+        return;
+    }
+    tracing::info!("Starting {} pods on launch", flagged.len());
+
+    futures_util::stream::iter(flagged)
+        .for_each_concurrent(MAX_PARALLEL_STARTS, |id| async move {
+            let state = app.state::<AppState>();
+            if let Err(e) = crate::commands::start_pod(state, app.clone(), id.clone()).await {
+                tracing::warn!("Failed to start pod {} on launch: {}", id, e);
+            }
+        })
+        .await;
+}
+
+async fn wait_for_endpoints(state: &AppState) {
+    let deadline = tokio::time::Instant::now() + CONNECT_WAIT;
+    while tokio::time::Instant::now() < deadline {
+        let connecting = state
+            .lock()
+            .await
+            .endpoints
+            .values()
+            .any(|e| e.connection.status == DockerConnectionStatus::Connecting);
+        if !connecting {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    tracing::warn!("Docker endpoints still connecting, starting pods found so far");
+}
+
+/// Register Nook to start minimized at login, or remove the registration.
+/// On Linux this is an XDG autostart entry; elsewhere it isn't supported
+/// yet.
+pub fn set_launch_at_login(enabled: bool) -> NookResult<()> {
+    let path = desktop_entry_path()?;
+    if !enabled {
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| {
+                NookError::ConfigError(format!("Failed to remove autostart entry: {}", e))
+            })?;
+        }
+        return Ok(());
+    }
+    if !cfg!(target_os = "linux") {
+        return Err(NookError::ConfigError(
+            "Launch at login is only supported on Linux".to_string(),
+        ));
+    }
+    let exe = std::env::current_exe().map_err(|e| {
+        NookError::ConfigError(format!("Failed to locate the Nook executable: {}", e))
+    })?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            NookError::ConfigError(format!("Failed to create autostart dir: {}", e))
+        })?;
+    }
+    std::fs::write(&path, desktop_entry(&exe))
+        .map_err(|e| NookError::ConfigError(format!("Failed to write autostart entry: {}", e)))?;
+    Ok(())
+}
+
+/// `$XDG_CONFIG_HOME/autostart/nook.desktop`
+fn desktop_entry_path() -> NookResult<PathBuf> {
+    let base = dirs::config_dir().ok_or_else(|| {
+        NookError::ConfigError("Could not determine config directory".to_string())
+    })?;
+    Ok(base.join("autostart").join("nook.desktop"))
+}
+
+fn desktop_entry(exe: &Path) -> String {
+    // Exec arguments with spaces must be quoted, and `"`, `` ` ``, `$` and
+    // `\` escaped inside the quotes
+    let exe = exe.to_string_lossy();
+    let exe = if exe.contains(' ') {
+        let escaped: String = exe
+            .chars()
+            .flat_map(|c| match c {
+                '"' | '`' | '$' | '\\' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect();
+        format!("\"{}\"", escaped)
+    } else {
+        exe.into_owned()
+    };
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Nook\n\
+         Comment=Devcontainer manager\n\
+         Exec={} {}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exe, MINIMIZED_FLAG
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/usr/bin/nook"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=/usr/bin/nook --minimized\n"));
+
+        let entry = desktop_entry(Path::new("/opt/My Apps/nook"));
+        assert!(entry.contains("\nExec=\"/opt/My Apps/nook\" --minimized\n"));
+    }
+}
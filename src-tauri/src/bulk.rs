@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::groups::PodGroup;
+use crate::config::pod_state::PodStateConfig;
+use crate::dependencies;
+use crate::error::{NookError, NookResult};
+use crate::network::expose::{self, ExposeTarget};
+use crate::state::AppState;
+use crate::types::{GroupPodResult, PodStatus, PortExposedEvent, PortStatus};
+
+/// Pods a group action works on at the same time.
+const MAX_PARALLEL: usize = 3;
+
+type Outcomes = HashMap<String, Result<(), String>>;
+
+/// Something done to every pod of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupAction {
+    /// Start the pods that aren't running, after their dependencies
+    Start,
+    /// Stop the running pods, dependents first
+    Stop,
+    Rebuild,
+    /// Expose every detected port on the same host port
+    Expose,
+}
+
+/// Run `action` on every pod of the group `name`, at most `MAX_PARALLEL`
+/// pods at a time and each under its pod lock. A pod failing doesn't stop
+/// the others; every pod gets its own result, in the group's order.
+pub async fn run_group(
+    app: &AppHandle,
+    name: &str,
+    action: GroupAction,
+) -> NookResult<Vec<GroupPodResult>> {
+    let group = PodGroup::load(name)?
+        .ok_or_else(|| NookError::ConfigError(format!("Group not found: {}", name)))?;
+    tracing::info!(
+        "{:?} group {} ({} pods)",
+        action,
+        group.name,
+        group.pods.len()
+    );
+
+    let mut outcomes = match action {
+        GroupAction::Start => start_all(app, &group.pods).await,
+        GroupAction::Stop => stop_all(app, &group.pods).await,
+        GroupAction::Rebuild => {
+            in_parallel(group.pods.clone(), |name| async move {
+                let state = app.state::<AppState>();
+                let (id, _) = find_pod(&state, &name).await?;
+                crate::commands::rebuild_pod(state, app.clone(), id, None).await
+            })
+            .await
+        }
+        GroupAction::Expose => {
+            in_parallel(group.pods.clone(), |name| async move {
+                expose_detected(app, &name).await
+            })
+            .await
+        }
+    };
+
+    let state = app.state::<AppState>();
+    let s = state.lock().await;
+    Ok(group
+        .pods
+        .iter()
+        .map(|pod_name| GroupPodResult {
+            pod_name: pod_name.clone(),
+            pod_id: s
+                .pods
+                .values()
+                .find(|p| &p.name == pod_name)
+                .map(|p| p.id.clone()),
+            error: outcomes.remove(pod_name).and_then(Result::err),
+        })
+        .collect())
+}
+
+/// Run `f` for each pod name, `MAX_PARALLEL` at a time.
+async fn in_parallel<F, Fut>(names: Vec<String>, f: F) -> Outcomes
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    futures_util::stream::iter(names)
+        .map(|name| {
+            let result = f(name.clone());
+            async move { (name, result.await) }
+        })
+        .buffer_unordered(MAX_PARALLEL)
+        .collect()
+        .await
+}
+
+async fn find_pod(state: &AppState, name: &str) -> Result<(String, PodStatus), String> {
+    state
+        .lock()
+        .await
+        .pods
+        .values()
+        .find(|p| p.name == name)
+        .map(|p| (p.id.clone(), p.status.clone()))
+        .ok_or_else(|| format!("Pod not found: {}", name))
+}
+
+/// Start `names` and everything they depend on, one dependency level at a
+/// time. Pods depended on are waited on until they pass their readiness
+/// check; a pod whose dependency failed isn't started.
+async fn start_all(app: &AppHandle, names: &[String]) -> Outcomes {
+    let plan = dependencies::load_configs().and_then(|configs| {
+        let levels = dependencies::start_levels(&configs, names)?;
+        Ok((configs, levels))
+    });
+    let (configs, levels) = match plan {
+        Ok(plan) => plan,
+        Err(e) => return failed_all(names, &e.to_string()),
+    };
+    let waited_on: HashSet<&String> = levels
+        .iter()
+        .flatten()
+        .filter_map(|name| configs.get(name))
+        .flat_map(|c| c.depends_on.iter())
+        .collect();
+
+    let mut outcomes = Outcomes::new();
+    for level in levels {
+        let done = &outcomes;
+        let configs = &configs;
+        let waited_on = &waited_on;
+        let results = in_parallel(level, |name| async move {
+            let config = configs.get(&name);
+            let state = app.state::<AppState>();
+            let (id, status) = find_pod(&state, &name).await?;
+            let failed_dep = config
+                .map(|c| c.depends_on.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .find_map(|dep| match done.get(dep) {
+                    Some(Err(e)) => Some((dep, e)),
+                    _ => None,
+                });
+            if let Some((dep, e)) = failed_dep {
+                let message = format!("Dependency {} failed: {}", dep, e);
+                crate::commands::fail_pods(&state, app, std::slice::from_ref(&id), &message).await;
+                return Err(message);
+            }
+            if !matches!(status, PodStatus::Running | PodStatus::Starting) {
+                crate::commands::start_single_pod(state.clone(), app.clone(), id.clone()).await?;
+            }
+            match config.and_then(|c| c.readiness.as_ref()) {
+                Some(readiness) if waited_on.contains(&name) => {
+                    dependencies::wait_ready(&state, &id, readiness)
+                        .await
+                        .map_err(|e| e.to_string())
+                }
+                _ => Ok(()),
+            }
+        })
+        .await;
+        outcomes.extend(results);
+    }
+    outcomes
+}
+
+/// Stop the running pods of `names`, pods before the pods they depend on.
+async fn stop_all(app: &AppHandle, names: &[String]) -> Outcomes {
+    let levels = dependencies::load_configs()
+        .and_then(|configs| dependencies::start_levels(&configs, names))
+        .unwrap_or_else(|_| vec![names.to_vec()]);
+
+    let mut outcomes = Outcomes::new();
+    for mut level in levels.into_iter().rev() {
+        level.retain(|name| names.contains(name));
+        let results = in_parallel(level, |name| async move {
+            let state = app.state::<AppState>();
+            let (id, status) = find_pod(&state, &name).await?;
+            if status == PodStatus::Stopped {
+                return Ok(());
+            }
+            crate::commands::stop_single_pod(state, app.clone(), id).await
+        })
+        .await;
+        outcomes.extend(results);
+    }
+    outcomes
+}
+
+/// Expose the ports detected in a running pod, each on the host port of
+/// the same number unless the pod maps it elsewhere.
+async fn expose_detected(app: &AppHandle, name: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let (id, status) = find_pod(&state, name).await?;
+    if status != PodStatus::Running {
+        return Err("Pod is not running".to_string());
+    }
+    let (pod_lock, detected) = {
+        let mut s = state.lock().await;
+        let detected = s
+            .pods
+            .get(&id)
+            .map(|p| p.detected_ports.clone())
+            .unwrap_or_default();
+        (s.get_or_create_pod_lock(&id), detected)
+    };
+    let _guard = pod_lock.lock().await;
+    let pod_config = PodStateConfig::load(name).ok().flatten();
+
+    let mut failures = Vec::new();
+    for port in detected {
+        let target = ExposeTarget {
+            container_port: port.container_port,
+            host_port: pod_config
+                .as_ref()
+                .map_or(port.container_port, |c| {
+                    c.host_port(port.service.as_deref(), port.container_port)
+                }),
+            service: port.service.clone(),
+            protocol: Some(port.protocol.clone()),
+            label: port.label.clone(),
+        };
+        match expose::expose_pod_port(&state, &id, target).await {
+            Ok(exposed) => {
+                if exposed.status == PortStatus::HostPortBusy {
+                    failures.push(format!("port {}: host port is busy", port.container_port));
+                }
+                let _ = app.emit(
+                    "port-exposed",
+                    &PortExposedEvent {
+                        pod_id: id.clone(),
+                        port: exposed,
+                    },
+                );
+            }
+            Err(e) => failures.push(format!("port {}: {}", port.container_port, e)),
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("; "))
+    }
+}
+
+fn failed_all(names: &[String], message: &str) -> Outcomes {
+    names
+        .iter()
+        .map(|name| (name.clone(), Err(message.to_string())))
+        .collect()
+}
@@ -1,12 +1,26 @@
 use tauri::{AppHandle, Emitter, State};
 
-use crate::config::pod_state::PodStateConfig;
-use crate::devcontainer::cli;
-use crate::docker::containers;
+use crate::bulk::{self, GroupAction};
+use crate::config::groups::PodGroup;
+use crate::config::pod_state::{DevcontainerEngine, PodStateConfig};
+use crate::config::snapshots::PodSnapshot;
+use crate::devcontainer::config::{ConfigSnapshot, DevcontainerPreview};
+use crate::devcontainer::{cli, config, discovery, drift, fast_start, lifecycle, native};
+use crate::docker::endpoint::{self, DockerEndpoint};
+use crate::dependencies;
+use crate::git;
+use crate::docker::restart;
+use crate::docker::shutdown::{self, StopPolicy};
+use crate::docker::{self, compose, containers, snapshots, volumes};
+use crate::sandbox;
+use crate::scheduler;
 use crate::state::AppState;
 use crate::terminal;
+use crate::terminal::session::{ExecTarget, TerminalSession};
 use crate::types::{
-    DependencyCheck, ExposedPort, Pod, PodStatus, PodStatusChanged, ProcessListUpdate,
+    DependencyCheck, DevcontainerConfigInfo, DockerConnection, ExposedPort, GroupPodResult,
+    LifecycleHook, Pod, PodMount, PodScheduleStatus, PodServicesUpdate, PodStatus, PodStatusChanged,
+    ProcessListUpdate, RestartRecord, TerminalExit, TerminalOutput, VolumeBackup,
 };
 
 #[tauri::command]
@@ -16,22 +30,217 @@ pub async fn list_pods(state: State<'_, AppState>) -> Result<Vec<Pod>, String> {
     Ok(pods)
 }
 
+#[tauri::command]
+pub async fn list_devcontainer_configs(path: String) -> Result<Vec<DevcontainerConfigInfo>, String> {
+    Ok(discovery::find_configs(&path))
+}
+
+#[tauri::command]
+pub async fn preview_devcontainer(
+    path: String,
+    config_path: Option<String>,
+) -> Result<DevcontainerPreview, String> {
+    Ok(config::preview(&path, config_path.as_deref()))
+}
+
 #[tauri::command]
 pub async fn add_pod(
     state: State<'_, AppState>,
+    app: AppHandle,
     path: String,
+    config_path: Option<String>,
 ) -> Result<Pod, String> {
-    let name = std::path::Path::new(&path)
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unnamed".to_string());
+    // Store default locations as "" so they keep following devcontainer CLI discovery
+    let config_path = config_path
+        .map(|c| discovery::relative_config_path(&path, &c))
+        .unwrap_or_default();
+    if !config_path.is_empty() && discovery::resolve_config_path(&path, Some(&config_path)).is_none() {
+        return Err(format!("devcontainer config not found: {}", config_path));
+    }
+    if PodStateConfig::find_by_project_and_config(&path, &config_path)
+        .map_err(|e| e.to_string())?
+        .is_some()
+    {
+        return Err(format!(
+            "A pod for this devcontainer config already exists: {}",
+            path
+        ));
+    }
+
+    let name = PodStateConfig::unique_name(&discovery::pod_name_for(
+        &path,
+        Some(config_path.as_str()).filter(|c| !c.is_empty()),
+    ))
+    .map_err(|e| e.to_string())?;
 
     let pod_id = uuid::Uuid::new_v4().to_string();
 
-    let pod = Pod {
-        id: pod_id.clone(),
+    let pod = new_pod(&pod_id, &name, &path, &config_path);
+
+    // Save pod state
+    let config = PodStateConfig {
         name: name.clone(),
-        project_path: path.clone(),
+        project_path: path,
+        config_path,
+        ..Default::default()
+    };
+    config.save().map_err(|e| e.to_string())?;
+
+    let mut state_guard = state.lock().await;
+    state_guard.pods.insert(pod_id.clone(), pod.clone());
+    drift::watch(&mut state_guard, &state, &app, &pod_id);
+    git::watch(&mut state_guard, &state, &app, &pod_id);
+
+    Ok(pod)
+}
+
+/// Create a second pod of a pod's project at `path`: a git worktree of
+/// `branch`, or a copy of the project directory without one. The clone gets
+/// its own config, name and container, and host ports that don't clash
+/// with other pods.
+#[tauri::command]
+pub async fn clone_pod(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+    path: String,
+    branch: Option<String>,
+) -> Result<Pod, String> {
+    let target = std::path::PathBuf::from(path.trim());
+    if !target.is_absolute() {
+        return Err("The clone's location must be an absolute path".to_string());
+    }
+    if !PodStateConfig::list_by_project_path(path.trim())
+        .map_err(|e| e.to_string())?
+        .is_empty()
+    {
+        return Err(format!("A pod already exists at {}", path.trim()));
+    }
+    let branch = branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+    let source_pod = {
+        let state = state.lock().await;
+        state
+            .pods
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| format!("Pod not found: {}", id))?
+    };
+    let source = PodStateConfig::load(&source_pod.name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No saved settings for pod {}", source_pod.name))?;
+
+    let project_path = sandbox::checkout(&source.project_path, &target, branch.as_deref())
+        .await
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    let name = PodStateConfig::unique_name(&format!(
+        "{}-{}",
+        source.name,
+        sandbox::name_suffix(branch.as_deref())
+    ))
+    .map_err(|e| e.to_string())?;
+
+    let ports = sandbox::host_facing_ports(&source_pod, &source);
+    let configs = PodStateConfig::list_all().map_err(|e| e.to_string())?;
+    let port_mappings = {
+        let state = state.lock().await;
+        let pods: Vec<&Pod> = state.pods.values().collect();
+        let taken = sandbox::taken_host_ports(&configs, &pods, &ports);
+        sandbox::remap_ports(&ports, &taken, sandbox::port_is_free)
+    };
+    let config = sandbox::clone_config(&source, &name, &project_path, port_mappings);
+    config.save().map_err(|e| e.to_string())?;
+    tracing::info!(
+        "Cloned pod {} as {} at {}",
+        source.name,
+        name,
+        project_path
+    );
+
+    let pod_id = uuid::Uuid::new_v4().to_string();
+    let mut pod = new_pod(&pod_id, &name, &project_path, &source.config_path);
+    pod.endpoint = source_pod.endpoint.clone();
+    let mut state_guard = state.lock().await;
+    state_guard.pods.insert(pod_id.clone(), pod.clone());
+    drift::watch(&mut state_guard, &state, &app, &pod_id);
+    git::watch(&mut state_guard, &state, &app, &pod_id);
+
+    Ok(pod)
+}
+
+/// Check `branch` of the repository at `path` out into a new worktree at
+/// `worktree_path` and add it as a pod. When `path` already has a pod, the
+/// new one is a clone of it, with its settings and host ports of its own.
+#[tauri::command]
+pub async fn add_pod_from_branch(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    path: String,
+    branch: String,
+    worktree_path: String,
+    config_path: Option<String>,
+) -> Result<Pod, String> {
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err("A branch is required".to_string());
+    }
+    let target = std::path::PathBuf::from(worktree_path.trim());
+    if !target.is_absolute() {
+        return Err("The worktree's location must be an absolute path".to_string());
+    }
+
+    let relative_config = config_path
+        .as_deref()
+        .map(|c| discovery::relative_config_path(&path, c))
+        .unwrap_or_default();
+    let existing = PodStateConfig::find_by_project_and_config(&path, &relative_config)
+        .map_err(|e| e.to_string())?;
+    let existing_id = match existing {
+        Some(config) => state
+            .lock()
+            .await
+            .pods
+            .values()
+            .find(|p| p.name == config.name)
+            .map(|p| p.id.clone()),
+        None => None,
+    };
+    if let Some(id) = existing_id {
+        return clone_pod(state, app, id, worktree_path, Some(branch.to_string())).await;
+    }
+
+    let project_path = sandbox::checkout(&path, &target, Some(branch))
+        .await
+        .map_err(|e| e.to_string())?;
+    add_pod(
+        state,
+        app,
+        project_path.to_string_lossy().to_string(),
+        config_path,
+    )
+    .await
+}
+
+/// Re-read the git state of every pod, e.g. for edits not staged yet, which
+/// the watchers don't see.
+#[tauri::command]
+pub async fn refresh_git(state: State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    let ids: Vec<String> = state.lock().await.pods.keys().cloned().collect();
+    for id in ids {
+        git::check(&state, &app, &id).await;
+    }
+    Ok(())
+}
+
+/// A stopped pod without a container yet.
+fn new_pod(id: &str, name: &str, project_path: &str, config_path: &str) -> Pod {
+    Pod {
+        id: id.to_string(),
+        name: name.to_string(),
+        project_path: project_path.to_string(),
         image: String::new(),
         status: PodStatus::Stopped,
         uptime_secs: None,
@@ -49,40 +258,139 @@ pub async fn add_pod(
         remote_user: None,
         remote_workspace_folder: None,
         container_name: None,
-    };
+        config_path: Some(config_path.to_string()).filter(|c| !c.is_empty()),
+        compose_project: None,
+        services: Vec::new(),
+        hook_runs: Vec::new(),
+        warning: None,
+        config_drift: None,
+        health: None,
+        last_exit: None,
+        endpoint: String::new(),
+        git: None,
+    }
+}
 
-    // Save pod state
-    let config = PodStateConfig {
-        name: name.clone(),
-        project_path: path,
-        shell: String::new(),
-        auto_expose_ports: Vec::new(),
-        port_mappings: std::collections::HashMap::new(),
-        alias: String::new(),
-        not_expose_filters: Vec::new(),
-        manual_expose_ports: Vec::new(),
-        terminal_override: String::new(),
-        working_dir: String::new(),
-        bind_address: String::new(),
-        remote_user: String::new(),
-    };
-    config.save().map_err(|e| e.to_string())?;
+/// Start a pod after the pods it depends on, see `start_chain`.
+#[tauri::command]
+pub async fn start_pod(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<(), String> {
+    start_chain(state, &app, std::slice::from_ref(&id)).await
+}
 
-    let mut state = state.lock().await;
-    state.pods.insert(pod_id, pod.clone());
+/// Start several pods and everything they depend on, dependencies first.
+#[tauri::command]
+pub async fn start_pods(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    ids: Vec<String>,
+) -> Result<(), String> {
+    start_chain(state, &app, &ids).await
+}
 
-    Ok(pod)
+/// Start `ids` after their dependencies, in dependency order. Dependencies
+/// that aren't running are started and then waited on until they pass
+/// their readiness check. If one fails, the pods in `ids` are moved to
+/// `Error` with the reason.
+async fn start_chain(
+    state: State<'_, AppState>,
+    app: &AppHandle,
+    ids: &[String],
+) -> Result<(), String> {
+    let configs = dependencies::load_configs().map_err(|e| e.to_string())?;
+    let roots: Vec<String> = {
+        let s = state.lock().await;
+        ids.iter()
+            .map(|id| {
+                s.pods
+                    .get(id)
+                    .map(|p| p.name.clone())
+                    .ok_or_else(|| format!("Pod not found: {}", id))
+            })
+            .collect::<Result<_, _>>()?
+    };
+    let order = dependencies::start_order(&configs, &roots).map_err(|e| e.to_string())?;
+
+    for name in order {
+        let is_root = roots.contains(&name);
+        let found = {
+            let s = state.lock().await;
+            s.pods
+                .values()
+                .find(|p| p.name == name)
+                .map(|p| (p.id.clone(), p.status.clone()))
+        };
+        let result = match found {
+            None => Err(format!(
+                "Dependency {} has no container yet; start it once on its own",
+                name
+            )),
+            Some((pod_id, status)) => {
+                let started = if is_root
+                    || !matches!(status, PodStatus::Running | PodStatus::Starting)
+                {
+                    start_single_pod(state.clone(), app.clone(), pod_id.clone()).await
+                } else {
+                    Ok(())
+                };
+                match (started, configs.get(&name).and_then(|c| c.readiness.as_ref())) {
+                    (Ok(()), Some(readiness)) if !is_root => {
+                        dependencies::wait_ready(&state, &pod_id, readiness)
+                            .await
+                            .map_err(|e| e.to_string())
+                    }
+                    (started, _) => started,
+                }
+            }
+        };
+        if let Err(e) = result {
+            if is_root {
+                return Err(e);
+            }
+            let message = format!("Dependency {} failed: {}", name, e);
+            fail_pods(&state, app, ids, &message).await;
+            return Err(message);
+        }
+    }
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn start_pod(
+/// Move pods that aren't running to `Error` with `message`.
+pub(crate) async fn fail_pods(state: &AppState, app: &AppHandle, ids: &[String], message: &str) {
+    let mut s = state.lock().await;
+    for id in ids {
+        let Some(pod) = s.pods.get_mut(id) else {
+            continue;
+        };
+        if matches!(pod.status, PodStatus::Running | PodStatus::Starting) {
+            continue;
+        }
+        pod.status = PodStatus::Error;
+        pod.error_message = Some(message.to_string());
+        let _ = app.emit(
+            "pod-status-changed",
+            &PodStatusChanged {
+                pod_id: id.clone(),
+                status: PodStatus::Error,
+                error_message: Some(message.to_string()),
+                exit: None,
+                health: None,
+            },
+        );
+    }
+}
+
+pub(crate) async fn start_single_pod(
     state: State<'_, AppState>,
     app: AppHandle,
     id: String,
 ) -> Result<(), String> {
-    let (project_path, pod_lock) = {
+    let (project_path, config_file, snapshot, previous_container, cli_opts, engine, pod_lock) = {
         let mut state = state.lock().await;
-        {
+        let config_file = {
             let pod = state
                 .pods
                 .get_mut(&id)
@@ -92,9 +400,18 @@ pub async fn start_pod(
                 return Err("Pod is already running or starting".to_string());
             }
 
+            let config_file = config_file_arg(&pod.project_path, pod.config_path.as_deref())?;
+
             pod.status = PodStatus::Starting;
             pod.error_message = None;
-        }
+            pod.hook_runs.clear();
+            pod.warning = None;
+            pod.last_exit = None;
+            pod.health = None;
+            config_file
+        };
+        // A manual start overtakes a restart waiting out its backoff
+        restart::cancel_pending(&mut state, &id);
 
         let _ = app.emit(
             "pod-status-changed",
@@ -102,12 +419,30 @@ pub async fn start_pod(
                 pod_id: id.clone(),
                 status: PodStatus::Starting,
                 error_message: None,
+                exit: None,
+                health: None,
             },
         );
 
-        let project_path = state.pods.get(&id).unwrap().project_path.clone();
+        let pod = state.pods.get(&id).unwrap();
+        let project_path = pod.project_path.clone();
+        // Taken before the build so edits made during it count as drift
+        let snapshot = ConfigSnapshot::take(&project_path, pod.config_path.as_deref());
+        let previous_container = pod.container_id.clone();
+        let engine = pod_engine(&pod.name);
+        let cli_opts = cli::CliOptions::from_settings(&state.settings)
+            .with_endpoint(state.endpoint_for(&id))
+            .with_runtime(state.runtime_for(&id));
         let lock = state.get_or_create_pod_lock(&id);
-        (project_path, lock)
+        (
+            project_path,
+            config_file,
+            snapshot,
+            previous_container,
+            cli_opts,
+            engine,
+            lock,
+        )
     };
 
     let _guard = pod_lock.lock().await;
@@ -119,17 +454,43 @@ pub async fn start_pod(
         s.build_cancel_tokens.insert(id.clone(), build_cancel.clone());
     }
 
-    // Use streaming build to emit log lines during devcontainer up
+    // An unchanged config starts the existing container directly; otherwise
+    // both engines stream build output to the pod's log
     let inner_state: crate::state::AppState = (*state).clone();
-    match cli::devcontainer_up_streaming(
-        &project_path,
-        &id,
-        &app,
-        &inner_state,
-        build_cancel.clone(),
-    )
-    .await
-    {
+    let fast_started = fast_start::try_fast_start(&inner_state, &app, &id).await;
+    // A direct start and the native engine run lifecycle hooks themselves;
+    // the devcontainer CLI is told to skip them
+    let hooks_pending = fast_started.is_none() && engine == DevcontainerEngine::Cli;
+    let up_result = match fast_started {
+        Some(output) => Ok(output),
+        None => match engine {
+            DevcontainerEngine::Cli => {
+                cli::devcontainer_up_streaming(
+                    &cli_opts,
+                    &project_path,
+                    config_file.as_deref(),
+                    &id,
+                    &app,
+                    &inner_state,
+                    build_cancel.clone(),
+                )
+                .await
+            }
+            DevcontainerEngine::Native => {
+                native::up(
+                    &project_path,
+                    config_file.as_deref(),
+                    false,
+                    &id,
+                    &app,
+                    &inner_state,
+                    build_cancel.clone(),
+                )
+                .await
+            }
+        },
+    };
+    match up_result {
         Ok(output) => {
             // Remove build cancel token
             {
@@ -143,10 +504,20 @@ pub async fn start_pod(
             let remote_user = dc_result.remote_user;
             let remote_workspace_folder = dc_result.remote_workspace_folder;
 
-            // Extract image name, container name, and detect shell from Docker inspect
-            let (image_name, detected_shell, container_name) = if let Some(ref cid) = container_id {
+            if let (true, Some(cid)) = (hooks_pending, &container_id) {
+                let target = lifecycle::HookTarget {
+                    container_id: cid,
+                    user: remote_user.as_deref(),
+                    working_dir: remote_workspace_folder.as_deref(),
+                };
+                let created = previous_container.as_ref() != Some(cid);
+                lifecycle::run_up_hooks(&inner_state, &app, &id, &target, created).await;
+            }
+
+            // Extract image name, container name, compose project, and detect shell from Docker inspect
+            let (image_name, detected_shell, container_name, compose_project) = if let Some(ref cid) = container_id {
                 let state_guard = state.lock().await;
-                if let Some(docker) = &state_guard.docker {
+                if let Some(docker) = &state_guard.docker_for(&id) {
                     let inspect = containers::inspect_container(docker, cid).await.ok();
                     let img = inspect
                         .as_ref()
@@ -156,20 +527,27 @@ pub async fn start_pod(
                         .as_ref()
                         .and_then(|info| info.name.clone())
                         .map(|n| n.trim_start_matches('/').to_string());
+                    let project = inspect
+                        .as_ref()
+                        .and_then(|info| info.config.as_ref())
+                        .and_then(|c| c.labels.as_ref())
+                        .and_then(compose::project_from_labels);
                     let shell = terminal::shell::detect_shell(
                         docker,
                         cid,
                         remote_user.as_deref(),
                     )
                     .await;
-                    (img, shell, cname)
+                    (img, shell, cname, project)
                 } else {
-                    (None, "/bin/sh".to_string(), None)
+                    (None, "/bin/sh".to_string(), None, None)
                 }
             } else {
-                (None, "/bin/sh".to_string(), None)
+                (None, "/bin/sh".to_string(), None, None)
             };
 
+            // `up` reuses an existing container even if its config changed
+            let new_container = container_id != previous_container;
             let mut state = state.lock().await;
             if let Some(pod) = state.pods.get_mut(&id) {
                 pod.status = PodStatus::Running;
@@ -179,6 +557,7 @@ pub async fn start_pod(
                 pod.remote_user = remote_user.clone();
                 pod.remote_workspace_folder = remote_workspace_folder.clone();
                 pod.container_name = container_name;
+                pod.compose_project = compose_project;
                 if let Some(ref img) = image_name {
                     pod.image = img.clone();
                 }
@@ -203,6 +582,15 @@ pub async fn start_pod(
                         cfg.shell = detected_shell.clone();
                         changed = true;
                     }
+                    // Record what the container was built from; a container
+                    // from before any snapshot is taken as matching
+                    if new_container || cfg.config_snapshot.is_none() {
+                        if let Some(snapshot) = snapshot {
+                            cfg.config_hash = snapshot.fingerprint();
+                            cfg.config_snapshot = Some(snapshot);
+                            changed = true;
+                        }
+                    }
                     if changed {
                         let _ = cfg.save();
                     }
@@ -215,76 +603,23 @@ pub async fn start_pod(
                     pod_id: id.clone(),
                     status: PodStatus::Running,
                     error_message: None,
+                    exit: None,
+                    health: None,
                 },
             );
 
-            // Start background monitoring tasks
-            let docker_clone = state.docker.clone();
-            let cid = state.pods.get(&id).and_then(|p| p.container_id.clone());
-
-            if let (Some(docker), Some(container_id)) = (docker_clone, cid) {
-                let cancel = tokio_util::sync::CancellationToken::new();
-                state.cancellation_tokens.insert(id.clone(), cancel.clone());
-                let interval = state.settings.process_scan_interval;
-
-                // Stats streaming
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                tokio::spawn(async move {
-                    crate::docker::stats::stream_stats(docker_c, cid_c, id_c, app_c, cancel_c)
-                        .await;
-                });
-
-                // Container log streaming
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                let state_c = inner_state.clone();
-                tokio::spawn(async move {
-                    crate::docker::logs::stream_logs(
-                        docker_c, cid_c, id_c, app_c, state_c, cancel_c, 100,
-                    )
-                    .await;
-                });
-
-                // Process polling
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                tokio::spawn(async move {
-                    crate::docker::processes::poll_processes(
-                        docker_c, cid_c, id_c, app_c, cancel_c, interval,
-                    )
-                    .await;
-                });
-
-                // Port scanning
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                let port_interval = state.settings.ports_scan_interval;
-                let port_overrides: std::collections::HashMap<String, String> = state
-                    .settings
-                    .port_protocols
-                    .iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .collect();
-                tokio::spawn(async move {
-                    crate::network::scan::poll_ports(
-                        docker_c, cid_c, id_c, app_c, cancel_c, port_interval, port_overrides,
-                    )
-                    .await;
-                });
+            // Pick up compose sidecars, then start background monitoring tasks
+            drop(state);
+            compose::refresh_services(&inner_state, &app, &id).await;
+            {
+                let mut s = inner_state.lock().await;
+                crate::docker::monitor::start_monitoring(&mut s, &inner_state, &app, &id);
+                // Re-check drift against the new snapshot and follow any new files
+                drift::watch(&mut s, &inner_state, &app, &id);
             }
+
+            // Expose forwardPorts declared in devcontainer.json
+            crate::devcontainer::ports::forward_declared_ports(&inner_state, &app, &id).await;
             Ok(())
         }
         Err(e) => {
@@ -304,6 +639,8 @@ pub async fn start_pod(
                         pod_id: id.clone(),
                         status: PodStatus::Error,
                         error_message: Some(e.to_string()),
+                        exit: None,
+                        health: None,
                     },
                 );
             }
@@ -312,21 +649,70 @@ pub async fn start_pod(
     }
 }
 
+/// Stop a pod; with `stop_dependents`, the running pods that depend on it
+/// are stopped before it, see `get_pod_dependents`.
 #[tauri::command]
 pub async fn stop_pod(
     state: State<'_, AppState>,
     app: AppHandle,
     id: String,
+    stop_dependents: Option<bool>,
 ) -> Result<(), String> {
-    let (container_id, pod_lock) = {
+    if stop_dependents.unwrap_or(false) {
+        for dependent in running_dependents(&state, &id).await? {
+            stop_single_pod(state.clone(), app.clone(), dependent).await?;
+        }
+    }
+    stop_single_pod(state, app, id).await
+}
+
+/// Running pods that depend on the pod, directly or not, in the order they
+/// would be stopped.
+#[tauri::command]
+pub async fn get_pod_dependents(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<String>, String> {
+    running_dependents(&state, &id).await
+}
+
+async fn running_dependents(state: &AppState, id: &str) -> Result<Vec<String>, String> {
+    let configs = dependencies::load_configs().map_err(|e| e.to_string())?;
+    let s = state.lock().await;
+    let name = s
+        .pods
+        .get(id)
+        .map(|p| p.name.clone())
+        .ok_or_else(|| format!("Pod not found: {}", id))?;
+    Ok(dependencies::dependents(&configs, &name)
+        .into_iter()
+        .filter_map(|dependent| {
+            s.pods
+                .values()
+                .find(|p| {
+                    p.name == dependent
+                        && matches!(p.status, PodStatus::Running | PodStatus::Starting)
+                })
+                .map(|p| p.id.clone())
+        })
+        .collect())
+}
+
+pub(crate) async fn stop_single_pod(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<(), String> {
+    let (container_id, services, pod_lock, policy) = {
         let mut state = state.lock().await;
-        {
+        let pod_name = {
             let pod = state
                 .pods
                 .get_mut(&id)
                 .ok_or_else(|| format!("Pod not found: {}", id))?;
             pod.status = PodStatus::Stopping;
-        }
+            pod.name.clone()
+        };
 
         let _ = app.emit(
             "pod-status-changed",
@@ -334,40 +720,35 @@ pub async fn stop_pod(
                 pod_id: id.clone(),
                 status: PodStatus::Stopping,
                 error_message: None,
+                exit: None,
+                health: None,
             },
         );
 
-        // Cancel monitoring tasks
-        if let Some(cancel) = state.cancellation_tokens.remove(&id) {
-            cancel.cancel();
-        }
-
-        // Cancel all port proxies for this pod
-        let proxy_keys: Vec<(String, u16)> = state
-            .port_proxy_tokens
-            .keys()
-            .filter(|(pid, _)| pid == &id)
-            .cloned()
-            .collect();
-        for key in proxy_keys {
-            if let Some(cancel) = state.port_proxy_tokens.remove(&key) {
-                cancel.cancel();
-            }
-        }
+        crate::docker::monitor::stop_monitoring(&mut state, &id);
+        restart::reset(&mut state, &id);
 
-        let container_id = state.pods.get(&id).unwrap().container_id.clone();
+        let pod = state.pods.get(&id).unwrap();
+        let container_id = pod.container_id.clone();
+        let services = pod.services.clone();
         let lock = state.get_or_create_pod_lock(&id);
-        (container_id, lock)
+        let pod_config = PodStateConfig::load(&pod_name).ok().flatten();
+        let policy = StopPolicy::resolve(&state.settings, pod_config.as_ref());
+        (container_id, services, lock, policy)
     };
 
     let _guard = pod_lock.lock().await;
 
-    // Stop container via Docker API (sends SIGTERM, waits grace period, then SIGKILL)
-    if let Some(cid) = &container_id {
-        let state_guard = state.lock().await;
-        if let Some(docker) = &state_guard.docker {
-            let _ = containers::stop_container(docker, cid).await;
+    // Signal the container, wait for it to exit and kill it after the timeout,
+    // then stop the compose sidecars it may depend on
+    let docker = state.lock().await.docker_for(&id);
+    if let Some(docker) = &docker {
+        if let Some(cid) = &container_id {
+            if let Err(e) = shutdown::stop_gracefully(docker, &app, &id, cid, &policy).await {
+                tracing::warn!("Failed to stop pod {}: {}", id, e);
+            }
         }
+        compose::stop_services(docker, &services, policy.timeout_secs).await;
     }
 
     let mut state = state.lock().await;
@@ -379,12 +760,22 @@ pub async fn stop_pod(
         pod.cpu_percent = 0.0;
         pod.memory_used = 0;
         pod.started_at = None;
+        compose::mark_stopped(&mut pod.services);
         let _ = app.emit(
             "pod-status-changed",
             &PodStatusChanged {
                 pod_id: id.clone(),
                 status: PodStatus::Stopped,
                 error_message: None,
+                exit: None,
+                health: None,
+            },
+        );
+        let _ = app.emit(
+            "pod-services-update",
+            &PodServicesUpdate {
+                pod_id: id.clone(),
+                services: pod.services.clone(),
             },
         );
         // Emit authoritative empty process list to prevent stale data
@@ -407,7 +798,7 @@ pub async fn restart_pod(
     id: String,
 ) -> Result<(), String> {
     // Stop first, then start
-    stop_pod(state.clone(), app.clone(), id.clone()).await?;
+    stop_single_pod(state.clone(), app.clone(), id.clone()).await?;
     start_pod(state, app, id).await?;
     Ok(())
 }
@@ -418,13 +809,15 @@ pub async fn force_stop_pod(
     app: AppHandle,
     id: String,
 ) -> Result<(), String> {
-    let container_id = {
+    let (container_id, services) = {
         let mut state = state.lock().await;
         let pod = state
             .pods
             .get_mut(&id)
             .ok_or_else(|| format!("Pod not found: {}", id))?;
         let cid = pod.container_id.clone();
+        let services = pod.services.clone();
+        restart::reset(&mut state, &id);
 
         // Cancel monitoring tasks
         if let Some(cancel) = state.cancellation_tokens.remove(&id) {
@@ -444,15 +837,16 @@ pub async fn force_stop_pod(
             }
         }
 
-        cid
+        (cid, services)
     };
 
-    // Force kill container via Docker SIGKILL
-    if let Some(cid) = &container_id {
-        let state_guard = state.lock().await;
-        if let Some(docker) = &state_guard.docker {
+    // Force kill the container and its compose sidecars via Docker SIGKILL
+    let docker = state.lock().await.docker_for(&id);
+    if let Some(docker) = &docker {
+        if let Some(cid) = &container_id {
             let _ = containers::kill_container(docker, cid).await;
         }
+        compose::kill_services(docker, &services).await;
     }
 
     let mut state = state.lock().await;
@@ -464,12 +858,22 @@ pub async fn force_stop_pod(
         pod.cpu_percent = 0.0;
         pod.memory_used = 0;
         pod.started_at = None;
+        compose::mark_stopped(&mut pod.services);
         let _ = app.emit(
             "pod-status-changed",
             &PodStatusChanged {
                 pod_id: id.clone(),
                 status: PodStatus::Stopped,
                 error_message: None,
+                exit: None,
+                health: None,
+            },
+        );
+        let _ = app.emit(
+            "pod-services-update",
+            &PodServicesUpdate {
+                pod_id: id.clone(),
+                services: pod.services.clone(),
             },
         );
         let _ = app.emit(
@@ -484,12 +888,30 @@ pub async fn force_stop_pod(
     Ok(())
 }
 
+/// Recreate the pod's container; with `pull`, its base images are pulled
+/// again first, see `native::pull_base_images`.
 #[tauri::command]
 pub async fn rebuild_pod(
     state: State<'_, AppState>,
     app: AppHandle,
     id: String,
+    pull: Option<bool>,
 ) -> Result<(), String> {
+    // Pull while the old container still runs
+    if pull.unwrap_or(false) {
+        let (project_path, config_path) = {
+            let state = state.lock().await;
+            let pod = state
+                .pods
+                .get(&id)
+                .ok_or_else(|| format!("Pod not found: {}", id))?;
+            (pod.project_path.clone(), pod.config_path.clone())
+        };
+        native::pull_base_images(&project_path, config_path.as_deref(), &id, &app, &state)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
     // Stop if running
     let current_status = {
         let state = state.lock().await;
@@ -501,20 +923,26 @@ pub async fn rebuild_pod(
     };
 
     if current_status == PodStatus::Running || current_status == PodStatus::Starting {
-        stop_pod(state.clone(), app.clone(), id.clone()).await?;
+        stop_single_pod(state.clone(), app.clone(), id.clone()).await?;
     }
 
     // Now rebuild with --remove-existing-container
-    let project_path = {
+    let (project_path, config_file, snapshot, cli_opts, engine, pod_lock) = {
         let mut state = state.lock().await;
-        {
+        let config_file = {
             let pod = state
                 .pods
                 .get_mut(&id)
                 .ok_or_else(|| format!("Pod not found: {}", id))?;
+            let config_file = config_file_arg(&pod.project_path, pod.config_path.as_deref())?;
             pod.status = PodStatus::Starting;
             pod.error_message = None;
-        }
+            pod.hook_runs.clear();
+            pod.warning = None;
+            pod.last_exit = None;
+            pod.health = None;
+            config_file
+        };
 
         let _ = app.emit(
             "pod-status-changed",
@@ -522,12 +950,27 @@ pub async fn rebuild_pod(
                 pod_id: id.clone(),
                 status: PodStatus::Starting,
                 error_message: None,
+                exit: None,
+                health: None,
             },
         );
 
-        state.pods.get(&id).unwrap().project_path.clone()
+        let lock = state.get_or_create_pod_lock(&id);
+        let pod = state.pods.get(&id).unwrap();
+        (
+            pod.project_path.clone(),
+            config_file,
+            ConfigSnapshot::take(&pod.project_path, pod.config_path.as_deref()),
+            cli::CliOptions::from_settings(&state.settings)
+                .with_endpoint(state.endpoint_for(&id))
+                .with_runtime(state.runtime_for(&id)),
+            pod_engine(&pod.name),
+            lock,
+        )
     };
 
+    let _guard = pod_lock.lock().await;
+
     // Rebuild still uses the non-streaming rebuild command (--remove-existing-container)
     // but we stream its output through build logs
     let build_cancel = tokio_util::sync::CancellationToken::new();
@@ -538,7 +981,24 @@ pub async fn rebuild_pod(
 
     let inner_state: crate::state::AppState = (*state).clone();
 
-    match cli::devcontainer_up_rebuild(&project_path).await {
+    let up_result = match engine {
+        DevcontainerEngine::Cli => {
+            cli::devcontainer_up_rebuild(&cli_opts, &project_path, config_file.as_deref()).await
+        }
+        DevcontainerEngine::Native => {
+            native::up(
+                &project_path,
+                config_file.as_deref(),
+                true,
+                &id,
+                &app,
+                &inner_state,
+                build_cancel.clone(),
+            )
+            .await
+        }
+    };
+    match up_result {
         Ok(output) => {
             {
                 let mut s = state.lock().await;
@@ -550,27 +1010,41 @@ pub async fn rebuild_pod(
             let remote_user = dc_result.remote_user;
             let remote_workspace_folder = dc_result.remote_workspace_folder;
 
-            // Detect shell and container name inside the rebuilt container
-            let (detected_shell, container_name) = if let Some(ref cid) = container_id {
+            if let (DevcontainerEngine::Cli, Some(cid)) = (engine, &container_id) {
+                let target = lifecycle::HookTarget {
+                    container_id: cid,
+                    user: remote_user.as_deref(),
+                    working_dir: remote_workspace_folder.as_deref(),
+                };
+                lifecycle::run_up_hooks(&inner_state, &app, &id, &target, true).await;
+            }
+
+            // Detect shell, container name and compose project of the rebuilt container
+            let (detected_shell, container_name, compose_project) = if let Some(ref cid) = container_id {
                 let state_guard = state.lock().await;
-                if let Some(docker) = &state_guard.docker {
-                    let cname = containers::inspect_container(docker, cid)
-                        .await
-                        .ok()
-                        .and_then(|info| info.name)
+                if let Some(docker) = &state_guard.docker_for(&id) {
+                    let inspect = containers::inspect_container(docker, cid).await.ok();
+                    let cname = inspect
+                        .as_ref()
+                        .and_then(|info| info.name.clone())
                         .map(|n| n.trim_start_matches('/').to_string());
+                    let project = inspect
+                        .as_ref()
+                        .and_then(|info| info.config.as_ref())
+                        .and_then(|c| c.labels.as_ref())
+                        .and_then(compose::project_from_labels);
                     let shell = terminal::shell::detect_shell(
                         docker,
                         cid,
                         remote_user.as_deref(),
                     )
                     .await;
-                    (shell, cname)
+                    (shell, cname, project)
                 } else {
-                    ("/bin/sh".to_string(), None)
+                    ("/bin/sh".to_string(), None, None)
                 }
             } else {
-                ("/bin/sh".to_string(), None)
+                ("/bin/sh".to_string(), None, None)
             };
 
             let mut state = state.lock().await;
@@ -582,6 +1056,16 @@ pub async fn rebuild_pod(
                 pod.remote_user = remote_user;
                 pod.remote_workspace_folder = remote_workspace_folder;
                 pod.container_name = container_name;
+                pod.compose_project = compose_project;
+
+                // The fresh container matches the current config
+                if let (Some(snapshot), Ok(Some(mut cfg))) =
+                    (snapshot, PodStateConfig::load(&pod.name))
+                {
+                    cfg.config_hash = snapshot.fingerprint();
+                    cfg.config_snapshot = Some(snapshot);
+                    let _ = cfg.save();
+                }
             }
 
             let _ = app.emit(
@@ -590,74 +1074,23 @@ pub async fn rebuild_pod(
                     pod_id: id.clone(),
                     status: PodStatus::Running,
                     error_message: None,
+                    exit: None,
+                    health: None,
                 },
             );
 
-            // Start background monitoring tasks
-            let docker_clone = state.docker.clone();
-            let cid = state.pods.get(&id).and_then(|p| p.container_id.clone());
-
-            if let (Some(docker), Some(container_id)) = (docker_clone, cid) {
-                let cancel = tokio_util::sync::CancellationToken::new();
-                state.cancellation_tokens.insert(id.clone(), cancel.clone());
-                let interval = state.settings.process_scan_interval;
-
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                tokio::spawn(async move {
-                    crate::docker::stats::stream_stats(docker_c, cid_c, id_c, app_c, cancel_c)
-                        .await;
-                });
-
-                // Container log streaming
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                let state_c = inner_state.clone();
-                tokio::spawn(async move {
-                    crate::docker::logs::stream_logs(
-                        docker_c, cid_c, id_c, app_c, state_c, cancel_c, 100,
-                    )
-                    .await;
-                });
-
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                tokio::spawn(async move {
-                    crate::docker::processes::poll_processes(
-                        docker_c, cid_c, id_c, app_c, cancel_c, interval,
-                    )
-                    .await;
-                });
-
-                // Port scanning
-                let docker_c = docker.clone();
-                let cid_c = container_id.clone();
-                let id_c = id.clone();
-                let app_c = app.clone();
-                let cancel_c = cancel.clone();
-                let port_interval = state.settings.ports_scan_interval;
-                let port_overrides: std::collections::HashMap<String, String> = state
-                    .settings
-                    .port_protocols
-                    .iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .collect();
-                tokio::spawn(async move {
-                    crate::network::scan::poll_ports(
-                        docker_c, cid_c, id_c, app_c, cancel_c, port_interval, port_overrides,
-                    )
-                    .await;
-                });
+            // Pick up compose sidecars, then start background monitoring tasks
+            drop(state);
+            compose::refresh_services(&inner_state, &app, &id).await;
+            {
+                let mut s = inner_state.lock().await;
+                crate::docker::monitor::start_monitoring(&mut s, &inner_state, &app, &id);
+                // Re-check drift against the new snapshot and follow any new files
+                drift::watch(&mut s, &inner_state, &app, &id);
             }
+
+            // Expose forwardPorts declared in devcontainer.json
+            crate::devcontainer::ports::forward_declared_ports(&inner_state, &app, &id).await;
             Ok(())
         }
         Err(e) => {
@@ -676,6 +1109,8 @@ pub async fn rebuild_pod(
                         pod_id: id.clone(),
                         status: PodStatus::Error,
                         error_message: Some(e.to_string()),
+                        exit: None,
+                        health: None,
                     },
                 );
             }
@@ -690,7 +1125,7 @@ pub async fn remove_pod(
     id: String,
     remove_volumes: bool,
 ) -> Result<(), String> {
-    let (container_id, pod_name, pod_lock) = {
+    let (container_id, pod_name, services, pod_lock) = {
         let mut state = state.lock().await;
         if !state.pods.contains_key(&id) {
             return Err(format!("Pod not found: {}", id));
@@ -700,6 +1135,11 @@ pub async fn remove_pod(
         if let Some(cancel) = state.cancellation_tokens.remove(&id) {
             cancel.cancel();
         }
+        state.config_watchers.remove(&id);
+        state.git_watchers.remove(&id);
+        state.terminal_sessions.retain(|_, session| session.pod_id != id);
+        restart::cancel_pending(&mut state, &id);
+        state.restart_trackers.remove(&id);
 
         // Cancel all port proxies for this pod
         let proxy_keys: Vec<(String, u16)> = state
@@ -716,22 +1156,30 @@ pub async fn remove_pod(
 
         let container_id = state.pods.get(&id).unwrap().container_id.clone();
         let pod_name = state.pods.get(&id).unwrap().name.clone();
+        let services = state.pods.get(&id).unwrap().services.clone();
         let lock = state.get_or_create_pod_lock(&id);
         (
             container_id,
             pod_name,
+            services,
             lock,
         )
     };
 
     let _guard = pod_lock.lock().await;
 
-    // Remove Docker container if exists
-    if let Some(cid) = &container_id {
-        let state_guard = state.lock().await;
-        if let Some(docker) = &state_guard.docker {
+    // Remove Docker container and compose sidecars if they exist
+    let docker = state.lock().await.docker_for(&id);
+    if let Some(docker) = &docker {
+        if let Some(cid) = &container_id {
             let _ = containers::remove_container(docker, cid, remove_volumes).await;
         }
+        compose::remove_services(docker, &services, remove_volumes).await;
+        for snapshot in PodSnapshot::list(&pod_name).unwrap_or_default() {
+            if let Err(e) = snapshots::remove_image(docker, &snapshot.image).await {
+                tracing::warn!("Failed to remove snapshot {}: {}", snapshot.image, e);
+            }
+        }
     }
 
     // Remove from state
@@ -739,23 +1187,35 @@ pub async fn remove_pod(
     state.pods.remove(&id);
     state.pod_locks.remove(&id);
 
-    // Remove config file
+    // Remove config file, snapshot records and group memberships
     let _ = PodStateConfig::delete(&pod_name);
+    let _ = PodSnapshot::delete_all(&pod_name);
+    if let Err(e) = PodGroup::forget_pod(&pod_name) {
+        tracing::warn!("Failed to remove pod {} from its groups: {}", pod_name, e);
+    }
 
     Ok(())
 }
 
-#[tauri::command]
-pub async fn open_terminal(
-    state: State<'_, AppState>,
-    id: String,
-) -> Result<(), String> {
+/// Who, where and with which shell a terminal attaches to a pod's container.
+struct AttachTarget {
+    container_id: String,
+    shell: String,
+    user: Option<String>,
+    working_dir: Option<String>,
+    /// Emulator from the pod's or the global settings; empty to detect one
+    terminal: String,
+    /// Program and flags for `exec` on the pod's endpoint
+    exec_cli: Vec<String>,
+}
+
+async fn attach_target(state: &AppState, id: &str) -> Result<AttachTarget, String> {
     // Extract pod info while holding the lock
-    let (container_id, pod_name, default_shell, global_terminal, remote_user, remote_workspace_folder, project_path) = {
+    let (container_id, pod_name, default_shell, global_terminal, remote_user, remote_workspace_folder, project_path, exec_cli) = {
         let state = state.lock().await;
         let pod = state
             .pods
-            .get(&id)
+            .get(id)
             .ok_or_else(|| format!("Pod not found: {}", id))?;
 
         let cid = pod
@@ -771,6 +1231,7 @@ pub async fn open_terminal(
             pod.remote_user.clone(),
             pod.remote_workspace_folder.clone(),
             pod.project_path.clone(),
+            state.runtime_for(id).exec_cli(state.endpoint_for(id)),
         )
     };
 
@@ -793,7 +1254,7 @@ pub async fn open_terminal(
     } else {
         // Try to detect via Docker inspect Config.User
         let state_guard = state.lock().await;
-        if let Some(docker) = &state_guard.docker {
+        if let Some(docker) = &state_guard.docker_for(id) {
             let inspect_user = containers::inspect_container(docker, &container_id)
                 .await
                 .ok()
@@ -835,7 +1296,7 @@ pub async fn open_terminal(
             default_shell
         } else {
             let state_guard = state.lock().await;
-            if let Some(docker) = &state_guard.docker {
+            if let Some(docker) = &state_guard.docker_for(id) {
                 terminal::shell::detect_shell(docker, &container_id, effective_user.as_deref()).await
             } else {
                 "/bin/sh".to_string()
@@ -845,7 +1306,7 @@ pub async fn open_terminal(
         default_shell
     } else {
         let state_guard = state.lock().await;
-        if let Some(docker) = &state_guard.docker {
+        if let Some(docker) = &state_guard.docker_for(id) {
             terminal::shell::detect_shell(docker, &container_id, effective_user.as_deref()).await
         } else {
             "/bin/sh".to_string()
@@ -853,136 +1314,221 @@ pub async fn open_terminal(
     };
 
     // Determine terminal: pod config override > global settings > auto-detect
-    let terminal_override = pod_config
+    let terminal = pod_config
         .as_ref()
-        .map(|c| c.terminal_override.as_str())
-        .unwrap_or("");
-    let effective_override = if !terminal_override.is_empty() {
-        terminal_override
-    } else {
-        &global_terminal
-    };
+        .map(|c| c.terminal_override.clone())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(global_terminal);
+
+    Ok(AttachTarget {
+        container_id,
+        shell,
+        user: effective_user,
+        working_dir: effective_workdir,
+        terminal,
+        exec_cli,
+    })
+}
 
-    let terminal_name = terminal::detect_terminal(effective_override)
+/// Open the pod's shell in an external terminal emulator.
+#[tauri::command]
+pub async fn open_terminal(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<(), String> {
+    let target = attach_target(&state, &id).await?;
+    let terminal_name = terminal::detect_terminal(&target.terminal)
         .map_err(|e| e.to_string())?;
 
     terminal::launch_terminal(
         &terminal_name,
-        &container_id,
-        &shell,
-        effective_user.as_deref(),
-        effective_workdir.as_deref(),
+        &target.exec_cli,
+        &target.container_id,
+        &target.shell,
+        target.user.as_deref(),
+        target.working_dir.as_deref(),
     )
     .map_err(|e| e.to_string())?;
 
+    run_post_attach(&state, app, id, target);
     Ok(())
 }
 
+/// Open the pod's shell in a terminal session inside Nook, sized `cols` by
+/// `rows`. Its output comes as `terminal-output` events and its end as
+/// `terminal-exit`. Returns the session's id.
 #[tauri::command]
-pub async fn expose_port(
+pub async fn open_terminal_session(
     state: State<'_, AppState>,
-    pod_id: String,
-    container_port: u16,
-    host_port: u16,
-) -> Result<ExposedPort, String> {
-    // Get container IP via Docker inspect
-    let container_ip = {
-        let state_guard = state.lock().await;
-        let container_id = state_guard
-            .pods
-            .get(&pod_id)
-            .and_then(|p| p.container_id.clone())
-            .ok_or_else(|| "No container ID for pod".to_string())?;
-        let docker = state_guard
-            .docker
-            .as_ref()
-            .ok_or_else(|| "Docker not connected".to_string())?;
-        let info = containers::inspect_container(docker, &container_id)
-            .await
-            .map_err(|e| e.to_string())?;
-        let ns = info.network_settings;
-        let direct_ip = ns
-            .as_ref()
-            .and_then(|n| n.ip_address.as_ref())
-            .filter(|ip| !ip.is_empty())
-            .cloned();
-        let bridge_ip = ns
-            .as_ref()
-            .and_then(|n| n.networks.as_ref())
-            .and_then(|nets| nets.values().next())
-            .and_then(|net| net.ip_address.clone())
-            .filter(|ip| !ip.is_empty());
-        direct_ip
-            .or(bridge_ip)
-            .ok_or_else(|| "Could not determine container IP".to_string())?
-    };
-
-    // Try to start the port proxy
-    let cancel = tokio_util::sync::CancellationToken::new();
-    let proxy_cancel = cancel.clone();
-    let proxy_ip = container_ip.clone();
-
-    // Test bind first to detect busy port without spawning
-    let status = match std::net::TcpListener::bind(format!("0.0.0.0:{}", host_port)) {
-        Ok(_listener) => {
-            // Port is free, drop the test listener and spawn the async proxy
-            drop(_listener);
-            let cancel_c = proxy_cancel.clone();
+    app: AppHandle,
+    id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<String, String> {
+    let target = attach_target(&state, &id).await?;
+    let docker = state
+        .lock()
+        .await
+        .docker_for(&id)
+        .ok_or_else(|| "Docker not connected".to_string())?;
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let output_app = app.clone();
+    let output_id = session_id.clone();
+    let exit_app = app.clone();
+    let exit_id = session_id.clone();
+    let exit_state: AppState = (*state).clone();
+    let mut session = TerminalSession::start(
+        &docker,
+        &id,
+        &ExecTarget {
+            container_id: &target.container_id,
+            shell: &target.shell,
+            user: target.user.as_deref(),
+            working_dir: target.working_dir.as_deref(),
+        },
+        (cols, rows),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    tracing::info!("Opened terminal session {} in pod {}", session_id, id);
+
+    // Run under the lock it's inserted with, so a shell that exits right
+    // away is only removed once it's there
+    let mut state_guard = state.lock().await;
+    session.run(
+        move |data| {
+            let _ = output_app.emit(
+                "terminal-output",
+                &TerminalOutput {
+                    session_id: output_id.clone(),
+                    data,
+                },
+            );
+        },
+        move |exit_code| {
             tokio::spawn(async move {
-                if let Err(e) =
-                    crate::network::expose::start_port_proxy(host_port, proxy_ip, container_port, cancel_c)
-                        .await
-                {
-                    tracing::error!("Port proxy error: {}", e);
-                }
+                exit_state.lock().await.terminal_sessions.remove(&exit_id);
+                let _ = exit_app.emit(
+                    "terminal-exit",
+                    &TerminalExit {
+                        session_id: exit_id,
+                        exit_code,
+                    },
+                );
             });
-            crate::types::PortStatus::Active
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
-            tracing::warn!("Host port {} is busy", host_port);
-            crate::types::PortStatus::HostPortBusy
-        }
-        Err(e) => {
-            return Err(format!("Failed to bind port {}: {}", host_port, e));
-        }
-    };
-
-    let mut state = state.lock().await;
-    let pod = state
-        .pods
-        .get_mut(&pod_id)
-        .ok_or_else(|| format!("Pod not found: {}", pod_id))?;
+        },
+    );
+    state_guard
+        .terminal_sessions
+        .insert(session_id.clone(), session);
+    drop(state_guard);
+
+    run_post_attach(&state, app, id, target);
+    Ok(session_id)
+}
 
-    // Determine protocol from detected ports
-    let protocol = pod
-        .detected_ports
-        .iter()
-        .find(|p| p.container_port == container_port)
-        .map(|p| p.protocol.clone())
-        .unwrap_or_default();
+/// Type into a terminal session.
+#[tauri::command]
+pub async fn write_terminal(
+    state: State<'_, AppState>,
+    session_id: String,
+    data: String,
+) -> Result<(), String> {
+    let state = state.lock().await;
+    let session = state
+        .terminal_sessions
+        .get(&session_id)
+        .ok_or_else(|| format!("Terminal session not found: {}", session_id))?;
+    session.write(data.into_bytes()).map_err(|e| e.to_string())
+}
 
-    let exposed = ExposedPort {
-        container_port,
-        host_port,
-        protocol,
-        status,
-        auto_expose: true,
+#[tauri::command]
+pub async fn resize_terminal(
+    state: State<'_, AppState>,
+    session_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    let (docker, exec_id) = {
+        let state = state.lock().await;
+        let session = state
+            .terminal_sessions
+            .get(&session_id)
+            .ok_or_else(|| format!("Terminal session not found: {}", session_id))?;
+        let docker = state
+            .docker_for(&session.pod_id)
+            .ok_or_else(|| "Docker not connected".to_string())?;
+        (docker, session.exec_id.clone())
     };
+    terminal::session::resize(&docker, &exec_id, cols, rows)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Remove from detected
-    pod.detected_ports
-        .retain(|p| p.container_port != container_port);
-
-    pod.exposed_ports.push(exposed.clone());
-
-    // Store the cancellation token for this proxy
-    if exposed.status == crate::types::PortStatus::Active {
-        state
-            .port_proxy_tokens
-            .insert((pod_id.clone(), container_port), cancel);
+/// End a terminal session. Its shell gets end of input, and
+/// `terminal-exit` follows once it is gone.
+#[tauri::command]
+pub async fn close_terminal(state: State<'_, AppState>, session_id: String) -> Result<(), String> {
+    if let Some(session) = state.lock().await.terminal_sessions.remove(&session_id) {
+        session.close();
     }
+    Ok(())
+}
 
-    Ok(exposed)
+/// Run postAttachCommand in the background, as an editor does on attach
+fn run_post_attach(state: &AppState, app: AppHandle, id: String, target: AttachTarget) {
+    let inner_state = state.clone();
+    tokio::spawn(async move {
+        let Some(docker) = inner_state.lock().await.docker_for(&id) else {
+            return;
+        };
+        let hooks = match lifecycle::container_hooks(&docker, &target.container_id).await {
+            Ok(hooks) => hooks,
+            Err(e) => {
+                tracing::warn!("Failed to read lifecycle hooks for pod {}: {}", id, e);
+                return;
+            }
+        };
+        let hook_target = lifecycle::HookTarget {
+            container_id: &target.container_id,
+            user: target.user.as_deref(),
+            working_dir: target.working_dir.as_deref(),
+        };
+        lifecycle::run_hook(
+            &inner_state,
+            &app,
+            &id,
+            &hook_target,
+            LifecycleHook::PostAttach,
+            hooks.commands(LifecycleHook::PostAttach),
+        )
+        .await;
+    });
+}
+
+#[tauri::command]
+pub async fn expose_port(
+    state: State<'_, AppState>,
+    pod_id: String,
+    container_port: u16,
+    host_port: u16,
+    service: Option<String>,
+) -> Result<ExposedPort, String> {
+    crate::network::expose::expose_pod_port(
+        &state,
+        &pod_id,
+        crate::network::expose::ExposeTarget {
+            container_port,
+            host_port,
+            service,
+            protocol: None,
+            label: None,
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -990,6 +1536,7 @@ pub async fn unexpose_port(
     state: State<'_, AppState>,
     pod_id: String,
     container_port: u16,
+    service: Option<String>,
 ) -> Result<(), String> {
     let mut state = state.lock().await;
     let pod = state
@@ -997,12 +1544,20 @@ pub async fn unexpose_port(
         .get_mut(&pod_id)
         .ok_or_else(|| format!("Pod not found: {}", pod_id))?;
 
+    let host_ports: Vec<u16> = pod
+        .exposed_ports
+        .iter()
+        .filter(|p| p.container_port == container_port && p.service == service)
+        .map(|p| p.host_port)
+        .collect();
     pod.exposed_ports
-        .retain(|p| p.container_port != container_port);
+        .retain(|p| !(p.container_port == container_port && p.service == service));
 
     // Cancel the port proxy
-    if let Some(cancel) = state.port_proxy_tokens.remove(&(pod_id.clone(), container_port)) {
-        cancel.cancel();
+    for host_port in host_ports {
+        if let Some(cancel) = state.port_proxy_tokens.remove(&(pod_id.clone(), host_port)) {
+            cancel.cancel();
+        }
     }
 
     Ok(())
@@ -1013,6 +1568,7 @@ pub async fn ignore_port(
     state: State<'_, AppState>,
     pod_id: String,
     container_port: u16,
+    service: Option<String>,
 ) -> Result<(), String> {
     let mut state = state.lock().await;
     let pod = state
@@ -1021,7 +1577,7 @@ pub async fn ignore_port(
         .ok_or_else(|| format!("Pod not found: {}", pod_id))?;
 
     pod.detected_ports
-        .retain(|p| p.container_port != container_port);
+        .retain(|p| !(p.container_port == container_port && p.service == service));
 
     Ok(())
 }
@@ -1037,37 +1593,46 @@ pub async fn get_settings(
 #[tauri::command]
 pub async fn save_settings(
     state: State<'_, AppState>,
+    app: AppHandle,
     settings: crate::config::settings::Settings,
 ) -> Result<(), String> {
+    if settings.launch_at_login != state.lock().await.settings.launch_at_login {
+        crate::autostart::set_launch_at_login(settings.launch_at_login)
+            .map_err(|e| e.to_string())?;
+    }
     settings.save().map_err(|e| e.to_string())?;
-    let mut state = state.lock().await;
-    state.settings = settings;
+    state.lock().await.settings = settings;
+    // The Docker socket or the set of endpoints may have changed
+    docker::connection::apply_endpoints(&state, &app).await;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn check_dependencies() -> Result<Vec<DependencyCheck>, String> {
+pub async fn check_dependencies(
+    state: State<'_, AppState>,
+) -> Result<Vec<DependencyCheck>, String> {
+    let (cli_opts, endpoint) = {
+        let state = state.lock().await;
+        (
+            cli::CliOptions::from_settings(&state.settings),
+            endpoint::default_endpoint(&state.settings, &endpoint::docker_config_dir()),
+        )
+    };
     let mut checks = Vec::new();
 
-    // Check Docker
-    let docker_ok = bollard::Docker::connect_with_local_defaults().is_ok();
-    let docker_ping = if docker_ok {
-        if let Ok(docker) = bollard::Docker::connect_with_local_defaults() {
-            docker.ping().await.is_ok()
-        } else {
-            false
-        }
-    } else {
-        false
+    // Check Docker (the default endpoint, honoring the configured socket)
+    let docker_ping = match endpoint.connect().await {
+        Ok(connection) => connection.docker.ping().await.is_ok(),
+        Err(_) => false,
     };
 
     checks.push(DependencyCheck {
         name: "Docker daemon".to_string(),
         satisfied: docker_ping,
         details: if docker_ping {
-            "Running".to_string()
+            format!("Running ({})", endpoint.host)
         } else {
-            "Not running or not accessible".to_string()
+            format!("Not running or not accessible at {}", endpoint.host)
         },
         fix_hint: if docker_ping {
             None
@@ -1076,20 +1641,37 @@ pub async fn check_dependencies() -> Result<Vec<DependencyCheck>, String> {
         },
     });
 
-    // Check devcontainer CLI
-    let devcontainer_ok = cli::is_devcontainer_available().await;
+    // Check devcontainer CLI (honoring the configured path)
+    let configured_missing =
+        cli_opts.is_explicit_path() && !std::path::Path::new(&cli_opts.program).is_file();
+    let devcontainer_version = if configured_missing {
+        None
+    } else {
+        cli::devcontainer_version(&cli_opts).await.ok()
+    };
+    let devcontainer_ok = devcontainer_version.is_some();
     checks.push(DependencyCheck {
         name: "devcontainer CLI".to_string(),
         satisfied: devcontainer_ok,
-        details: if devcontainer_ok {
-            "Installed".to_string()
-        } else {
-            "Not found".to_string()
+        details: match devcontainer_version {
+            Some(version) if version.is_empty() => "Installed".to_string(),
+            Some(version) => format!("Installed ({})", version),
+            None if configured_missing => format!("Not found at {}", cli_opts.program),
+            None if cli_opts.is_explicit_path() => {
+                format!("Failed to run {}", cli_opts.program)
+            }
+            None => "Not found".to_string(),
         },
         fix_hint: if devcontainer_ok {
             None
+        } else if cli_opts.is_explicit_path() {
+            Some("Check the devcontainer CLI path in Settings".to_string())
         } else {
-            Some("Install: npm install -g @devcontainers/cli".to_string())
+            Some(
+                "Install: npm install -g @devcontainers/cli, set its path in Settings, \
+                 or switch pods to the native engine"
+                    .to_string(),
+            )
         },
     });
 
@@ -1130,13 +1712,27 @@ pub async fn get_default_settings() -> Result<crate::config::settings::Settings,
 }
 
 #[tauri::command]
-pub async fn check_docker_health(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn check_docker_health(
+    state: State<'_, AppState>,
+) -> Result<Vec<DockerConnection>, String> {
     let state = state.lock().await;
-    if let Some(docker) = &state.docker {
-        Ok(docker.ping().await.is_ok())
-    } else {
-        Ok(false)
-    }
+    let mut connections: Vec<DockerConnection> = state
+        .endpoints
+        .values()
+        .map(|e| e.connection.clone())
+        .collect();
+    connections.sort_by_key(|c| (c.endpoint != endpoint::DEFAULT_ENDPOINT, c.endpoint.clone()));
+    Ok(connections)
+}
+
+/// Docker endpoints that can be enabled in Settings: the default one, Docker
+/// contexts and local rootless Docker or Podman sockets.
+#[tauri::command]
+pub async fn list_docker_endpoints(
+    state: State<'_, AppState>,
+) -> Result<Vec<DockerEndpoint>, String> {
+    let settings = state.lock().await.settings.clone();
+    Ok(endpoint::discover(&settings, &endpoint::docker_config_dir()))
 }
 
 #[tauri::command]
@@ -1158,6 +1754,332 @@ pub async fn get_pod_logs(
     }
 }
 
+/// Exits of the pod's container seen by Nook since it started, oldest first.
+#[tauri::command]
+pub async fn get_restart_history(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<RestartRecord>, String> {
+    let state = state.lock().await;
+    Ok(state
+        .restart_trackers
+        .get(&id)
+        .map(|t| t.history())
+        .unwrap_or_default())
+}
+
+/// The pod's schedules with their next run and recent runs.
+#[tauri::command]
+pub async fn get_pod_schedules(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<PodScheduleStatus>, String> {
+    let state = state.lock().await;
+    let name = state
+        .pods
+        .get(&id)
+        .map(|p| p.name.clone())
+        .ok_or_else(|| format!("Pod not found: {}", id))?;
+    let schedules = PodStateConfig::load(&name)
+        .map_err(|e| e.to_string())?
+        .map(|c| c.schedules)
+        .unwrap_or_default();
+    let now = chrono::Local::now();
+    Ok(schedules
+        .into_iter()
+        .map(|schedule| PodScheduleStatus {
+            next_run: scheduler::next_run(&schedule, &now).map(|t| t.timestamp()),
+            history: state.scheduler.history(&schedule.id),
+            schedule,
+        })
+        .collect())
+}
+
+/// The pod's name, container and Docker client, and its pod lock.
+async fn pod_container(
+    state: &AppState,
+    id: &str,
+) -> Result<(String, String, bollard::Docker, std::sync::Arc<tokio::sync::Mutex<()>>), String> {
+    let mut state = state.lock().await;
+    let pod = state
+        .pods
+        .get(id)
+        .ok_or_else(|| format!("Pod not found: {}", id))?;
+    let name = pod.name.clone();
+    let container_id = pod
+        .container_id
+        .clone()
+        .ok_or_else(|| "Pod has no container yet".to_string())?;
+    let docker = state
+        .docker_for(id)
+        .ok_or_else(|| "Docker not connected".to_string())?;
+    Ok((name, container_id, docker, state.get_or_create_pod_lock(id)))
+}
+
+/// Commit the pod's container to a snapshot image, dropping the oldest
+/// snapshots beyond the retention setting.
+#[tauri::command]
+pub async fn snapshot_pod(
+    state: State<'_, AppState>,
+    id: String,
+    note: Option<String>,
+) -> Result<PodSnapshot, String> {
+    let (name, container_id, docker, pod_lock) = pod_container(&state, &id).await?;
+    let keep = state.lock().await.settings.snapshot_retention as usize;
+    let _guard = pod_lock.lock().await;
+
+    let note = note.unwrap_or_default();
+    let snapshot = snapshots::commit(&docker, &container_id, &name, note.trim())
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!("Snapshot {} of pod {}", snapshot.image, name);
+    let pruned = snapshot.add(&name, keep).map_err(|e| e.to_string())?;
+    for old in pruned {
+        if let Err(e) = snapshots::remove_image(&docker, &old.image).await {
+            tracing::warn!("Failed to remove snapshot {}: {}", old.image, e);
+        }
+    }
+    Ok(snapshot)
+}
+
+/// The pod's snapshots, newest first.
+#[tauri::command]
+pub async fn list_snapshots(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<PodSnapshot>, String> {
+    let name = {
+        let state = state.lock().await;
+        state
+            .pods
+            .get(&id)
+            .map(|p| p.name.clone())
+            .ok_or_else(|| format!("Pod not found: {}", id))?
+    };
+    PodSnapshot::list(&name).map_err(|e| e.to_string())
+}
+
+/// Recreate the pod's container from a snapshot with the same labels and
+/// mounts. A running pod is stopped first and started again after.
+#[tauri::command]
+pub async fn restore_snapshot(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+    snapshot_id: String,
+) -> Result<(), String> {
+    let (name, status) = {
+        let state = state.lock().await;
+        state
+            .pods
+            .get(&id)
+            .map(|p| (p.name.clone(), p.status.clone()))
+            .ok_or_else(|| format!("Pod not found: {}", id))?
+    };
+    let snapshot = PodSnapshot::load(&name, &snapshot_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Snapshot not found: {}", snapshot_id))?;
+
+    let was_running = matches!(status, PodStatus::Running | PodStatus::Starting);
+    if was_running {
+        stop_single_pod(state.clone(), app.clone(), id.clone()).await?;
+    }
+
+    {
+        let (_, container_id, docker, pod_lock) = pod_container(&state, &id).await?;
+        let _guard = pod_lock.lock().await;
+        let new_id = snapshots::recreate(&docker, &container_id, &snapshot.image)
+            .await
+            .map_err(|e| e.to_string())?;
+        tracing::info!("Restored pod {} from snapshot {}", name, snapshot.image);
+        let mut state = state.lock().await;
+        if let Some(pod) = state.pods.get_mut(&id) {
+            pod.container_id = Some(new_id);
+        }
+    }
+
+    if was_running {
+        start_single_pod(state, app, id).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_snapshot(
+    state: State<'_, AppState>,
+    id: String,
+    snapshot_id: String,
+) -> Result<(), String> {
+    let (name, docker) = {
+        let state = state.lock().await;
+        let name = state
+            .pods
+            .get(&id)
+            .map(|p| p.name.clone())
+            .ok_or_else(|| format!("Pod not found: {}", id))?;
+        (name, state.docker_for(&id))
+    };
+    if let Some(snapshot) = PodSnapshot::load(&name, &snapshot_id).map_err(|e| e.to_string())? {
+        let docker = docker.ok_or_else(|| "Docker not connected".to_string())?;
+        snapshots::remove_image(&docker, &snapshot.image)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    PodSnapshot::remove(&name, &snapshot_id).map_err(|e| e.to_string())
+}
+
+/// The volume and bind mounts of the pod's container.
+#[tauri::command]
+pub async fn list_pod_volumes(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<PodMount>, String> {
+    let (_, container_id, docker, _) = pod_container(&state, &id).await?;
+    volumes::list_mounts(&docker, &container_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Archive the pod's named volumes under the backup directory, emitting
+/// `volume-transfer-progress`.
+#[tauri::command]
+pub async fn backup_pod_volumes(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+) -> Result<VolumeBackup, String> {
+    let (name, container_id, docker, pod_lock) = pod_container(&state, &id).await?;
+    let root = state
+        .lock()
+        .await
+        .settings
+        .backups_dir()
+        .map_err(|e| e.to_string())?;
+    let _guard = pod_lock.lock().await;
+    let backup = volumes::backup(&docker, &container_id, &root, &name, &app, &id)
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        "Backed up {} volumes of pod {} as {}",
+        backup.volumes.len(),
+        name,
+        backup.id
+    );
+    Ok(backup)
+}
+
+/// Volume backups of all pods, newest first, so a fresh pod can be filled
+/// from one of a removed pod.
+#[tauri::command]
+pub async fn list_volume_backups(state: State<'_, AppState>) -> Result<Vec<VolumeBackup>, String> {
+    let root = state
+        .lock()
+        .await
+        .settings
+        .backups_dir()
+        .map_err(|e| e.to_string())?;
+    volumes::list_backups(&root).map_err(|e| e.to_string())
+}
+
+/// Restore the backup `backup_id` of the pod `pod_name` into the volumes of
+/// the pod `id`. A running pod is stopped first and started again after.
+#[tauri::command]
+pub async fn restore_pod_volumes(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    id: String,
+    pod_name: String,
+    backup_id: String,
+) -> Result<(), String> {
+    let (root, status) = {
+        let state = state.lock().await;
+        let status = state
+            .pods
+            .get(&id)
+            .map(|p| p.status.clone())
+            .ok_or_else(|| format!("Pod not found: {}", id))?;
+        (state.settings.backups_dir().map_err(|e| e.to_string())?, status)
+    };
+    let backup = volumes::load_backup(&root, &pod_name, &backup_id).map_err(|e| e.to_string())?;
+
+    let was_running = matches!(status, PodStatus::Running | PodStatus::Starting);
+    if was_running {
+        stop_single_pod(state.clone(), app.clone(), id.clone()).await?;
+    }
+    {
+        let (name, container_id, docker, pod_lock) = pod_container(&state, &id).await?;
+        let _guard = pod_lock.lock().await;
+        volumes::restore(&docker, &container_id, &root, &backup, &app, &id)
+            .await
+            .map_err(|e| e.to_string())?;
+        tracing::info!("Restored backup {} of {} into pod {}", backup.id, pod_name, name);
+    }
+    if was_running {
+        start_single_pod(state, app, id).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_volume_backup(
+    state: State<'_, AppState>,
+    pod_name: String,
+    backup_id: String,
+) -> Result<(), String> {
+    let root = state
+        .lock()
+        .await
+        .settings
+        .backups_dir()
+        .map_err(|e| e.to_string())?;
+    volumes::delete_backup(&root, &pod_name, &backup_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_groups() -> Result<Vec<PodGroup>, String> {
+    PodGroup::list_all().map_err(|e| e.to_string())
+}
+
+/// Create a group, or replace the group with the same name.
+#[tauri::command]
+pub async fn save_group(group: PodGroup) -> Result<(), String> {
+    group.save().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_group(name: String) -> Result<(), String> {
+    PodGroup::delete(&name).map_err(|e| e.to_string())
+}
+
+/// Start the group's pods after their dependencies, see `bulk::run_group`.
+#[tauri::command]
+pub async fn start_group(app: AppHandle, name: String) -> Result<Vec<GroupPodResult>, String> {
+    bulk::run_group(&app, &name, GroupAction::Start)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_group(app: AppHandle, name: String) -> Result<Vec<GroupPodResult>, String> {
+    bulk::run_group(&app, &name, GroupAction::Stop)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rebuild_group(app: AppHandle, name: String) -> Result<Vec<GroupPodResult>, String> {
+    bulk::run_group(&app, &name, GroupAction::Rebuild)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn expose_group(app: AppHandle, name: String) -> Result<Vec<GroupPodResult>, String> {
+    bulk::run_group(&app, &name, GroupAction::Expose)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn clear_pod_logs(
     state: State<'_, AppState>,
@@ -1189,6 +2111,8 @@ pub async fn cancel_build(
                 pod_id: id.clone(),
                 status: PodStatus::Stopped,
                 error_message: None,
+                exit: None,
+                health: None,
             },
         );
     }
@@ -1215,8 +2139,20 @@ pub async fn get_pod_settings(
 pub async fn save_pod_settings(
     state: State<'_, AppState>,
     id: String,
-    config: PodStateConfig,
+    mut config: PodStateConfig,
 ) -> Result<(), String> {
+    for schedule in &mut config.schedules {
+        scheduler::parse_time(&schedule.time).map_err(|e| e.to_string())?;
+        if schedule.id.is_empty() {
+            schedule.id = uuid::Uuid::new_v4().to_string();
+        }
+    }
+
+    // Refuse dependencies that would form a cycle
+    let mut configs = dependencies::load_configs().map_err(|e| e.to_string())?;
+    configs.insert(config.name.clone(), config.clone());
+    dependencies::start_order(&configs, std::slice::from_ref(&config.name))
+        .map_err(|e| e.to_string())?;
     config.save().map_err(|e| e.to_string())?;
 
     // Sync config overrides to pod runtime state
@@ -1239,6 +2175,11 @@ pub async fn save_pod_settings(
         if !config.shell.is_empty() {
             pod.default_shell = config.shell.clone();
         }
+        // A pod with a container stays on that container's endpoint until it
+        // is removed
+        if pod.container_id.is_none() {
+            pod.endpoint = config.endpoint.clone();
+        }
     }
 
     Ok(())
@@ -1261,6 +2202,26 @@ pub async fn open_in_file_manager(path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Absolute `--config` argument for pods bound to a non-default devcontainer.json.
+fn config_file_arg(project_path: &str, config_path: Option<&str>) -> Result<Option<String>, String> {
+    match config_path.filter(|c| !c.is_empty()) {
+        Some(rel) => discovery::resolve_config_path(project_path, Some(rel))
+            .map(|p| Some(p.to_string_lossy().to_string()))
+            .ok_or_else(|| format!("devcontainer config not found: {}", rel)),
+        None => Ok(None),
+    }
+}
+
+/// The engine a pod is configured to use; the devcontainer CLI if it has no
+/// saved config.
+fn pod_engine(pod_name: &str) -> DevcontainerEngine {
+    PodStateConfig::load(pod_name)
+        .ok()
+        .flatten()
+        .map(|c| c.engine)
+        .unwrap_or_default()
+}
+
 struct DevcontainerUpResult {
     container_id: Option<String>,
     remote_user: Option<String>,
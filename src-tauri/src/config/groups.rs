@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::settings::Settings;
+use crate::error::{NookError, NookResult};
+
+/// A named set of pods that are started, stopped, rebuilt and exposed
+/// together, e.g. every pod a team works on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PodGroup {
+    pub name: String,
+    /// Pod names, in the order results are reported
+    #[serde(default)]
+    pub pods: Vec<String>,
+}
+
+/// `groups.toml`, one `[[group]]` table per group.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GroupsFile {
+    #[serde(default, rename = "group")]
+    groups: Vec<PodGroup>,
+}
+
+impl GroupsFile {
+    /// Add `group`, or replace the group with the same name.
+    fn upsert(&mut self, group: PodGroup) {
+        match self.groups.iter_mut().find(|g| g.name == group.name) {
+            Some(existing) => *existing = group,
+            None => self.groups.push(group),
+        }
+        self.groups.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Drop a pod from every group it is in.
+    fn forget_pod(&mut self, pod_name: &str) -> bool {
+        let mut changed = false;
+        for group in &mut self.groups {
+            let before = group.pods.len();
+            group.pods.retain(|p| p != pod_name);
+            changed |= group.pods.len() != before;
+        }
+        changed
+    }
+}
+
+impl PodGroup {
+    fn groups_path() -> NookResult<PathBuf> {
+        Ok(Settings::config_dir()?.join("groups.toml"))
+    }
+
+    fn read_file() -> NookResult<GroupsFile> {
+        let path = Self::groups_path()?;
+        if !path.exists() {
+            return Ok(GroupsFile::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| NookError::ConfigError(format!("Failed to read groups: {}", e)))?;
+        toml::from_str(&content)
+            .map_err(|e| NookError::ConfigError(format!("Failed to parse groups: {}", e)))
+    }
+
+    fn write_file(file: &GroupsFile) -> NookResult<()> {
+        let dir = Settings::config_dir()?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| NookError::ConfigError(format!("Failed to create config dir: {}", e)))?;
+        let content = toml::to_string_pretty(file)
+            .map_err(|e| NookError::Serialization(format!("Failed to serialize groups: {}", e)))?;
+        std::fs::write(Self::groups_path()?, content)
+            .map_err(|e| NookError::ConfigError(format!("Failed to write groups: {}", e)))
+    }
+
+    pub fn list_all() -> NookResult<Vec<Self>> {
+        Ok(Self::read_file()?.groups)
+    }
+
+    pub fn load(name: &str) -> NookResult<Option<Self>> {
+        Ok(Self::list_all()?.into_iter().find(|g| g.name == name))
+    }
+
+    pub fn save(&self) -> NookResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(NookError::ConfigError(
+                "Group name must not be empty".to_string(),
+            ));
+        }
+        let mut file = Self::read_file()?;
+        file.upsert(self.clone());
+        Self::write_file(&file)
+    }
+
+    pub fn delete(name: &str) -> NookResult<()> {
+        let mut file = Self::read_file()?;
+        file.groups.retain(|g| g.name != name);
+        Self::write_file(&file)
+    }
+
+    /// Remove a deleted pod from the groups that list it.
+    pub fn forget_pod(pod_name: &str) -> NookResult<()> {
+        let mut file = Self::read_file()?;
+        if file.forget_pod(pod_name) {
+            Self::write_file(&file)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(name: &str, pods: &[&str]) -> PodGroup {
+        PodGroup {
+            name: name.to_string(),
+            pods: pods.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_groups_file() {
+        let mut file = GroupsFile::default();
+        file.upsert(group(
+            "checkout-team",
+            &["cart", "payments", "search", "infra"],
+        ));
+        file.upsert(group("billing", &["payments", "infra"]));
+        file.upsert(group("checkout-team", &["cart", "payments", "infra"]));
+
+        let serialized = toml::to_string_pretty(&file).unwrap();
+        assert!(serialized.contains("[[group]]"));
+        let parsed: GroupsFile = toml::from_str(&serialized).unwrap();
+        assert_eq!(
+            parsed.groups,
+            vec![
+                group("billing", &["payments", "infra"]),
+                group("checkout-team", &["cart", "payments", "infra"]),
+            ]
+        );
+
+        let mut parsed = parsed;
+        assert!(parsed.forget_pod("payments"));
+        assert!(!parsed.forget_pod("payments"));
+        assert_eq!(parsed.groups[0], group("billing", &["infra"]));
+    }
+}
@@ -1,2 +1,4 @@
+pub mod groups;
 pub mod pod_state;
 pub mod settings;
+pub mod snapshots;
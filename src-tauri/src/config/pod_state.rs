@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 
 use crate::config::settings::Settings;
+use crate::devcontainer::config::ConfigSnapshot;
 use crate::error::{NookError, NookResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +23,85 @@ pub struct PodNotExposeFilter {
     pub port: Option<u16>,
 }
 
+/// How a pod's devcontainer is brought up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DevcontainerEngine {
+    /// The `@devcontainers/cli` `devcontainer up` command.
+    #[default]
+    Cli,
+    /// Nook's built-in engine, talking to Docker directly. Covers image and
+    /// Dockerfile configs without features.
+    Native,
+}
+
+/// What Nook does when a pod's container exits on its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestartPolicy {
+    /// Leave the pod stopped.
+    #[default]
+    Never,
+    /// Restart after a non-zero exit or an OOM kill, up to
+    /// `restart_max_retries` times.
+    OnFailure,
+    /// Restart after every exit.
+    Always,
+}
+
+/// When a started pod counts as ready for the pods that depend on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Readiness {
+    /// The port scanner found this container port listening.
+    Port { port: u16 },
+    /// This shell command exits 0 inside the container.
+    Command { command: String },
+}
+
+impl Readiness {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Port { port } => format!("Port {}", port),
+            Self::Command { command } => format!("`{}`", command),
+        }
+    }
+}
+
+/// What a schedule does to its pod.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+    Rebuild,
+}
+
+/// A pod action repeated at a local time of day, e.g. start on weekdays at
+/// 08:45.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodSchedule {
+    /// Keys the schedule's run history; assigned when the pod settings are
+    /// saved.
+    #[serde(default)]
+    pub id: String,
+    pub action: ScheduleAction,
+    /// Local time, `HH:MM`
+    pub time: String,
+    /// Days to run on, e.g. `["Mon", "Fri"]`; empty means every day.
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Pull the base images again before a rebuild.
+    #[serde(default)]
+    pub pull: bool,
+    /// Run once on wake-up if runs were missed while the machine slept.
+    #[serde(default = "default_true")]
+    pub catch_up: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PodStateConfig {
     pub name: String,
@@ -32,6 +113,8 @@ pub struct PodStateConfig {
     #[serde(default)]
     pub auto_expose_ports: Vec<AutoExposePort>,
 
+    /// Host port each container port is exposed on, keyed by
+    /// `port_mapping_key`; ports not listed use the same number.
     #[serde(default)]
     pub port_mappings: HashMap<String, u16>,
 
@@ -55,10 +138,121 @@ pub struct PodStateConfig {
 
     #[serde(default)]
     pub remote_user: String,
+
+    /// devcontainer.json path relative to `project_path`; empty means the
+    /// default `.devcontainer/devcontainer.json` / `.devcontainer.json`.
+    #[serde(default)]
+    pub config_path: String,
+
+    #[serde(default)]
+    pub engine: DevcontainerEngine,
+
+    /// `config::fingerprint` of the devcontainer config the pod's container
+    /// was built from; a match lets `start_pod` start it directly.
+    #[serde(default)]
+    pub config_hash: String,
+
+    /// What the pod's container was built from, to summarise config drift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_snapshot: Option<ConfigSnapshot>,
+
+    /// Docker endpoint new containers are created on; empty means the
+    /// default one.
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Signal sent to stop the pod's container; empty means the global
+    /// default.
+    #[serde(default)]
+    pub stop_signal: String,
+
+    /// Seconds to wait after the stop signal before killing the container;
+    /// `None` means the global default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_timeout: Option<u64>,
+
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+
+    /// Restarts `OnFailure` attempts in a row before giving up; 0 means no
+    /// limit.
+    #[serde(default = "default_restart_max_retries")]
+    pub restart_max_retries: u32,
+
+    /// Start the pod when Nook launches.
+    #[serde(default)]
+    pub autostart: bool,
+
+    /// Minutes without CPU use, terminal sessions or proxy traffic after
+    /// which the running pod is stopped; 0 means never.
+    #[serde(default)]
+    pub idle_stop_minutes: u32,
+
+    /// Names of pods started before this one.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    /// What dependent pods wait for after starting this one; `None` means
+    /// they start as soon as it is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness: Option<Readiness>,
+
+    #[serde(default)]
+    pub schedules: Vec<PodSchedule>,
+}
+
+fn default_restart_max_retries() -> u32 {
+    5
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PodStateConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            project_path: String::new(),
+            shell: String::new(),
+            auto_expose_ports: Vec::new(),
+            port_mappings: HashMap::new(),
+            alias: String::new(),
+            not_expose_filters: Vec::new(),
+            manual_expose_ports: Vec::new(),
+            terminal_override: String::new(),
+            working_dir: String::new(),
+            bind_address: String::new(),
+            remote_user: String::new(),
+            config_path: String::new(),
+            engine: DevcontainerEngine::default(),
+            config_hash: String::new(),
+            config_snapshot: None,
+            endpoint: String::new(),
+            stop_signal: String::new(),
+            stop_timeout: None,
+            restart_policy: RestartPolicy::default(),
+            restart_max_retries: default_restart_max_retries(),
+            autostart: false,
+            idle_stop_minutes: 0,
+            depends_on: Vec::new(),
+            readiness: None,
+            schedules: Vec::new(),
+        }
+    }
 }
 
 impl PodStateConfig {
-    fn pods_dir() -> NookResult<PathBuf> {
+    /// Host port to expose `container_port` of the compose `service` on, or
+    /// of the pod's own container for `None`; see `port_mappings`.
+    pub fn host_port(&self, service: Option<&str>, container_port: u16) -> u16 {
+        self.port_mappings
+            .get(&port_mapping_key(service, container_port))
+            .copied()
+            .unwrap_or(container_port)
+    }
+
+    pub(crate) fn pods_dir() -> NookResult<PathBuf> {
         Ok(Settings::config_dir()?.join("pods"))
     }
 
@@ -95,9 +289,27 @@ impl PodStateConfig {
         Ok(())
     }
 
-    pub fn find_by_project_path(path: &str) -> NookResult<Option<Self>> {
+    /// Every pod of a project; clones of a pod live at their own path.
+    pub fn list_by_project_path(path: &str) -> NookResult<Vec<Self>> {
+        let configs = Self::list_all()?;
+        Ok(configs
+            .into_iter()
+            .filter(|c| c.project_path == path)
+            .collect())
+    }
+
+    /// Find the pod bound to a specific devcontainer.json within a project.
+    pub fn find_by_project_and_config(path: &str, config_path: &str) -> NookResult<Option<Self>> {
         let configs = Self::list_all()?;
-        Ok(configs.into_iter().find(|c| c.project_path == path))
+        Ok(configs
+            .into_iter()
+            .find(|c| c.project_path == path && c.config_path == config_path))
+    }
+
+    /// Return `base`, or `base-2`, `base-3`, ... if a pod with that name already exists.
+    pub fn unique_name(base: &str) -> NookResult<String> {
+        let taken: Vec<String> = Self::list_all()?.into_iter().map(|c| c.name).collect();
+        Ok(next_free_name(base, &taken))
     }
 
     pub fn list_all() -> NookResult<Vec<Self>> {
@@ -126,9 +338,37 @@ impl PodStateConfig {
     }
 }
 
+/// Key of a port in `port_mappings`: `service:port` for a compose service,
+/// the bare port for the pod's own container.
+pub fn port_mapping_key(service: Option<&str>, container_port: u16) -> String {
+    match service {
+        Some(service) => format!("{}:{}", service, container_port),
+        None => container_port.to_string(),
+    }
+}
+
+/// The service and container port of a `port_mappings` key.
+pub fn parse_port_mapping_key(key: &str) -> Option<(Option<String>, u16)> {
+    match key.rsplit_once(':') {
+        Some((service, port)) => Some((Some(service.to_string()), port.parse().ok()?)),
+        None => Some((None, key.parse().ok()?)),
+    }
+}
+
+fn next_free_name(base: &str, taken: &[String]) -> String {
+    if !taken.iter().any(|t| t == base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| base.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_pod_state_roundtrip() {
@@ -148,14 +388,38 @@ mod tests {
                     protocol: "postgres".to_string(),
                 },
             ],
-            port_mappings: HashMap::from([("8080".to_string(), 9090)]),
+            port_mappings: HashMap::from([
+                ("8080".to_string(), 9090),
+                ("db:5432".to_string(), 5433),
+            ]),
             alias: "My Project".to_string(),
-            not_expose_filters: Vec::new(),
-            manual_expose_ports: Vec::new(),
-            terminal_override: String::new(),
-            working_dir: String::new(),
-            bind_address: String::new(),
             remote_user: "vscode".to_string(),
+            config_path: ".devcontainer/api/devcontainer.json".to_string(),
+            engine: DevcontainerEngine::Native,
+            config_hash: "0123456789abcdef".to_string(),
+            config_snapshot: Some(ConfigSnapshot {
+                files: BTreeMap::from([("devcontainer.json".to_string(), "ab".to_string())]),
+                features: BTreeMap::new(),
+            }),
+            endpoint: "build-box".to_string(),
+            stop_signal: "SIGINT".to_string(),
+            stop_timeout: Some(60),
+            restart_policy: RestartPolicy::OnFailure,
+            restart_max_retries: 3,
+            autostart: true,
+            idle_stop_minutes: 45,
+            depends_on: vec!["infra".to_string()],
+            readiness: Some(Readiness::Port { port: 5432 }),
+            schedules: vec![PodSchedule {
+                id: "s1".to_string(),
+                action: ScheduleAction::Rebuild,
+                time: "03:00".to_string(),
+                days: vec![Weekday::Sun],
+                pull: true,
+                catch_up: false,
+                enabled: true,
+            }],
+            ..Default::default()
         };
 
         let serialized = toml::to_string_pretty(&config).unwrap();
@@ -165,8 +429,26 @@ mod tests {
         assert_eq!(deserialized.shell, "/bin/zsh");
         assert_eq!(deserialized.auto_expose_ports.len(), 2);
         assert_eq!(deserialized.port_mappings.get("8080"), Some(&9090));
+        assert_eq!(deserialized.host_port(None, 8080), 9090);
+        assert_eq!(deserialized.host_port(None, 3000), 3000);
+        assert_eq!(deserialized.host_port(Some("db"), 5432), 5433);
+        assert_eq!(deserialized.host_port(None, 5432), 5432);
+        assert_eq!(deserialized.host_port(Some("cache"), 8080), 8080);
         assert_eq!(deserialized.alias, "My Project");
         assert_eq!(deserialized.remote_user, "vscode");
+        assert_eq!(deserialized.config_path, ".devcontainer/api/devcontainer.json");
+        assert_eq!(deserialized.engine, DevcontainerEngine::Native);
+        assert_eq!(deserialized.config_snapshot, config.config_snapshot);
+        assert_eq!(deserialized.endpoint, "build-box");
+        assert_eq!(deserialized.stop_signal, "SIGINT");
+        assert_eq!(deserialized.stop_timeout, Some(60));
+        assert_eq!(deserialized.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(deserialized.restart_max_retries, 3);
+        assert!(deserialized.autostart);
+        assert_eq!(deserialized.idle_stop_minutes, 45);
+        assert_eq!(deserialized.depends_on, vec!["infra"]);
+        assert_eq!(deserialized.readiness, Some(Readiness::Port { port: 5432 }));
+        assert_eq!(deserialized.schedules, config.schedules);
     }
 
     #[test]
@@ -188,5 +470,35 @@ project_path = "/tmp/test"
         assert!(config.working_dir.is_empty());
         assert!(config.bind_address.is_empty());
         assert!(config.remote_user.is_empty());
+        assert!(config.config_path.is_empty());
+        assert_eq!(config.engine, DevcontainerEngine::Cli);
+        assert!(config.config_hash.is_empty());
+        assert!(config.config_snapshot.is_none());
+        assert!(config.stop_signal.is_empty());
+        assert!(config.stop_timeout.is_none());
+        assert_eq!(config.restart_policy, RestartPolicy::Never);
+        assert_eq!(config.restart_max_retries, 5);
+        assert!(!config.autostart);
+        assert_eq!(config.idle_stop_minutes, 0);
+        assert!(config.depends_on.is_empty());
+        assert!(config.readiness.is_none());
+        assert!(config.schedules.is_empty());
+    }
+
+    #[test]
+    fn test_next_free_name() {
+        let taken = vec!["mono".to_string(), "mono-2".to_string()];
+        assert_eq!(next_free_name("mono", &taken), "mono-3");
+        assert_eq!(next_free_name("mono-api", &taken), "mono-api");
+    }
+
+    #[test]
+    fn test_port_mapping_key() {
+        for (service, port) in [(None, 3000), (Some("db".to_string()), 5432)] {
+            let key = port_mapping_key(service.as_deref(), port);
+            assert_eq!(parse_port_mapping_key(&key), Some((service, port)));
+        }
+        assert_eq!(port_mapping_key(Some("db"), 5432), "db:5432");
+        assert_eq!(parse_port_mapping_key("db:x"), None);
     }
 }
@@ -47,17 +47,77 @@ pub struct Settings {
     #[serde(default = "default_process_scan_interval")]
     pub process_scan_interval: u64,
 
+    /// Docker host for the default endpoint: a socket path or a `unix://`,
+    /// `tcp://` or `ssh://` URL. Empty means `DOCKER_HOST`, the current
+    /// Docker context, or the first local socket found.
     #[serde(default)]
     pub docker_socket_path: String,
 
+    /// Further endpoints to connect to alongside the default one, by name
+    /// (a Docker context, `rootless` or `podman`).
+    #[serde(default)]
+    pub docker_endpoints: Vec<String>,
+
     #[serde(default)]
     pub onboarding_complete: bool,
 
+    /// Start Nook minimized when the user logs in.
+    #[serde(default)]
+    pub launch_at_login: bool,
+
     #[serde(default = "default_log_level")]
     pub log_level: String,
 
     #[serde(default)]
     pub port_action: PortAction,
+
+    /// Path to the devcontainer CLI; empty means look it up on PATH.
+    #[serde(default)]
+    pub devcontainer_cli_path: String,
+
+    /// Extra arguments appended to every devcontainer CLI invocation
+    /// (e.g. `--docker-path`, `--log-level`).
+    #[serde(default)]
+    pub devcontainer_extra_args: Vec<String>,
+
+    /// Build timeout for `devcontainer up` in seconds; 0 disables the timeout.
+    #[serde(default = "default_devcontainer_build_timeout")]
+    pub devcontainer_build_timeout: u64,
+
+    /// Signal that asks a pod's container to shut down, unless the pod
+    /// sets its own.
+    #[serde(default = "default_stop_signal")]
+    pub stop_signal: String,
+
+    /// Seconds to wait after the stop signal before killing the container.
+    #[serde(default = "default_stop_timeout")]
+    pub stop_timeout: u64,
+
+    /// Exits within `crash_loop_window` seconds after which Nook stops
+    /// restarting a pod and marks it as crash looping; 0 disables the check.
+    #[serde(default = "default_crash_loop_exits")]
+    pub crash_loop_exits: u32,
+
+    #[serde(default = "default_crash_loop_window")]
+    pub crash_loop_window: u64,
+
+    /// CPU percentage below which a pod's containers count as idle.
+    #[serde(default = "default_idle_cpu_threshold")]
+    pub idle_cpu_threshold: f64,
+
+    /// Seconds before an idle pod is stopped that Nook warns about it.
+    #[serde(default = "default_idle_warning")]
+    pub idle_warning: u64,
+
+    /// Snapshots kept per pod; older ones are removed with their images.
+    /// 0 keeps all.
+    #[serde(default = "default_snapshot_retention")]
+    pub snapshot_retention: u32,
+
+    /// Directory volume backups are written to; empty means `backups` in
+    /// Nook's data directory.
+    #[serde(default)]
+    pub backup_dir: String,
 }
 
 fn default_expose_protocols() -> Vec<String> {
@@ -100,6 +160,38 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_devcontainer_build_timeout() -> u64 {
+    600
+}
+
+fn default_stop_signal() -> String {
+    "SIGTERM".to_string()
+}
+
+fn default_stop_timeout() -> u64 {
+    10
+}
+
+fn default_crash_loop_exits() -> u32 {
+    5
+}
+
+fn default_crash_loop_window() -> u64 {
+    120
+}
+
+fn default_idle_cpu_threshold() -> f64 {
+    2.0
+}
+
+fn default_idle_warning() -> u64 {
+    300
+}
+
+fn default_snapshot_retention() -> u32 {
+    5
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -111,9 +203,22 @@ impl Default for Settings {
             ports_scan_interval: default_ports_scan_interval(),
             process_scan_interval: default_process_scan_interval(),
             docker_socket_path: String::new(),
+            docker_endpoints: Vec::new(),
             onboarding_complete: false,
+            launch_at_login: false,
             log_level: default_log_level(),
             port_action: PortAction::default(),
+            devcontainer_cli_path: String::new(),
+            devcontainer_extra_args: Vec::new(),
+            devcontainer_build_timeout: default_devcontainer_build_timeout(),
+            stop_signal: default_stop_signal(),
+            stop_timeout: default_stop_timeout(),
+            crash_loop_exits: default_crash_loop_exits(),
+            crash_loop_window: default_crash_loop_window(),
+            idle_cpu_threshold: default_idle_cpu_threshold(),
+            idle_warning: default_idle_warning(),
+            snapshot_retention: default_snapshot_retention(),
+            backup_dir: String::new(),
         }
     }
 }
@@ -126,6 +231,17 @@ impl Settings {
         Ok(base.join("nook"))
     }
 
+    /// Where volume backups go, see `backup_dir`.
+    pub fn backups_dir(&self) -> NookResult<PathBuf> {
+        if !self.backup_dir.trim().is_empty() {
+            return Ok(PathBuf::from(self.backup_dir.trim()));
+        }
+        let base = dirs::data_dir().ok_or_else(|| {
+            NookError::ConfigError("Could not determine data directory".to_string())
+        })?;
+        Ok(base.join("nook").join("backups"))
+    }
+
     pub fn settings_path() -> NookResult<PathBuf> {
         Ok(Self::config_dir()?.join("settings.toml"))
     }
@@ -215,6 +331,25 @@ onboardingComplete = false
         let settings: Settings = toml::from_str(toml_str).unwrap();
         assert_eq!(settings.log_level, "info");
         assert_eq!(settings.port_action, PortAction::Prompt);
+        assert!(settings.devcontainer_cli_path.is_empty());
+        assert!(settings.devcontainer_extra_args.is_empty());
+        assert_eq!(settings.devcontainer_build_timeout, 600);
+    }
+
+    #[test]
+    fn test_devcontainer_cli_settings() {
+        let toml_str = r#"
+devcontainerCliPath = "~/.nvm/versions/node/v20.11.0/bin/devcontainer"
+devcontainerExtraArgs = ["--docker-path", "podman", "--log-level", "debug"]
+devcontainerBuildTimeout = 0
+"#;
+        let settings: Settings = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            settings.devcontainer_cli_path,
+            "~/.nvm/versions/node/v20.11.0/bin/devcontainer"
+        );
+        assert_eq!(settings.devcontainer_extra_args.len(), 4);
+        assert_eq!(settings.devcontainer_build_timeout, 0);
     }
 
     #[test]
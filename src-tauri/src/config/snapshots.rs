@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::pod_state::PodStateConfig;
+use crate::error::{NookError, NookResult};
+
+/// An image committed from a pod's container, to go back to after a risky
+/// change. Named volumes aren't part of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodSnapshot {
+    pub id: String,
+    /// Image reference, e.g. `nook-snapshot/api:20240503-084500123`
+    pub image: String,
+    /// Unix seconds
+    pub created_at: i64,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// `pods/snapshots/<pod>.toml`, one `[[snapshot]]` table per snapshot,
+/// oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnapshotsFile {
+    #[serde(default, rename = "snapshot")]
+    snapshots: Vec<PodSnapshot>,
+}
+
+impl SnapshotsFile {
+    fn add(&mut self, snapshot: PodSnapshot) {
+        self.snapshots.retain(|s| s.id != snapshot.id);
+        self.snapshots.push(snapshot);
+        self.snapshots.sort_by_key(|s| s.created_at);
+    }
+
+    /// Drop all but the newest `keep` snapshots and return the dropped ones;
+    /// 0 keeps all.
+    fn prune(&mut self, keep: usize) -> Vec<PodSnapshot> {
+        if keep == 0 || self.snapshots.len() <= keep {
+            return Vec::new();
+        }
+        let excess = self.snapshots.len() - keep;
+        self.snapshots.drain(..excess).collect()
+    }
+}
+
+impl PodSnapshot {
+    fn snapshots_path(pod_name: &str) -> NookResult<PathBuf> {
+        Ok(PodStateConfig::pods_dir()?
+            .join("snapshots")
+            .join(format!("{}.toml", pod_name)))
+    }
+
+    fn read_file(pod_name: &str) -> NookResult<SnapshotsFile> {
+        let path = Self::snapshots_path(pod_name)?;
+        if !path.exists() {
+            return Ok(SnapshotsFile::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| NookError::ConfigError(format!("Failed to read snapshots: {}", e)))?;
+        toml::from_str(&content)
+            .map_err(|e| NookError::ConfigError(format!("Failed to parse snapshots: {}", e)))
+    }
+
+    fn write_file(pod_name: &str, file: &SnapshotsFile) -> NookResult<()> {
+        let path = Self::snapshots_path(pod_name)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                NookError::ConfigError(format!("Failed to create snapshots dir: {}", e))
+            })?;
+        }
+        let content = toml::to_string_pretty(file).map_err(|e| {
+            NookError::Serialization(format!("Failed to serialize snapshots: {}", e))
+        })?;
+        std::fs::write(&path, content)
+            .map_err(|e| NookError::ConfigError(format!("Failed to write snapshots: {}", e)))
+    }
+
+    /// The pod's snapshots, newest first.
+    pub fn list(pod_name: &str) -> NookResult<Vec<Self>> {
+        let mut snapshots = Self::read_file(pod_name)?.snapshots;
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    pub fn load(pod_name: &str, id: &str) -> NookResult<Option<Self>> {
+        Ok(Self::read_file(pod_name)?
+            .snapshots
+            .into_iter()
+            .find(|s| s.id == id))
+    }
+
+    /// Record a new snapshot of the pod, keeping the newest `keep`. Returns
+    /// the snapshots that no longer fit, whose images are for the caller to
+    /// remove.
+    pub fn add(&self, pod_name: &str, keep: usize) -> NookResult<Vec<Self>> {
+        let mut file = Self::read_file(pod_name)?;
+        file.add(self.clone());
+        let pruned = file.prune(keep);
+        Self::write_file(pod_name, &file)?;
+        Ok(pruned)
+    }
+
+    pub fn remove(pod_name: &str, id: &str) -> NookResult<()> {
+        let mut file = Self::read_file(pod_name)?;
+        file.snapshots.retain(|s| s.id != id);
+        Self::write_file(pod_name, &file)
+    }
+
+    /// Forget all of a deleted pod's snapshots.
+    pub fn delete_all(pod_name: &str) -> NookResult<()> {
+        let path = Self::snapshots_path(pod_name)?;
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| {
+                NookError::ConfigError(format!("Failed to delete snapshots: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(id: &str, created_at: i64) -> PodSnapshot {
+        PodSnapshot {
+            id: id.to_string(),
+            image: format!("nook-snapshot/api:{}", id),
+            created_at,
+            note: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshots_file() {
+        let mut file = SnapshotsFile::default();
+        file.add(snapshot("b", 20));
+        file.add(snapshot("a", 10));
+        file.add(snapshot("c", 30));
+        assert!(file.prune(0).is_empty());
+        assert!(file.prune(3).is_empty());
+
+        let serialized = toml::to_string_pretty(&file).unwrap();
+        assert!(serialized.contains("[[snapshot]]"));
+        let mut parsed: SnapshotsFile = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed.snapshots, file.snapshots);
+
+        // The oldest go first
+        assert_eq!(parsed.prune(1), vec![snapshot("a", 10), snapshot("b", 20)]);
+        assert_eq!(parsed.snapshots, vec![snapshot("c", 30)]);
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::StreamExt;
+
+use crate::config::pod_state::{PodStateConfig, Readiness};
+use crate::error::{NookError, NookResult};
+use crate::state::AppState;
+
+/// How long a dependency may take to pass its readiness check.
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Saved pod configs by pod name.
+pub fn load_configs() -> NookResult<HashMap<String, PodStateConfig>> {
+    Ok(PodStateConfig::list_all()?
+        .into_iter()
+        .map(|c| (c.name.clone(), c))
+        .collect())
+}
+
+/// Pod names in the order they have to start to bring up `roots`: every
+/// pod after all of its dependencies, each name once. Names without a
+/// config are taken to have no dependencies. Fails on a dependency cycle.
+pub fn start_order(
+    configs: &HashMap<String, PodStateConfig>,
+    roots: &[String],
+) -> NookResult<Vec<String>> {
+    fn visit(
+        name: &str,
+        configs: &HashMap<String, PodStateConfig>,
+        path: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) -> NookResult<()> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|p| p == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(NookError::ConfigError(format!(
+                "Dependency cycle: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        path.push(name.to_string());
+        for dep in configs
+            .get(name)
+            .map(|c| c.depends_on.as_slice())
+            .unwrap_or_default()
+        {
+            visit(dep, configs, path, done, order)?;
+        }
+        path.pop();
+        done.insert(name.to_string());
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut done = HashSet::new();
+    let mut order = Vec::new();
+    for root in roots {
+        visit(root, configs, &mut Vec::new(), &mut done, &mut order)?;
+    }
+    Ok(order)
+}
+
+/// `start_order` split into rounds that can start in parallel: each pod
+/// is in the round after the last of its dependencies.
+pub fn start_levels(
+    configs: &HashMap<String, PodStateConfig>,
+    roots: &[String],
+) -> NookResult<Vec<Vec<String>>> {
+    let mut depth: HashMap<String, usize> = HashMap::new();
+    let mut levels: Vec<Vec<String>> = Vec::new();
+    for name in start_order(configs, roots)? {
+        let level = configs
+            .get(&name)
+            .map(|c| c.depends_on.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|dep| depth.get(dep))
+            .map(|d| d + 1)
+            .max()
+            .unwrap_or(0);
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Vec::new);
+        }
+        levels[level].push(name.clone());
+        depth.insert(name, level);
+    }
+    Ok(levels)
+}
+
+/// Pods that depend on `name`, directly or not, in the order they have to
+/// stop: every pod before the pods it depends on.
+pub fn dependents(configs: &HashMap<String, PodStateConfig>, name: &str) -> Vec<String> {
+    let mut names: Vec<String> = configs
+        .keys()
+        .filter(|other| *other != name)
+        .filter(|other| {
+            start_order(configs, std::slice::from_ref(*other))
+                .is_ok_and(|order| order.iter().any(|n| n == name))
+        })
+        .cloned()
+        .collect();
+    names.sort();
+    let mut order = start_order(configs, &names).unwrap_or_default();
+    order.retain(|n| names.contains(n));
+    order.reverse();
+    order
+}
+
+/// Wait until a started pod passes its readiness check: the port scanner
+/// found the port listening, or the command exited 0 in the container.
+pub async fn wait_ready(state: &AppState, pod_id: &str, readiness: &Readiness) -> NookResult<()> {
+    let deadline = tokio::time::Instant::now() + READY_TIMEOUT;
+    loop {
+        let (docker, container_id, ready) = {
+            let s = state.lock().await;
+            let pod = s
+                .pods
+                .get(pod_id)
+                .ok_or_else(|| NookError::ConfigError(format!("Pod not found: {}", pod_id)))?;
+            let ready = match readiness {
+                Readiness::Port { port } => pod
+                    .detected_ports
+                    .iter()
+                    .map(|p| p.container_port)
+                    .chain(pod.exposed_ports.iter().map(|p| p.container_port))
+                    .any(|p| p == *port),
+                Readiness::Command { .. } => false,
+            };
+            (s.docker_for(pod_id), pod.container_id.clone(), ready)
+        };
+        if ready {
+            return Ok(());
+        }
+        if let (Readiness::Command { command }, Some(docker), Some(cid)) =
+            (readiness, &docker, &container_id)
+        {
+            match exec_status(docker, cid, command).await {
+                Ok(0) => return Ok(()),
+                Ok(code) => tracing::debug!("Readiness check of {} exited {}", pod_id, code),
+                Err(e) => tracing::debug!("Readiness check of {} failed: {}", pod_id, e),
+            }
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(NookError::Timeout(format!(
+                "{} not ready after {}s",
+                readiness.describe(),
+                READY_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Run `command` with `sh -c` in the container, discarding its output.
+/// Returns the exit code.
+async fn exec_status(docker: &Docker, container_id: &str, command: &str) -> NookResult<i64> {
+    let exec = docker
+        .create_exec(
+            container_id,
+            CreateExecOptions {
+                cmd: Some(vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    command.to_string(),
+                ]),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                ..Default::default()
+            },
+        )
+        .await?;
+    if let StartExecResults::Attached { mut output, .. } = docker.start_exec(&exec.id, None).await?
+    {
+        while let Some(chunk) = output.next().await {
+            if let LogOutput::StdErr { message } = chunk? {
+                tracing::trace!("Readiness check: {}", String::from_utf8_lossy(&message));
+            }
+        }
+    }
+    Ok(docker.inspect_exec(&exec.id).await?.exit_code.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configs(edges: &[(&str, &[&str])]) -> HashMap<String, PodStateConfig> {
+        edges
+            .iter()
+            .map(|(name, deps)| {
+                let mut config: PodStateConfig = toml::from_str(&format!(
+                    "name = \"{}\"\nproject_path = \"/tmp/{}\"",
+                    name, name
+                ))
+                .unwrap();
+                config.depends_on = deps.iter().map(|d| d.to_string()).collect();
+                (name.to_string(), config)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_start_order() {
+        let configs = configs(&[
+            ("frontend", &["api"]),
+            ("api", &["infra"]),
+            ("worker", &["infra", "api"]),
+            ("infra", &[]),
+        ]);
+        assert_eq!(
+            start_order(&configs, &["frontend".to_string()]).unwrap(),
+            vec!["infra", "api", "frontend"]
+        );
+        assert_eq!(
+            start_order(&configs, &["worker".to_string(), "frontend".to_string()]).unwrap(),
+            vec!["infra", "api", "worker", "frontend"]
+        );
+        assert_eq!(
+            dependents(&configs, "infra"),
+            vec!["worker", "frontend", "api"]
+        );
+        assert!(dependents(&configs, "frontend").is_empty());
+    }
+
+    #[test]
+    fn test_start_levels() {
+        let configs = configs(&[
+            ("frontend", &["api"]),
+            ("api", &["infra"]),
+            ("worker", &["infra"]),
+            ("infra", &[]),
+            ("docs", &[]),
+        ]);
+        let roots: Vec<String> = ["frontend", "worker", "docs"]
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(
+            start_levels(&configs, &roots).unwrap(),
+            vec![
+                vec!["infra", "docs"],
+                vec!["api", "worker"],
+                vec!["frontend"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let configs = configs(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+        let err = start_order(&configs, &["a".to_string()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Configuration error: Dependency cycle: a -> b -> c -> a"
+        );
+        assert!(dependents(&configs, "a").is_empty());
+    }
+}
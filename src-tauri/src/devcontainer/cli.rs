@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::time::Duration;
 
 use tauri::{AppHandle, Emitter};
@@ -5,96 +6,217 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::settings::Settings;
+use crate::docker::endpoint::DockerEndpoint;
+use crate::docker::runtime::RuntimeInfo;
 use crate::error::{NookError, NookResult};
 use crate::state::AppState;
 use crate::types::{LogBatchEvent, LogEntry, LogLevel, LogSource};
 
-/// Run `devcontainer up` for the given workspace folder
-pub async fn devcontainer_up(workspace_path: &str) -> NookResult<String> {
-    let timeout = Duration::from_secs(120);
+const DEFAULT_PROGRAM: &str = "devcontainer";
+
+/// How to invoke the devcontainer CLI, resolved from `Settings`.
+#[derive(Debug, Clone)]
+pub struct CliOptions {
+    pub program: String,
+    pub extra_args: Vec<String>,
+    pub timeout: Option<Duration>,
+    /// Environment selecting the pod's Docker endpoint
+    pub env: Vec<(String, String)>,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            program: DEFAULT_PROGRAM.to_string(),
+            extra_args: Vec::new(),
+            timeout: Some(Duration::from_secs(600)),
+            env: Vec::new(),
+        }
+    }
+}
+
+impl CliOptions {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let program = if settings.devcontainer_cli_path.trim().is_empty() {
+            DEFAULT_PROGRAM.to_string()
+        } else {
+            expand_home(settings.devcontainer_cli_path.trim())
+        };
+        let timeout = match settings.devcontainer_build_timeout {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        Self {
+            program,
+            extra_args: settings.devcontainer_extra_args.clone(),
+            timeout,
+            env: Vec::new(),
+        }
+    }
+
+    /// Point the CLI, and the docker commands it runs, at `endpoint`.
+    pub fn with_endpoint(mut self, endpoint: Option<&DockerEndpoint>) -> Self {
+        self.env = endpoint.map(|e| e.cli_env()).unwrap_or_default();
+        self
+    }
+
+    /// Have the CLI drive `podman` for Podman endpoints, unless the user
+    /// already chose a docker path. The podman CLI is pointed at the
+    /// endpoint's socket, since it doesn't read `DOCKER_HOST`.
+    pub fn with_runtime(mut self, runtime: RuntimeInfo) -> Self {
+        if !runtime.is_podman() {
+            return self;
+        }
+        if !self.extra_args.iter().any(|a| a.starts_with("--docker-path")) {
+            self.extra_args.push("--docker-path".to_string());
+            self.extra_args.push("podman".to_string());
+        }
+        let host = self
+            .env
+            .iter()
+            .find(|(k, _)| k == "DOCKER_HOST")
+            .map(|(_, v)| v.clone());
+        if let Some(host) = host {
+            self.env.push(("CONTAINER_HOST".to_string(), host));
+        }
+        self
+    }
+
+    /// True when the program is an explicit path rather than a name looked up on PATH.
+    pub fn is_explicit_path(&self) -> bool {
+        std::path::Path::new(&self.program).components().count() > 1
+    }
+
+    /// Build the argument list for a subcommand: `<subcommand> <args...> <extra_args...>`.
+    fn args(&self, subcommand: &str, args: &[&str]) -> Vec<String> {
+        let mut out = Vec::with_capacity(1 + args.len() + self.extra_args.len());
+        out.push(subcommand.to_string());
+        out.extend(args.iter().map(|a| a.to_string()));
+        out.extend(self.extra_args.iter().cloned());
+        out
+    }
 
-    let result = tokio::time::timeout(timeout, async {
-        let output = Command::new("devcontainer")
-            .args(["up", "--workspace-folder", workspace_path])
+    fn command(&self, subcommand: &str, args: &[&str]) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(self.args(subcommand, args))
+            .envs(self.env.clone());
+        cmd
+    }
+}
+
+/// `--workspace-folder` plus, for pods bound to a non-default config, `--config`.
+fn workspace_args<'a>(workspace_path: &'a str, config_path: Option<&'a str>) -> Vec<&'a str> {
+    let mut args = vec!["--workspace-folder", workspace_path];
+    if let Some(config) = config_path {
+        args.push("--config");
+        args.push(config);
+    }
+    args
+}
+
+/// Arguments for `up`. Lifecycle hooks are skipped so Nook can run them with
+/// their own log source (`lifecycle::run_up_hooks`); `postAttachCommand` runs
+/// when a terminal is opened, as an editor does on attach.
+fn up_args<'a>(workspace_path: &'a str, config_path: Option<&'a str>) -> Vec<&'a str> {
+    let mut args = workspace_args(workspace_path, config_path);
+    args.push("--skip-post-create");
+    args
+}
+
+fn expand_home(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest).to_string_lossy().to_string();
+        }
+    }
+    path.to_string()
+}
+
+fn map_spawn_error(e: std::io::Error) -> NookError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        NookError::DevcontainerCliNotFound
+    } else {
+        NookError::Io(e)
+    }
+}
+
+/// Await `fut`, bounded by the configured timeout (if any).
+pub async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    what: &str,
+    fut: impl Future<Output = NookResult<T>>,
+) -> NookResult<T> {
+    match timeout {
+        Some(limit) => match tokio::time::timeout(limit, fut).await {
+            Ok(inner) => inner,
+            Err(_) => Err(NookError::Timeout(format!(
+                "{} did not complete within {}s",
+                what,
+                limit.as_secs()
+            ))),
+        },
+        None => fut.await,
+    }
+}
+
+fn up_failed(code: Option<i32>, stderr: &str) -> NookError {
+    let last_lines: Vec<&str> = stderr.lines().rev().take(3).collect();
+    let error_msg = last_lines.into_iter().rev().collect::<Vec<&str>>().join("\n");
+    NookError::DevcontainerUpFailed(format!("Exit code: {:?}. {}", code, error_msg))
+}
+
+/// Run `devcontainer up` for the given workspace folder
+pub async fn devcontainer_up(
+    opts: &CliOptions,
+    workspace_path: &str,
+    config_path: Option<&str>,
+) -> NookResult<String> {
+    with_timeout(opts.timeout, "Container start", async {
+        let output = opts
+            .command("up", &up_args(workspace_path, config_path))
             .output()
             .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    NookError::DevcontainerCliNotFound
-                } else {
-                    NookError::Io(e)
-                }
-            })?;
+            .map_err(map_spawn_error)?;
 
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
             // devcontainer up outputs JSON with containerId
-            Ok(stdout)
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let last_lines: Vec<&str> = stderr.lines().rev().take(3).collect();
-            let error_msg = last_lines.into_iter().rev().collect::<Vec<&str>>().join("\n");
-            Err(NookError::DevcontainerUpFailed(format!(
-                "Exit code: {:?}. {}",
+            Err(up_failed(
                 output.status.code(),
-                error_msg
-            )))
+                &String::from_utf8_lossy(&output.stderr),
+            ))
         }
     })
-    .await;
-
-    match result {
-        Ok(inner) => inner,
-        Err(_) => Err(NookError::Timeout(
-            "Container did not start within 120s".to_string(),
-        )),
-    }
+    .await
 }
 
 /// Run `devcontainer up --remove-existing-container` for rebuild
-pub async fn devcontainer_up_rebuild(workspace_path: &str) -> NookResult<String> {
-    let timeout = Duration::from_secs(180);
-
-    let result = tokio::time::timeout(timeout, async {
-        let output = Command::new("devcontainer")
-            .args([
-                "up",
-                "--workspace-folder",
-                workspace_path,
-                "--remove-existing-container",
-            ])
+pub async fn devcontainer_up_rebuild(
+    opts: &CliOptions,
+    workspace_path: &str,
+    config_path: Option<&str>,
+) -> NookResult<String> {
+    with_timeout(opts.timeout, "Container rebuild", async {
+        let mut args = up_args(workspace_path, config_path);
+        args.push("--remove-existing-container");
+        let output = opts
+            .command("up", &args)
             .output()
             .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    NookError::DevcontainerCliNotFound
-                } else {
-                    NookError::Io(e)
-                }
-            })?;
+            .map_err(map_spawn_error)?;
 
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            Ok(stdout)
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let last_lines: Vec<&str> = stderr.lines().rev().take(3).collect();
-            let error_msg = last_lines.into_iter().rev().collect::<Vec<&str>>().join("\n");
-            Err(NookError::DevcontainerUpFailed(format!(
-                "Exit code: {:?}. {}",
+            Err(up_failed(
                 output.status.code(),
-                error_msg
-            )))
+                &String::from_utf8_lossy(&output.stderr),
+            ))
         }
     })
-    .await;
-
-    match result {
-        Ok(inner) => inner,
-        Err(_) => Err(NookError::Timeout(
-            "Container rebuild did not complete within 180s".to_string(),
-        )),
-    }
+    .await
 }
 
 /// Run `devcontainer up` with streaming build output.
@@ -102,27 +224,22 @@ pub async fn devcontainer_up_rebuild(workspace_path: &str) -> NookResult<String>
 /// Returns the final JSON output (last line with containerId) on success.
 /// On cancellation, kills the child process.
 pub async fn devcontainer_up_streaming(
+    opts: &CliOptions,
     workspace_path: &str,
+    config_path: Option<&str>,
     pod_id: &str,
     app: &AppHandle,
     state: &AppState,
     cancel: CancellationToken,
 ) -> NookResult<String> {
-    let timeout = Duration::from_secs(180);
-
-    let result = tokio::time::timeout(timeout, async {
-        let mut child = Command::new("devcontainer")
-            .args(["up", "--workspace-folder", workspace_path])
+    with_timeout(opts.timeout, "Container build", async {
+        let mut child = opts
+            .command("up", &up_args(workspace_path, config_path))
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
             .spawn()
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    NookError::DevcontainerCliNotFound
-                } else {
-                    NookError::Io(e)
-                }
-            })?;
+            .map_err(map_spawn_error)?;
 
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
@@ -191,28 +308,16 @@ pub async fn devcontainer_up_streaming(
                     // The last stdout line should contain the JSON with containerId
                     Ok(last_stdout_line)
                 } else {
-                    let last_lines: Vec<&str> = stderr_output.lines().rev().take(3).collect();
-                    let error_msg = last_lines.into_iter().rev().collect::<Vec<&str>>().join("\n");
-                    Err(NookError::DevcontainerUpFailed(format!(
-                        "Exit code: {:?}. {}",
-                        status.code(),
-                        error_msg
-                    )))
+                    Err(up_failed(status.code(), &stderr_output))
                 }
             }
         }
     })
-    .await;
-
-    match result {
-        Ok(inner) => inner,
-        Err(_) => Err(NookError::Timeout(
-            "Container build did not complete within 180s".to_string(),
-        )),
-    }
+    .await
 }
 
-async fn emit_build_line(
+/// Append a line to the pod's build log and emit it as `pod-log-update`.
+pub async fn emit_build_line(
     app: &AppHandle,
     state: &AppState,
     pod_id: &str,
@@ -224,6 +329,7 @@ async fn emit_build_line(
         message: line.to_string(),
         source: LogSource::Build,
         level,
+        service: None,
     };
 
     // Store in LogBuffer
@@ -246,33 +352,149 @@ async fn emit_build_line(
 
 /// Run a command inside the devcontainer
 pub async fn devcontainer_exec(
+    opts: &CliOptions,
     workspace_path: &str,
+    config_path: Option<&str>,
     command: &[&str],
 ) -> NookResult<String> {
-    let mut args = vec!["exec", "--workspace-folder", workspace_path, "--"];
-    args.extend_from_slice(command);
+    let mut args = opts.args("exec", &workspace_args(workspace_path, config_path));
+    args.push("--".to_string());
+    args.extend(command.iter().map(|c| c.to_string()));
 
-    let output = Command::new("devcontainer")
+    let output = Command::new(&opts.program)
         .args(&args)
+        .envs(opts.env.clone())
         .output()
         .await
-        .map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                NookError::DevcontainerCliNotFound
-            } else {
-                NookError::Io(e)
-            }
-        })?;
+        .map_err(map_spawn_error)?;
 
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-/// Check if devcontainer CLI is available
-pub async fn is_devcontainer_available() -> bool {
-    Command::new("devcontainer")
+/// Run `devcontainer --version` and return the reported version.
+pub async fn devcontainer_version(opts: &CliOptions) -> NookResult<String> {
+    let output = Command::new(&opts.program)
         .arg("--version")
         .output()
         .await
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+        .map_err(map_spawn_error)?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(NookError::Other(format!(
+            "{} --version exited with {:?}",
+            opts.program,
+            output.status.code()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_options_defaults_to_path_lookup() {
+        let opts = CliOptions::from_settings(&Settings::default());
+        assert_eq!(opts.program, "devcontainer");
+        assert!(!opts.is_explicit_path());
+        assert_eq!(opts.timeout, Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_cli_options_zero_timeout_disables_it() {
+        let settings = Settings {
+            devcontainer_build_timeout: 0,
+            ..Settings::default()
+        };
+        assert_eq!(CliOptions::from_settings(&settings).timeout, None);
+    }
+
+    #[test]
+    fn test_cli_options_custom_path() {
+        let settings = Settings {
+            devcontainer_cli_path: "/opt/node/bin/devcontainer".to_string(),
+            ..Settings::default()
+        };
+        let opts = CliOptions::from_settings(&settings);
+        assert_eq!(opts.program, "/opt/node/bin/devcontainer");
+        assert!(opts.is_explicit_path());
+    }
+
+    #[test]
+    fn test_cli_options_expands_home() {
+        let settings = Settings {
+            devcontainer_cli_path: "~/.nvm/bin/devcontainer".to_string(),
+            ..Settings::default()
+        };
+        let opts = CliOptions::from_settings(&settings);
+        assert!(!opts.program.starts_with('~'));
+        assert!(opts.program.ends_with(".nvm/bin/devcontainer"));
+    }
+
+    #[test]
+    fn test_cli_args_append_extra_args() {
+        let opts = CliOptions {
+            extra_args: vec!["--docker-path".to_string(), "podman".to_string()],
+            ..CliOptions::default()
+        };
+        assert_eq!(
+            opts.args("up", &["--workspace-folder", "/src/app"]),
+            vec!["up", "--workspace-folder", "/src/app", "--docker-path", "podman"]
+        );
+    }
+
+    #[test]
+    fn test_cli_options_podman() {
+        use crate::docker::runtime::ContainerRuntime;
+
+        let podman = RuntimeInfo {
+            runtime: ContainerRuntime::Podman,
+            rootless: true,
+        };
+        let opts = CliOptions {
+            env: vec![(
+                "DOCKER_HOST".to_string(),
+                "unix:///run/user/1000/podman/podman.sock".to_string(),
+            )],
+            ..CliOptions::default()
+        }
+        .with_runtime(podman);
+        assert_eq!(opts.extra_args, vec!["--docker-path", "podman"]);
+        assert_eq!(opts.env[1].0, "CONTAINER_HOST");
+
+        // An explicit docker path wins
+        let opts = CliOptions {
+            extra_args: vec!["--docker-path=/usr/local/bin/podman".to_string()],
+            ..CliOptions::default()
+        }
+        .with_runtime(podman);
+        assert_eq!(opts.extra_args.len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_args_with_config() {
+        assert_eq!(
+            workspace_args("/src/mono", None),
+            vec!["--workspace-folder", "/src/mono"]
+        );
+        assert_eq!(
+            workspace_args("/src/mono", Some("/src/mono/.devcontainer/api/devcontainer.json")),
+            vec![
+                "--workspace-folder",
+                "/src/mono",
+                "--config",
+                "/src/mono/.devcontainer/api/devcontainer.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_up_args_skip_hooks() {
+        assert_eq!(
+            up_args("/src/app", None),
+            vec!["--workspace-folder", "/src/app", "--skip-post-create"]
+        );
+    }
 }
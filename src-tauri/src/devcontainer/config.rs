@@ -0,0 +1,1023 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::devcontainer::{discovery, jsonc};
+use crate::error::{NookError, NookResult};
+
+/// Typed model of devcontainer.json.
+///
+/// Only the properties Nook acts on are modelled; unknown properties are
+/// ignored rather than rejected so newer spec additions don't break parsing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    // Image / Dockerfile / Compose sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildConfig>,
+    /// Legacy top-level form of `build.dockerfile`
+    #[serde(
+        default,
+        rename = "dockerFile",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub docker_file: Option<String>,
+    /// Legacy top-level form of `build.context`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docker_compose_file: Option<StringOrList>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub run_services: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_mount: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, Value>,
+
+    // Ports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forward_ports: Vec<ForwardPort>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ports_attributes: BTreeMap<String, PortAttributes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_ports_attributes: Option<PortAttributes>,
+
+    // Environment, mounts and container options
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remote_env: BTreeMap<String, Option<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mounts: Vec<Mount>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub run_args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_command: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privileged: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cap_add: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security_opt: Vec<String>,
+
+    // Lifecycle commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialize_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_create_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_content_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_create_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_start_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_attach_command: Option<LifecycleCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub customizations: Option<Value>,
+}
+
+impl DevcontainerConfig {
+    /// Dockerfile path from `build.dockerfile` or the legacy `dockerFile`.
+    pub fn dockerfile(&self) -> Option<&str> {
+        self.build
+            .as_ref()
+            .and_then(|b| b.dockerfile.as_deref())
+            .or(self.docker_file.as_deref())
+    }
+
+    pub fn compose_files(&self) -> Vec<String> {
+        self.docker_compose_file
+            .as_ref()
+            .map(StringOrList::to_vec)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_from: Option<StringOrList>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            StringOrList::One(s) => vec![s.clone()],
+            StringOrList::Many(v) => v.clone(),
+        }
+    }
+}
+
+/// A `forwardPorts` entry: a port number or a `"service:port"` string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum ForwardPort {
+    Port(u64),
+    Service(String),
+}
+
+impl ForwardPort {
+    /// Split into (service/host, port). `None` if the entry is malformed.
+    pub fn target(&self) -> Option<(Option<&str>, u16)> {
+        match self {
+            ForwardPort::Port(p) => valid_port(*p).map(|p| (None, p)),
+            ForwardPort::Service(s) => match s.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() => port
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(valid_port)
+                    .map(|p| (Some(host), p)),
+                Some(_) => None,
+                None => s
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(valid_port)
+                    .map(|p| (None, p)),
+            },
+        }
+    }
+}
+
+fn valid_port(p: u64) -> Option<u16> {
+    u16::try_from(p).ok().filter(|p| *p > 0)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PortAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_auto_forward: Option<OnAutoForward>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_local_port: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevate_if_needed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum OnAutoForward {
+    Notify,
+    OpenBrowser,
+    OpenBrowserOnce,
+    OpenPreview,
+    Silent,
+    Ignore,
+}
+
+/// A `mounts` entry: a `--mount` style string or an object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Mount {
+    Spec(String),
+    Object {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        mount_type: String,
+    },
+}
+
+/// A lifecycle command: a shell string, an argv array, or named parallel commands.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    Shell(String),
+    Exec(Vec<String>),
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem found while parsing or validating devcontainer.json.
+/// `line` and `column` are 1-based; `column` is only known for syntax/type errors.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// Result of `preview_devcontainer`: the resolved config (if it parsed) and
+/// every issue found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerPreview {
+    pub config_file: Option<String>,
+    pub config: Option<DevcontainerConfig>,
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// Values for `${...}` variable substitution.
+pub struct SubstitutionContext<'a> {
+    pub local_workspace_folder: &'a str,
+    pub local_env: &'a dyn Fn(&str) -> Option<String>,
+}
+
+/// Parse devcontainer.json text. Returns the resolved config (with variables
+/// substituted) when it parses, along with all validation issues.
+/// `config_dir` enables checks for referenced files (Dockerfile, compose files).
+pub fn parse(
+    text: &str,
+    ctx: &SubstitutionContext,
+    config_dir: Option<&Path>,
+) -> (Option<DevcontainerConfig>, Vec<ValidationIssue>) {
+    let json = jsonc::to_json(text);
+    let mut issues = Vec::new();
+
+    let mut value = match serde_json::from_str::<Value>(&json) {
+        Ok(value) => value,
+        Err(e) => {
+            issues.push(serde_issue(&e));
+            return (None, issues);
+        }
+    };
+    if let Err(e) = serde_json::from_str::<DevcontainerConfig>(&json) {
+        issues.push(serde_issue(&e));
+        return (None, issues);
+    }
+
+    substitute_variables(&mut value, text, ctx, &mut issues);
+
+    let config = match serde_json::from_value::<DevcontainerConfig>(value) {
+        Ok(config) => config,
+        Err(e) => {
+            issues.push(issue(1, IssueSeverity::Error, e.to_string()));
+            return (None, issues);
+        }
+    };
+
+    validate(&config, text, config_dir, &mut issues);
+    (Some(config), issues)
+}
+
+/// Load and resolve a pod's devcontainer.json using the host environment.
+/// Validation issues are ignored here; only unreadable or unparseable files fail.
+pub fn load(project_path: &str, config_path: Option<&str>) -> NookResult<DevcontainerConfig> {
+    let path = discovery::resolve_config_path(project_path, config_path).ok_or_else(|| {
+        NookError::ConfigError(format!("No devcontainer.json found in {}", project_path))
+    })?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| NookError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let env = |name: &str| std::env::var(name).ok();
+    let ctx = SubstitutionContext {
+        local_workspace_folder: project_path,
+        local_env: &env,
+    };
+    let (config, issues) = parse(&text, &ctx, path.parent());
+    config.ok_or_else(|| {
+        let detail = issues
+            .first()
+            .map(|i| format!("line {}: {}", i.line, i.message))
+            .unwrap_or_default();
+        NookError::ConfigError(format!("Invalid {}: {}", path.display(), detail))
+    })
+}
+
+/// Everything a pod's container is built from, recorded at build time so
+/// later edits to the config can be detected and summarised.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSnapshot {
+    /// Content hash per file, keyed by path relative to the config directory:
+    /// devcontainer.json plus the Dockerfile and compose files it references.
+    /// Referenced files that don't exist are left out.
+    pub files: BTreeMap<String, String>,
+    /// Declared features, mapped to their options as JSON.
+    pub features: BTreeMap<String, String>,
+}
+
+impl ConfigSnapshot {
+    /// Snapshot a pod's config as it is on disk now. `None` if there is no
+    /// devcontainer.json.
+    pub fn take(project_path: &str, config_path: Option<&str>) -> Option<Self> {
+        let path = discovery::resolve_config_path(project_path, config_path)?;
+        let text = std::fs::read_to_string(&path).ok()?;
+        let dir = path.parent()?;
+        let name = path.file_name()?.to_string_lossy().to_string();
+
+        // Hash the parsed JSON so comments and formatting don't count
+        let canonical = serde_json::from_str::<Value>(&jsonc::to_json(&text))
+            .map(|v| v.to_string())
+            .unwrap_or(text);
+        let mut files = BTreeMap::from([(name, hash_hex(canonical.as_bytes()))]);
+        let mut features = BTreeMap::new();
+        if let Ok(config) = load(project_path, config_path) {
+            let referenced = config
+                .dockerfile()
+                .map(String::from)
+                .into_iter()
+                .chain(config.compose_files());
+            for file in referenced {
+                if let Ok(bytes) = std::fs::read(dir.join(&file)) {
+                    files.insert(file, hash_hex(&bytes));
+                }
+            }
+            features = config
+                .features
+                .iter()
+                .map(|(id, options)| (id.clone(), options.to_string()))
+                .collect();
+        }
+        Some(Self { files, features })
+    }
+
+    /// Single hash over the whole snapshot.
+    pub fn fingerprint(&self) -> String {
+        let mut hash = FNV_OFFSET;
+        for (key, value) in self.files.iter().chain(&self.features) {
+            hash = fnv1a(hash, key.as_bytes());
+            hash = fnv1a(hash, value.as_bytes());
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// Human-readable changes from `before` to this snapshot, files first,
+    /// then features. Empty if nothing changed.
+    pub fn changes_since(&self, before: &Self) -> Vec<String> {
+        let mut changes = map_changes(&before.files, &self.files, "", "changed");
+        changes.extend(map_changes(
+            &before.features,
+            &self.features,
+            "feature ",
+            "options changed",
+        ));
+        changes
+    }
+}
+
+fn map_changes(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+    prefix: &str,
+    changed: &str,
+) -> Vec<String> {
+    let keys: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let what = match (before.get(key), after.get(key)) {
+                (None, Some(_)) => "added",
+                (Some(_), None) => "removed",
+                (Some(a), Some(b)) if a != b => changed,
+                _ => return None,
+            };
+            Some(format!("{}{} {}", prefix, key, what))
+        })
+        .collect()
+}
+
+/// Fingerprint of a pod's config as it is on disk now; see `ConfigSnapshot`.
+/// Tells whether an existing container still matches its config. `None` if
+/// the config can't be read.
+pub fn fingerprint(project_path: &str, config_path: Option<&str>) -> Option<String> {
+    ConfigSnapshot::take(project_path, config_path).map(|s| s.fingerprint())
+}
+
+/// Hex FNV-1a hash of `bytes`, stable enough to persist.
+pub(crate) fn hash_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", fnv1a(FNV_OFFSET, bytes))
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` it is stable across Rust releases,
+/// so its hashes can be persisted.
+fn fnv1a(state: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(state, |h, b| (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Resolve and validate a project's devcontainer.json without adding a pod.
+pub fn preview(project_path: &str, config_path: Option<&str>) -> DevcontainerPreview {
+    let Some(path) = discovery::resolve_config_path(project_path, config_path) else {
+        return DevcontainerPreview {
+            config_file: None,
+            config: None,
+            issues: vec![issue(
+                1,
+                IssueSeverity::Error,
+                format!(
+                    "devcontainer.json not found: {}",
+                    config_path.unwrap_or(".devcontainer/devcontainer.json")
+                ),
+            )],
+        };
+    };
+
+    let config_file = Some(path.to_string_lossy().to_string());
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            return DevcontainerPreview {
+                config_file,
+                config: None,
+                issues: vec![issue(
+                    1,
+                    IssueSeverity::Error,
+                    format!("Failed to read: {}", e),
+                )],
+            }
+        }
+    };
+
+    let env = |name: &str| std::env::var(name).ok();
+    let ctx = SubstitutionContext {
+        local_workspace_folder: project_path,
+        local_env: &env,
+    };
+    let (config, issues) = parse(&text, &ctx, path.parent());
+    DevcontainerPreview {
+        config_file,
+        config,
+        issues,
+    }
+}
+
+fn issue(line: usize, severity: IssueSeverity, message: String) -> ValidationIssue {
+    ValidationIssue {
+        line,
+        column: None,
+        severity,
+        message,
+    }
+}
+
+fn serde_issue(e: &serde_json::Error) -> ValidationIssue {
+    // serde_json appends " at line X column Y"; the position is reported separately
+    let message = e.to_string();
+    let message = message
+        .rsplit_once(" at line ")
+        .map(|(m, _)| m.to_string())
+        .unwrap_or(message);
+    ValidationIssue {
+        line: e.line().max(1),
+        column: Some(e.column()).filter(|c| *c > 0),
+        severity: IssueSeverity::Error,
+        message,
+    }
+}
+
+/// 1-based line of the first occurrence of `needle` in `text`, or 1 if absent.
+fn line_of(text: &str, needle: &str) -> usize {
+    text.find(needle)
+        .map(|idx| text[..idx].matches('\n').count() + 1)
+        .unwrap_or(1)
+}
+
+fn key_line(text: &str, key: &str) -> usize {
+    line_of(text, &format!("\"{}\"", key))
+}
+
+fn substitute_variables(
+    value: &mut Value,
+    text: &str,
+    ctx: &SubstitutionContext,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let local_folder = ctx.local_workspace_folder.trim_end_matches('/');
+    let local_basename = Path::new(local_folder)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // containerWorkspaceFolder comes from workspaceFolder, which may itself use local variables
+    let no_container = Vars {
+        local_folder,
+        local_basename: &local_basename,
+        container_folder: None,
+        container_basename: None,
+        env: ctx.local_env,
+    };
+    let container_folder = value
+        .get("workspaceFolder")
+        .and_then(|v| v.as_str())
+        .map(|w| no_container.substitute(w, &mut Vec::new()))
+        .unwrap_or_else(|| format!("/workspaces/{}", local_basename));
+    let container_basename = Path::new(&container_folder)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let vars = Vars {
+        container_folder: Some(&container_folder),
+        container_basename: Some(&container_basename),
+        ..no_container
+    };
+
+    let mut unset = Vec::new();
+    walk_strings(value, &mut |s| *s = vars.substitute(s, &mut unset));
+
+    unset.sort();
+    unset.dedup();
+    for name in unset {
+        issues.push(issue(
+            line_of(text, &format!("${{localEnv:{}", name)),
+            IssueSeverity::Warning,
+            format!(
+                "Environment variable {} is not set; substituted an empty string",
+                name
+            ),
+        ));
+    }
+}
+
+fn walk_strings(value: &mut Value, f: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| walk_strings(v, f)),
+        Value::Object(map) => map.values_mut().for_each(|v| walk_strings(v, f)),
+        _ => {}
+    }
+}
+
+/// A `${...}` variable reference.
+static VARIABLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{([^}]+)\}").unwrap());
+
+struct Vars<'a> {
+    local_folder: &'a str,
+    local_basename: &'a str,
+    container_folder: Option<&'a str>,
+    container_basename: Option<&'a str>,
+    env: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Vars<'_> {
+    /// Replace `${...}` variables that can be resolved on the host. Variables
+    /// resolved inside the container (`${containerEnv:...}`) are left as-is.
+    fn substitute(&self, input: &str, unset: &mut Vec<String>) -> String {
+        VARIABLE.replace_all(input, |caps: &regex::Captures| {
+            let whole = caps[0].to_string();
+            let var = &caps[1];
+            match var {
+                "localWorkspaceFolder" => self.local_folder.to_string(),
+                "localWorkspaceFolderBasename" => self.local_basename.to_string(),
+                "containerWorkspaceFolder" => {
+                    self.container_folder.map(String::from).unwrap_or(whole)
+                }
+                "containerWorkspaceFolderBasename" => {
+                    self.container_basename.map(String::from).unwrap_or(whole)
+                }
+                _ => {
+                    let env_ref = var
+                        .strip_prefix("localEnv:")
+                        .or_else(|| var.strip_prefix("env:"));
+                    match env_ref {
+                        Some(env_ref) => {
+                            let (name, default) = match env_ref.split_once(':') {
+                                Some((name, default)) => (name, Some(default)),
+                                None => (env_ref, None),
+                            };
+                            match ((self.env)(name), default) {
+                                (Some(val), _) => val,
+                                (None, Some(default)) => default.to_string(),
+                                (None, None) => {
+                                    unset.push(name.to_string());
+                                    String::new()
+                                }
+                            }
+                        }
+                        None => whole,
+                    }
+                }
+            }
+        })
+        .to_string()
+    }
+}
+
+fn validate(
+    config: &DevcontainerConfig,
+    text: &str,
+    config_dir: Option<&Path>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let has_image = config.image.is_some();
+    let has_build = config.dockerfile().is_some();
+    let has_compose = config.docker_compose_file.is_some();
+
+    if !has_image && !has_build && !has_compose {
+        issues.push(issue(
+            1,
+            IssueSeverity::Error,
+            "One of image, build.dockerfile or dockerComposeFile is required".to_string(),
+        ));
+    }
+    if [has_image, has_build, has_compose]
+        .iter()
+        .filter(|b| **b)
+        .count()
+        > 1
+    {
+        issues.push(issue(
+            key_line(
+                text,
+                if has_compose {
+                    "dockerComposeFile"
+                } else {
+                    "image"
+                },
+            ),
+            IssueSeverity::Warning,
+            "image, build and dockerComposeFile are alternatives; only one should be set"
+                .to_string(),
+        ));
+    }
+    if config.build.is_some()
+        && config
+            .build
+            .as_ref()
+            .and_then(|b| b.dockerfile.as_ref())
+            .is_none()
+    {
+        issues.push(issue(
+            key_line(text, "build"),
+            IssueSeverity::Error,
+            "build.dockerfile is required when build is set".to_string(),
+        ));
+    }
+    if has_compose && config.service.is_none() {
+        issues.push(issue(
+            key_line(text, "dockerComposeFile"),
+            IssueSeverity::Error,
+            "service is required when dockerComposeFile is set".to_string(),
+        ));
+    }
+
+    if let Some(dir) = config_dir {
+        if let Some(dockerfile) = config.dockerfile() {
+            if !dir.join(dockerfile).is_file() {
+                let key = if config.docker_file.is_some() {
+                    "dockerFile"
+                } else {
+                    "dockerfile"
+                };
+                issues.push(issue(
+                    key_line(text, key),
+                    IssueSeverity::Error,
+                    format!("Dockerfile not found: {}", dockerfile),
+                ));
+            }
+        }
+        for compose in config.compose_files() {
+            if !dir.join(&compose).is_file() {
+                issues.push(issue(
+                    key_line(text, "dockerComposeFile"),
+                    IssueSeverity::Error,
+                    format!("Compose file not found: {}", compose),
+                ));
+            }
+        }
+    }
+
+    for port in &config.forward_ports {
+        if port.target().is_none() {
+            let shown = match port {
+                ForwardPort::Port(p) => p.to_string(),
+                ForwardPort::Service(s) => s.clone(),
+            };
+            issues.push(issue(
+                key_line(text, "forwardPorts"),
+                IssueSeverity::Error,
+                format!("Invalid forwardPorts entry: {}", shown),
+            ));
+        }
+    }
+
+    for key in config.ports_attributes.keys() {
+        if !is_port_pattern(key) && Regex::new(key).is_err() {
+            issues.push(issue(
+                key_line(text, key),
+                IssueSeverity::Error,
+                format!("Invalid portsAttributes key: {}", key),
+            ));
+        }
+    }
+
+    for mount in &config.mounts {
+        if let Mount::Spec(spec) = mount {
+            let has_target = spec.split(',').any(|part| {
+                matches!(
+                    part.split_once('=').map(|(k, _)| k.trim()),
+                    Some("target") | Some("dst") | Some("destination")
+                )
+            });
+            if !has_target {
+                issues.push(issue(
+                    line_of(text, spec),
+                    IssueSeverity::Error,
+                    format!("Mount is missing a target: {}", spec),
+                ));
+            }
+        }
+    }
+}
+
+/// `portsAttributes` keys may be a port (`3000`) or a range (`3000-3010`).
+/// Anything else is treated as a regex over the process command line.
+pub fn is_port_pattern(key: &str) -> bool {
+    match key.split_once('-') {
+        Some((start, end)) => start.parse::<u16>().is_ok() && end.parse::<u16>().is_ok(),
+        None => key.parse::<u16>().is_ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_with_env(
+        text: &str,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> (Option<DevcontainerConfig>, Vec<ValidationIssue>) {
+        let ctx = SubstitutionContext {
+            local_workspace_folder: "/home/dev/projects/shop",
+            local_env: env,
+        };
+        parse(text, &ctx, None)
+    }
+
+    fn parse_text(text: &str) -> (Option<DevcontainerConfig>, Vec<ValidationIssue>) {
+        parse_with_env(text, &|_| None)
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let text = r#"{
+  // Shop API
+  "name": "shop",
+  "build": { "dockerfile": "Dockerfile", "args": { "NODE_VERSION": "20" } },
+  "features": { "ghcr.io/devcontainers/features/node:1": { "version": "lts" } },
+  "forwardPorts": [3000, "db:5432"],
+  "portsAttributes": {
+    "3000": { "label": "Web", "onAutoForward": "openBrowser", "protocol": "https" },
+  },
+  "containerEnv": { "APP_ENV": "dev" },
+  "remoteEnv": { "PATH": "${containerEnv:PATH}:/opt/bin", "UNSET": null },
+  "mounts": ["source=cache,target=/cache,type=volume", { "source": "/tmp", "target": "/host-tmp", "type": "bind" }],
+  "postCreateCommand": "npm ci",
+  "postStartCommand": ["npm", "run", "dev"],
+  "postAttachCommand": { "server": "npm start", "watch": ["npm", "run", "watch"] },
+  "remoteUser": "node",
+}"#;
+        let (config, issues) = parse_text(text);
+        assert!(issues.is_empty(), "{:?}", issues);
+        let config = config.unwrap();
+        assert_eq!(config.name.as_deref(), Some("shop"));
+        assert_eq!(config.dockerfile(), Some("Dockerfile"));
+        assert_eq!(config.features.len(), 1);
+        assert_eq!(config.forward_ports[0].target(), Some((None, 3000)));
+        assert_eq!(config.forward_ports[1].target(), Some((Some("db"), 5432)));
+        let web = &config.ports_attributes["3000"];
+        assert_eq!(web.label.as_deref(), Some("Web"));
+        assert_eq!(web.on_auto_forward, Some(OnAutoForward::OpenBrowser));
+        assert_eq!(
+            config.remote_env["PATH"].as_deref(),
+            Some("${containerEnv:PATH}:/opt/bin")
+        );
+        assert_eq!(config.remote_env["UNSET"], None);
+        assert_eq!(config.mounts.len(), 2);
+        assert_eq!(
+            config.post_create_command,
+            Some(LifecycleCommand::Shell("npm ci".to_string()))
+        );
+        assert!(
+            matches!(config.post_attach_command, Some(LifecycleCommand::Parallel(ref m)) if m.len() == 2)
+        );
+        assert_eq!(config.remote_user.as_deref(), Some("node"));
+    }
+
+    #[test]
+    fn test_variable_substitution() {
+        let text = r#"{
+  "image": "node:20",
+  "workspaceFolder": "/work/${localWorkspaceFolderBasename}",
+  "containerEnv": {
+    "TOKEN": "${localEnv:API_TOKEN}",
+    "REGION": "${localEnv:REGION:eu-west-1}",
+    "SRC": "${localWorkspaceFolder}",
+    "DST": "${containerWorkspaceFolder}/src"
+  }
+}"#;
+        let env = |name: &str| (name == "API_TOKEN").then(|| "secret".to_string());
+        let (config, issues) = parse_with_env(text, &env);
+        assert!(issues.is_empty(), "{:?}", issues);
+        let config = config.unwrap();
+        assert_eq!(config.workspace_folder.as_deref(), Some("/work/shop"));
+        assert_eq!(config.container_env["TOKEN"], "secret");
+        assert_eq!(config.container_env["REGION"], "eu-west-1");
+        assert_eq!(config.container_env["SRC"], "/home/dev/projects/shop");
+        assert_eq!(config.container_env["DST"], "/work/shop/src");
+    }
+
+    #[test]
+    fn test_unset_local_env_is_a_warning() {
+        let text = "{\n  \"image\": \"node:20\",\n  \"containerEnv\": { \"T\": \"${localEnv:MISSING}\" }\n}";
+        let (config, issues) = parse_text(text);
+        assert_eq!(config.unwrap().container_env["T"], "");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert_eq!(issues[0].line, 3);
+    }
+
+    #[test]
+    fn test_syntax_error_has_line_number() {
+        let text = "{\n  \"image\": \"node:20\"\n  \"remoteUser\": \"node\"\n}";
+        let (config, issues) = parse_text(text);
+        assert!(config.is_none());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0].column.is_some());
+    }
+
+    #[test]
+    fn test_type_error_has_line_number() {
+        let text = "{\n  \"image\": \"node:20\",\n  \"forwardPorts\": 3000\n}";
+        let (config, issues) = parse_text(text);
+        assert!(config.is_none());
+        assert_eq!(issues[0].line, 3);
+        assert!(!issues[0].message.contains(" at line "));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let text = r#"{
+  "dockerComposeFile": "docker-compose.yml",
+  "forwardPorts": [70000, "db:"],
+  "portsAttributes": { "(unclosed": { "label": "bad" } },
+  "mounts": ["source=cache,type=volume"]
+}"#;
+        let (config, issues) = parse_text(text);
+        assert!(config.is_some());
+        let messages: Vec<(usize, &str)> = issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+        assert!(messages.contains(&(2, "service is required when dockerComposeFile is set")));
+        assert!(messages.contains(&(3, "Invalid forwardPorts entry: 70000")));
+        assert!(messages.contains(&(3, "Invalid forwardPorts entry: db:")));
+        assert!(messages.contains(&(4, "Invalid portsAttributes key: (unclosed")));
+        assert!(messages.contains(&(5, "Mount is missing a target: source=cache,type=volume")));
+    }
+
+    #[test]
+    fn test_missing_source_is_an_error() {
+        let (config, issues) = parse_text(r#"{ "name": "empty" }"#);
+        assert!(config.is_some());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+    }
+
+    #[test]
+    fn test_missing_dockerfile_is_reported() {
+        let dir = std::env::temp_dir().join(format!("nook-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let text = "{\n  \"build\": {\n    \"dockerfile\": \"Dockerfile\"\n  }\n}";
+        let ctx = SubstitutionContext {
+            local_workspace_folder: "/src/app",
+            local_env: &|_| None,
+        };
+
+        let (_, issues) = parse(text, &ctx, Some(&dir));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 3);
+
+        std::fs::write(dir.join("Dockerfile"), "FROM alpine").unwrap();
+        let (_, issues) = parse(text, &ctx, Some(&dir));
+        assert!(issues.is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_dockerfile() {
+        let dir = std::env::temp_dir().join(format!("nook-config-{}", uuid::Uuid::new_v4()));
+        let dc = dir.join(".devcontainer");
+        std::fs::create_dir_all(&dc).unwrap();
+        std::fs::write(dc.join("devcontainer.json"), r#"{ "build": { "dockerfile": "Dockerfile" } }"#)
+            .unwrap();
+        std::fs::write(dc.join("Dockerfile"), "FROM alpine").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let first = fingerprint(&project, None).unwrap();
+        assert_eq!(fingerprint(&project, None).unwrap(), first);
+
+        std::fs::write(dc.join("Dockerfile"), "FROM alpine:3.20").unwrap();
+        let second = fingerprint(&project, None).unwrap();
+        assert_ne!(second, first);
+
+        // Comments and formatting don't count
+        std::fs::write(
+            dc.join("devcontainer.json"),
+            "{\n  // built locally\n  \"build\": { \"dockerfile\": \"Dockerfile\" },\n}",
+        )
+        .unwrap();
+        let snapshot = ConfigSnapshot::take(&project, None).unwrap();
+        assert_eq!(snapshot.fingerprint(), second);
+        assert_eq!(
+            snapshot.files.keys().collect::<Vec<_>>(),
+            ["Dockerfile", "devcontainer.json"]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+        assert_eq!(fingerprint(&project, None), None);
+    }
+
+    #[test]
+    fn test_snapshot_changes() {
+        let snapshot = |files: &[(&str, &str)], features: &[(&str, &str)]| ConfigSnapshot {
+            files: files
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            features: features
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        let before = snapshot(
+            &[("devcontainer.json", "a"), ("Dockerfile", "b")],
+            &[("node:1", "{}"), ("python:1", "{}")],
+        );
+        let after = snapshot(
+            &[("devcontainer.json", "c"), ("compose.yml", "d")],
+            &[("node:1", r#"{"version":"20"}"#), ("go:1", "{}")],
+        );
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                "Dockerfile removed",
+                "compose.yml added",
+                "devcontainer.json changed",
+                "feature go:1 added",
+                "feature node:1 options changed",
+                "feature python:1 removed",
+            ]
+        );
+        assert!(before.changes_since(&before).is_empty());
+    }
+
+    #[test]
+    fn test_is_port_pattern() {
+        assert!(is_port_pattern("3000"));
+        assert!(is_port_pattern("3000-3010"));
+        assert!(!is_port_pattern("node.*"));
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use crate::types::DevcontainerConfigInfo;
+
+/// Default config locations, in the order the devcontainer CLI checks them.
+const DEFAULT_CONFIGS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Enumerate every devcontainer.json in a project.
+///
+/// Covers `.devcontainer/devcontainer.json`, `.devcontainer.json` and the
+/// one-level-deep `.devcontainer/<name>/devcontainer.json` form used by
+/// monorepos. Paths are returned relative to `project_path`.
+pub fn find_configs(project_path: &str) -> Vec<DevcontainerConfigInfo> {
+    let root = Path::new(project_path);
+    let mut configs = Vec::new();
+
+    for rel in DEFAULT_CONFIGS {
+        if root.join(rel).is_file() {
+            configs.push(DevcontainerConfigInfo {
+                config_path: rel.to_string(),
+                name: None,
+            });
+        }
+    }
+
+    let mut named: Vec<DevcontainerConfigInfo> = std::fs::read_dir(root.join(".devcontainer"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("devcontainer.json").is_file())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            DevcontainerConfigInfo {
+                config_path: format!(".devcontainer/{}/devcontainer.json", name),
+                name: Some(name),
+            }
+        })
+        .collect();
+    named.sort_by(|a, b| a.config_path.cmp(&b.config_path));
+    configs.extend(named);
+
+    configs
+}
+
+/// Resolve the devcontainer.json a pod uses: the bound `config_path` if set,
+/// otherwise the first default location that exists.
+pub fn resolve_config_path(project_path: &str, config_path: Option<&str>) -> Option<PathBuf> {
+    let root = Path::new(project_path);
+    match config_path.filter(|c| !c.is_empty()) {
+        Some(config) => {
+            let path = root.join(config);
+            path.is_file().then_some(path)
+        }
+        None => DEFAULT_CONFIGS
+            .iter()
+            .map(|rel| root.join(rel))
+            .find(|path| path.is_file()),
+    }
+}
+
+/// Convert a config path (e.g. from the `devcontainer.config_file` label) into
+/// the project-relative form stored in `PodStateConfig::config_path`.
+/// Default locations map to an empty string.
+pub fn relative_config_path(project_path: &str, config_file: &str) -> String {
+    let rel = Path::new(config_file)
+        .strip_prefix(project_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| config_file.to_string());
+    if DEFAULT_CONFIGS.contains(&rel.as_str()) {
+        String::new()
+    } else {
+        rel
+    }
+}
+
+/// Derive a pod name from the project directory and, for named configs, the
+/// `.devcontainer/<name>` subfolder (e.g. `monorepo-api`).
+pub fn pod_name_for(project_path: &str, config_path: Option<&str>) -> String {
+    let base = Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unnamed".to_string());
+
+    let sub = config_path
+        .map(Path::new)
+        .filter(|p| p.file_name().is_some_and(|f| f == "devcontainer.json"))
+        .and_then(|p| p.parent())
+        .filter(|dir| {
+            dir.parent()
+                .is_some_and(|d| d == Path::new(".devcontainer"))
+        })
+        .and_then(|dir| dir.file_name())
+        .map(|n| n.to_string_lossy().to_string());
+
+    match sub {
+        Some(sub) => format!("{}-{}", base, sub),
+        None => base,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("nook-discovery-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(path: PathBuf) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "{}").unwrap();
+    }
+
+    #[test]
+    fn test_find_configs_monorepo() {
+        let root = temp_project("monorepo");
+        touch(root.join(".devcontainer/devcontainer.json"));
+        touch(root.join(".devcontainer/web/devcontainer.json"));
+        touch(root.join(".devcontainer/api/devcontainer.json"));
+        std::fs::create_dir_all(root.join(".devcontainer/empty")).unwrap();
+
+        let configs = find_configs(root.to_str().unwrap());
+        let paths: Vec<&str> = configs.iter().map(|c| c.config_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ".devcontainer/devcontainer.json",
+                ".devcontainer/api/devcontainer.json",
+                ".devcontainer/web/devcontainer.json",
+            ]
+        );
+        assert_eq!(configs[1].name.as_deref(), Some("api"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_config_path() {
+        let root = temp_project("resolve");
+        touch(root.join(".devcontainer.json"));
+        touch(root.join(".devcontainer/api/devcontainer.json"));
+        let project = root.to_str().unwrap();
+
+        assert_eq!(
+            resolve_config_path(project, None),
+            Some(root.join(".devcontainer.json"))
+        );
+        assert_eq!(
+            resolve_config_path(project, Some(".devcontainer/api/devcontainer.json")),
+            Some(root.join(".devcontainer/api/devcontainer.json"))
+        );
+        assert_eq!(
+            resolve_config_path(project, Some(".devcontainer/nope/devcontainer.json")),
+            None
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_relative_config_path() {
+        assert_eq!(
+            relative_config_path("/src/mono", "/src/mono/.devcontainer/api/devcontainer.json"),
+            ".devcontainer/api/devcontainer.json"
+        );
+        assert_eq!(
+            relative_config_path("/src/mono", "/src/mono/.devcontainer/devcontainer.json"),
+            ""
+        );
+        assert_eq!(
+            relative_config_path("/src/mono", "/elsewhere/devcontainer.json"),
+            "/elsewhere/devcontainer.json"
+        );
+    }
+
+    #[test]
+    fn test_pod_name_for() {
+        assert_eq!(pod_name_for("/src/mono", None), "mono");
+        assert_eq!(
+            pod_name_for("/src/mono", Some(".devcontainer/devcontainer.json")),
+            "mono"
+        );
+        assert_eq!(
+            pod_name_for("/src/mono", Some(".devcontainer/api/devcontainer.json")),
+            "mono-api"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+use crate::config::pod_state::PodStateConfig;
+use crate::devcontainer::config::ConfigSnapshot;
+use crate::devcontainer::discovery;
+use crate::state::{AppState, AppStateInner};
+use crate::types::{ConfigDrift, PodConfigDriftEvent};
+
+/// Quiet period after a change before re-checking, so a `git pull` touching
+/// several files results in a single check.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// Watches the files a pod's container is built from. Dropping it stops the
+/// watcher and its check task.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Check a pod for config drift now, then again whenever its devcontainer.json,
+/// Dockerfile or compose files change. Replaces any existing watcher for the
+/// pod. A change to devcontainer.json itself re-derives the watched files, as
+/// it may point at a different Dockerfile or compose files.
+pub fn watch(state: &mut AppStateInner, app_state: &AppState, app: &AppHandle, pod_id: &str) {
+    state.config_watchers.remove(pod_id);
+    let Some(pod) = state.pods.get(pod_id) else {
+        return;
+    };
+    let Some(config_file) =
+        discovery::resolve_config_path(&pod.project_path, pod.config_path.as_deref())
+    else {
+        return;
+    };
+    let Some(config_dir) = config_file.parent().map(PathBuf::from) else {
+        return;
+    };
+    let files: Vec<PathBuf> = ConfigSnapshot::take(&pod.project_path, pod.config_path.as_deref())
+        .map(|s| s.files.into_keys().map(|f| config_dir.join(f)).collect())
+        .unwrap_or_default();
+    let names: HashSet<OsString> = files
+        .iter()
+        .filter_map(|f| f.file_name().map(|n| n.to_os_string()))
+        .collect();
+    let config_name = config_file.file_name().map(|n| n.to_os_string());
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let handler = move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        // Our own reads show up as access events
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for name in event.paths.iter().filter_map(|p| p.file_name()) {
+            if config_name.as_deref() == Some(name) {
+                let _ = tx.send(true);
+            } else if names.contains(name) {
+                let _ = tx.send(false);
+            }
+        }
+    };
+    let mut watcher = match notify::recommended_watcher(handler) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Failed to watch config for pod {}: {}", pod_id, e);
+            return;
+        }
+    };
+    // Watch directories rather than files so replace-by-rename (git, most
+    // editors) is still seen
+    let dirs: HashSet<PathBuf> = files
+        .iter()
+        .filter_map(|f| f.parent().map(PathBuf::from))
+        .chain([config_dir])
+        .collect();
+    for dir in dirs {
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            tracing::warn!("Failed to watch {}: {}", dir.display(), e);
+        }
+    }
+    state
+        .config_watchers
+        .insert(pod_id.to_string(), ConfigWatcher { _watcher: watcher });
+
+    let app_state = app_state.clone();
+    let app = app.clone();
+    let pod_id = pod_id.to_string();
+    tokio::spawn(async move {
+        check(&app_state, &app, &pod_id).await;
+        // Ends when the watcher, and with it the sender, is dropped
+        while let Some(mut config_changed) = rx.recv().await {
+            tokio::time::sleep(SETTLE).await;
+            while let Ok(changed) = rx.try_recv() {
+                config_changed |= changed;
+            }
+            if config_changed {
+                // Starts over with the files the config now refers to
+                let mut state = app_state.lock().await;
+                if state.config_watchers.contains_key(&pod_id) {
+                    watch(&mut state, &app_state, &app, &pod_id);
+                }
+                return;
+            }
+            check(&app_state, &app, &pod_id).await;
+        }
+    });
+}
+
+/// Compare a pod's config on disk with the snapshot taken when its container
+/// was built, updating `pod.config_drift` and emitting `pod-config-drift`
+/// when the result changes.
+pub async fn check(state: &AppState, app: &AppHandle, pod_id: &str) {
+    let (name, project_path, config_path) = {
+        let state = state.lock().await;
+        match state.pods.get(pod_id) {
+            Some(pod) => (
+                pod.name.clone(),
+                pod.project_path.clone(),
+                pod.config_path.clone(),
+            ),
+            None => return,
+        }
+    };
+    let drift = PodStateConfig::load(&name)
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.config_snapshot)
+        .and_then(|built| {
+            let current =
+                ConfigSnapshot::take(&project_path, config_path.as_deref()).unwrap_or_default();
+            let changes = current.changes_since(&built);
+            (!changes.is_empty()).then(|| ConfigDrift {
+                changes,
+                detected_at: chrono::Utc::now().timestamp(),
+            })
+        });
+
+    let mut state = state.lock().await;
+    let Some(pod) = state.pods.get_mut(pod_id) else {
+        return;
+    };
+    let unchanged =
+        pod.config_drift.as_ref().map(|d| &d.changes) == drift.as_ref().map(|d| &d.changes);
+    if unchanged {
+        return;
+    }
+    pod.config_drift = drift.clone();
+    let _ = app.emit(
+        "pod-config-drift",
+        &PodConfigDriftEvent {
+            pod_id: pod_id.to_string(),
+            drift,
+        },
+    );
+}
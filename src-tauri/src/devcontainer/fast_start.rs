@@ -0,0 +1,102 @@
+use tauri::AppHandle;
+
+use crate::config::pod_state::PodStateConfig;
+use crate::devcontainer::lifecycle::{self, HookTarget, LifecycleHooks};
+use crate::devcontainer::{cli, config, native};
+use crate::docker::{compose, containers};
+use crate::state::AppState;
+use crate::types::{LifecycleHook, LogLevel};
+
+/// Start a pod's existing container directly, skipping `devcontainer up`.
+///
+/// Only taken when the pod still has its container and the devcontainer
+/// config fingerprint matches the one saved when it was built. Compose
+/// sidecars are started first, then the container, then `postStartCommand`
+/// from the container's metadata label.
+///
+/// Returns the same JSON result `devcontainer up` prints, or `None` when the
+/// caller should fall back to a full `up`.
+pub async fn try_fast_start(state: &AppState, app: &AppHandle, pod_id: &str) -> Option<String> {
+    let (docker, pod) = {
+        let s = state.lock().await;
+        (s.docker_for(pod_id)?, s.pods.get(pod_id)?.clone())
+    };
+    let container_id = pod.container_id.clone()?;
+    let saved = PodStateConfig::load(&pod.name).ok().flatten()?.config_hash;
+    let current = config::fingerprint(&pod.project_path, pod.config_path.as_deref())?;
+    if saved.is_empty() || saved != current {
+        return None;
+    }
+    let info = containers::inspect_container(&docker, &container_id)
+        .await
+        .ok()?;
+    let labels = info
+        .config
+        .as_ref()
+        .and_then(|c| c.labels.clone())
+        .unwrap_or_default();
+
+    let running = info.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+    if !running {
+        cli::emit_build_line(
+            app,
+            state,
+            pod_id,
+            "Config unchanged, starting existing container",
+            LogLevel::Stdout,
+        )
+        .await;
+        compose::start_services(&docker, &pod.services).await;
+        if let Err(e) = containers::start_container(&docker, &container_id).await {
+            tracing::info!(
+                "Fast start failed for pod {}, falling back to devcontainer up: {}",
+                pod_id,
+                e
+            );
+            return None;
+        }
+    }
+
+    let metadata = labels.get("devcontainer.metadata");
+    let label_meta = metadata.map(|m| containers::parse_devcontainer_metadata_label(m));
+    let json_meta =
+        containers::read_devcontainer_json(&pod.project_path, pod.config_path.as_deref());
+    let remote_user = label_meta
+        .as_ref()
+        .and_then(|m| m.remote_user.clone())
+        .or(json_meta.remote_user);
+    let workspace_folder = label_meta
+        .and_then(|m| m.remote_workspace_folder)
+        .or(json_meta.remote_workspace_folder)
+        .unwrap_or_else(|| native::default_workspace_target(&pod.project_path));
+
+    if !running {
+        let hooks = metadata
+            .map(|m| LifecycleHooks::from_metadata_label(m))
+            .unwrap_or_default();
+        let target = HookTarget {
+            container_id: &container_id,
+            user: remote_user.as_deref(),
+            working_dir: Some(&workspace_folder),
+        };
+        lifecycle::run_hook(
+            state,
+            app,
+            pod_id,
+            &target,
+            LifecycleHook::PostStart,
+            hooks.commands(LifecycleHook::PostStart),
+        )
+        .await;
+    }
+
+    Some(
+        serde_json::json!({
+            "outcome": "success",
+            "containerId": container_id,
+            "remoteUser": remote_user,
+            "remoteWorkspaceFolder": workspace_folder,
+        })
+        .to_string(),
+    )
+}
@@ -0,0 +1,173 @@
+/// Convert JSONC (JSON with comments and trailing commas, as used by
+/// devcontainer.json) into plain JSON.
+///
+/// Comments and trailing commas are replaced with spaces rather than removed,
+/// so line and column numbers reported by `serde_json` still point at the
+/// original text.
+pub fn to_json(input: &str) -> String {
+    let stripped = strip_comments(input);
+    strip_trailing_commas(&stripped)
+}
+
+fn strip_comments(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                // keep escaped char verbatim
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                // line comment — blank until newline
+                out.push(' ');
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                    out.push(blank(ch));
+                }
+            }
+            ('/', Some('*')) => {
+                // block comment — blank until */
+                out.push(' ');
+                out.push(' ');
+                chars.next();
+                while let Some(ch) = chars.next() {
+                    if ch == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        out.push(' ');
+                        out.push(' ');
+                        break;
+                    }
+                    out.push(blank(ch));
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn strip_trailing_commas(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut out = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(&next) = chars.get(i + 1) {
+                    out.push(next);
+                    i += 1;
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|ch| !ch.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                out.push(' ');
+            } else {
+                out.push(c);
+            }
+        } else {
+            out.push(c);
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Replace a comment character with whitespace, keeping line breaks.
+fn blank(c: char) -> char {
+    if c == '\n' || c == '\r' {
+        c
+    } else {
+        ' '
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_comments() {
+        let input = r#"{
+  // This is a comment
+  "remoteUser": "vscode",
+  /* block comment */
+  "workspaceFolder": "/workspaces/app"
+}"#;
+        let val: serde_json::Value = serde_json::from_str(&to_json(input)).unwrap();
+        assert_eq!(val.get("remoteUser").unwrap().as_str().unwrap(), "vscode");
+        assert_eq!(
+            val.get("workspaceFolder").unwrap().as_str().unwrap(),
+            "/workspaces/app"
+        );
+    }
+
+    #[test]
+    fn test_comments_in_strings_are_kept() {
+        let input = r#"{"url": "http://example.com", "glob": "/* not a comment */"}"#;
+        let val: serde_json::Value = serde_json::from_str(&to_json(input)).unwrap();
+        assert_eq!(
+            val.get("url").unwrap().as_str().unwrap(),
+            "http://example.com"
+        );
+        assert_eq!(
+            val.get("glob").unwrap().as_str().unwrap(),
+            "/* not a comment */"
+        );
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        let input = r#"{
+  "forwardPorts": [3000, 5432,],
+  "features": {
+    "ghcr.io/devcontainers/features/node:1": {}, // trailing
+  },
+}"#;
+        let val: serde_json::Value = serde_json::from_str(&to_json(input)).unwrap();
+        assert_eq!(val["forwardPorts"].as_array().unwrap().len(), 2);
+        assert!(val["features"].is_object());
+    }
+
+    #[test]
+    fn test_commas_in_strings_are_kept() {
+        let input = r#"{"postCreateCommand": "echo a,]",}"#;
+        let val: serde_json::Value = serde_json::from_str(&to_json(input)).unwrap();
+        assert_eq!(val["postCreateCommand"].as_str().unwrap(), "echo a,]");
+    }
+
+    #[test]
+    fn test_preserves_line_numbers() {
+        let input = "{\n  /* multi\n line */\n  \"image\": 42 oops\n}";
+        let err = serde_json::from_str::<serde_json::Value>(&to_json(input)).unwrap_err();
+        assert_eq!(err.line(), 4);
+    }
+}
@@ -0,0 +1,381 @@
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::future::join_all;
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter};
+
+use crate::devcontainer::config::LifecycleCommand;
+use crate::docker::{containers, logs};
+use crate::error::NookResult;
+use crate::state::AppState;
+use crate::types::{HookRun, LifecycleHook, LogEntry, LogLevel, LogSource, PodHookEvent};
+
+/// Lifecycle commands recorded in a container's `devcontainer.metadata`
+/// label, in the order the devcontainer CLI runs them: features first, then
+/// devcontainer.json itself.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleHooks {
+    pub on_create: Vec<LifecycleCommand>,
+    pub update_content: Vec<LifecycleCommand>,
+    pub post_create: Vec<LifecycleCommand>,
+    pub post_start: Vec<LifecycleCommand>,
+    pub post_attach: Vec<LifecycleCommand>,
+}
+
+impl LifecycleHooks {
+    pub fn from_metadata_label(label: &str) -> Self {
+        let mut hooks = Self::default();
+        let Ok(entries) = serde_json::from_str::<Vec<serde_json::Value>>(label) else {
+            return hooks;
+        };
+        for entry in &entries {
+            let command = |hook: LifecycleHook| {
+                entry
+                    .get(hook.config_key())
+                    .and_then(|v| serde_json::from_value::<LifecycleCommand>(v.clone()).ok())
+            };
+            hooks.on_create.extend(command(LifecycleHook::OnCreate));
+            hooks
+                .update_content
+                .extend(command(LifecycleHook::UpdateContent));
+            hooks.post_create.extend(command(LifecycleHook::PostCreate));
+            hooks.post_start.extend(command(LifecycleHook::PostStart));
+            hooks.post_attach.extend(command(LifecycleHook::PostAttach));
+        }
+        hooks
+    }
+
+    pub fn commands(&self, hook: LifecycleHook) -> &[LifecycleCommand] {
+        match hook {
+            LifecycleHook::OnCreate => &self.on_create,
+            LifecycleHook::UpdateContent => &self.update_content,
+            LifecycleHook::PostCreate => &self.post_create,
+            LifecycleHook::PostStart => &self.post_start,
+            LifecycleHook::PostAttach => &self.post_attach,
+        }
+    }
+}
+
+/// Read a container's lifecycle hooks from its metadata label.
+pub async fn container_hooks(docker: &Docker, container_id: &str) -> NookResult<LifecycleHooks> {
+    let info = containers::inspect_container(docker, container_id).await?;
+    Ok(info
+        .config
+        .and_then(|c| c.labels)
+        .and_then(|labels| {
+            labels
+                .get("devcontainer.metadata")
+                .map(|m| LifecycleHooks::from_metadata_label(m))
+        })
+        .unwrap_or_default())
+}
+
+/// Where, and as whom, hook commands run.
+pub struct HookTarget<'a> {
+    pub container_id: &'a str,
+    pub user: Option<&'a str>,
+    pub working_dir: Option<&'a str>,
+}
+
+/// Run a hook's commands in the container, streaming their output into the
+/// pod's log under `LogSource::Lifecycle`. The entries of a named command
+/// run at the same time; the first non-zero exit stops the hook. The run is
+/// recorded in `pod.hook_runs` and `pod-hook-finished` is emitted; a failed
+/// run sets the pod's warning rather than failing the pod.
+///
+/// Returns the exit code, or `None` if there was nothing to run.
+pub async fn run_hook(
+    state: &AppState,
+    app: &AppHandle,
+    pod_id: &str,
+    target: &HookTarget<'_>,
+    hook: LifecycleHook,
+    commands: &[LifecycleCommand],
+) -> Option<i64> {
+    let steps: Vec<Vec<Vec<String>>> = commands
+        .iter()
+        .map(argvs)
+        .filter(|step| !step.is_empty())
+        .collect();
+    if steps.is_empty() {
+        return None;
+    }
+    let (docker, runtime) = {
+        let s = state.lock().await;
+        (s.docker_for(pod_id)?, s.runtime_for(pod_id))
+    };
+    let default_user = match target.user {
+        Some(_) => None,
+        None => runtime.default_exec_user(&docker, target.container_id).await,
+    };
+    let target = &HookTarget {
+        container_id: target.container_id,
+        user: target.user.or(default_user.as_deref()),
+        working_dir: target.working_dir,
+    };
+    let log = HookLog { app, state, pod_id };
+
+    log.line(&format!("Running {}", hook.config_key()), LogLevel::Stdout)
+        .await;
+    let mut exit_code = 0;
+    for step in &steps {
+        let (docker, log) = (&docker, &log);
+        let runs = step.iter().map(|argv| async move {
+            match exec_logged(docker, target, argv, log).await {
+                Ok(code) => code,
+                Err(e) => {
+                    log.line(
+                        &format!("Failed to run {:?}: {}", argv, e),
+                        LogLevel::Stderr,
+                    )
+                    .await;
+                    -1
+                }
+            }
+        });
+        exit_code = merged_exit_code(&join_all(runs).await);
+        if exit_code != 0 {
+            break;
+        }
+    }
+    let level = if exit_code == 0 {
+        LogLevel::Stdout
+    } else {
+        LogLevel::Stderr
+    };
+    log.line(
+        &format!("{} exited with code {}", hook.config_key(), exit_code),
+        level,
+    )
+    .await;
+
+    let run = HookRun {
+        hook,
+        exit_code,
+        finished_at: chrono::Utc::now().timestamp(),
+    };
+    let mut state = state.lock().await;
+    if let Some(pod) = state.pods.get_mut(pod_id) {
+        pod.hook_runs.retain(|r| r.hook != hook);
+        pod.hook_runs.push(run.clone());
+        pod.warning = hook_warning(&pod.hook_runs);
+        let _ = app.emit(
+            "pod-hook-finished",
+            &PodHookEvent {
+                pod_id: pod_id.to_string(),
+                run,
+                warning: pod.warning.clone(),
+            },
+        );
+    }
+    Some(exit_code)
+}
+
+/// Run the hooks `devcontainer up --skip-post-create` leaves to Nook, as
+/// listed in the container's metadata label: the creation hooks if the
+/// container is new, then `postStartCommand`. A failed hook skips the rest,
+/// as it fails `up` in the devcontainer CLI.
+pub async fn run_up_hooks(
+    state: &AppState,
+    app: &AppHandle,
+    pod_id: &str,
+    target: &HookTarget<'_>,
+    created: bool,
+) {
+    let Some(docker) = state.lock().await.docker_for(pod_id) else {
+        return;
+    };
+    let hooks = match container_hooks(&docker, target.container_id).await {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            tracing::warn!("Failed to read lifecycle hooks for pod {}: {}", pod_id, e);
+            return;
+        }
+    };
+    let creation = [
+        LifecycleHook::OnCreate,
+        LifecycleHook::UpdateContent,
+        LifecycleHook::PostCreate,
+    ];
+    let due = creation
+        .into_iter()
+        .filter(|_| created)
+        .chain([LifecycleHook::PostStart]);
+    for hook in due {
+        let exit_code = run_hook(state, app, pod_id, target, hook, hooks.commands(hook)).await;
+        if exit_code.is_some_and(|code| code != 0) {
+            break;
+        }
+    }
+}
+
+/// Warning for the first failed hook run, if any.
+pub fn hook_warning(runs: &[HookRun]) -> Option<String> {
+    runs.iter().find(|r| r.exit_code != 0).map(|r| {
+        format!(
+            "{} failed with exit code {}",
+            r.hook.config_key(),
+            r.exit_code
+        )
+    })
+}
+
+/// Exit code of commands run side by side: the first failure, or 0.
+fn merged_exit_code(codes: &[i64]) -> i64 {
+    codes.iter().copied().find(|&code| code != 0).unwrap_or(0)
+}
+
+/// Argument vectors for a lifecycle command, run in parallel. Shell strings
+/// run through `/bin/sh -c`; named commands give one vector per entry.
+pub fn argvs(command: &LifecycleCommand) -> Vec<Vec<String>> {
+    match command {
+        LifecycleCommand::Shell(script) => {
+            vec![vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                script.clone(),
+            ]]
+        }
+        LifecycleCommand::Exec(argv) if argv.is_empty() => Vec::new(),
+        LifecycleCommand::Exec(argv) => vec![argv.clone()],
+        LifecycleCommand::Parallel(named) => named.values().flat_map(argvs).collect(),
+    }
+}
+
+struct HookLog<'a> {
+    app: &'a AppHandle,
+    state: &'a AppState,
+    pod_id: &'a str,
+}
+
+impl HookLog<'_> {
+    async fn line(&self, line: &str, level: LogLevel) {
+        self.lines(&[line], level).await;
+    }
+
+    async fn lines(&self, lines: &[&str], level: LogLevel) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let entries = lines
+            .iter()
+            .map(|line| LogEntry {
+                timestamp,
+                message: line.to_string(),
+                source: LogSource::Lifecycle,
+                level: level.clone(),
+                service: None,
+            })
+            .collect();
+        logs::append_logs(self.app, self.state, self.pod_id, entries).await;
+    }
+}
+
+/// Run one command, streaming its output to the log. Returns the exit code.
+async fn exec_logged(
+    docker: &Docker,
+    target: &HookTarget<'_>,
+    argv: &[String],
+    log: &HookLog<'_>,
+) -> NookResult<i64> {
+    let exec = docker
+        .create_exec(
+            target.container_id,
+            CreateExecOptions {
+                cmd: Some(argv.to_vec()),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                user: target.user.map(String::from),
+                working_dir: target.working_dir.map(String::from),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    if let StartExecResults::Attached { mut output, .. } = docker.start_exec(&exec.id, None).await?
+    {
+        while let Some(chunk) = output.next().await {
+            let (message, level) = match chunk? {
+                LogOutput::StdErr { message } => (message, LogLevel::Stderr),
+                LogOutput::StdOut { message } | LogOutput::Console { message } => {
+                    (message, LogLevel::Stdout)
+                }
+                LogOutput::StdIn { .. } => continue,
+            };
+            let text = String::from_utf8_lossy(&message);
+            log.lines(&text.lines().collect::<Vec<_>>(), level).await;
+        }
+    }
+
+    // No exit code means the command's outcome is unknown; count it as failed
+    Ok(docker.inspect_exec(&exec.id).await?.exit_code.unwrap_or(-1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_from_metadata_label() {
+        let label = r#"[
+            {"id": "ghcr.io/devcontainers/features/node:1", "postStartCommand": ["node", "--version"]},
+            {"remoteUser": "node", "onCreateCommand": "npm ci", "postStartCommand": "npm start", "postAttachCommand": {"a": "echo a"}}
+        ]"#;
+        let hooks = LifecycleHooks::from_metadata_label(label);
+        assert_eq!(
+            hooks.commands(LifecycleHook::PostStart),
+            &[
+                LifecycleCommand::Exec(vec!["node".to_string(), "--version".to_string()]),
+                LifecycleCommand::Shell("npm start".to_string()),
+            ]
+        );
+        assert_eq!(hooks.post_attach.len(), 1);
+        assert_eq!(
+            hooks.commands(LifecycleHook::OnCreate),
+            &[LifecycleCommand::Shell("npm ci".to_string())]
+        );
+        assert!(hooks.post_create.is_empty());
+
+        assert!(LifecycleHooks::from_metadata_label("not json")
+            .post_start
+            .is_empty());
+    }
+
+    #[test]
+    fn test_argvs() {
+        let shell = LifecycleCommand::Shell("npm install".to_string());
+        assert_eq!(argvs(&shell), vec![vec!["/bin/sh", "-c", "npm install"]]);
+
+        let named: LifecycleCommand =
+            serde_json::from_str(r#"{ "deps": ["npm", "ci"], "db": "make migrate", "noop": [] }"#)
+                .unwrap();
+        assert_eq!(
+            argvs(&named),
+            vec![vec!["/bin/sh", "-c", "make migrate"], vec!["npm", "ci"]]
+        );
+    }
+
+    #[test]
+    fn test_merged_exit_code() {
+        assert_eq!(merged_exit_code(&[0, 0]), 0);
+        assert_eq!(merged_exit_code(&[0, 2, 1]), 2);
+        assert_eq!(merged_exit_code(&[]), 0);
+    }
+
+    #[test]
+    fn test_hook_warning() {
+        let run = |hook, exit_code| HookRun {
+            hook,
+            exit_code,
+            finished_at: 0,
+        };
+        assert_eq!(hook_warning(&[run(LifecycleHook::PostStart, 0)]), None);
+        assert_eq!(
+            hook_warning(&[
+                run(LifecycleHook::PostStart, 0),
+                run(LifecycleHook::PostAttach, 127)
+            ])
+            .as_deref(),
+            Some("postAttachCommand failed with exit code 127")
+        );
+    }
+}
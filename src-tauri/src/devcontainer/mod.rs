@@ -1 +1,9 @@
 pub mod cli;
+pub mod config;
+pub mod discovery;
+pub mod drift;
+pub mod fast_start;
+pub mod jsonc;
+pub mod lifecycle;
+pub mod native;
+pub mod ports;
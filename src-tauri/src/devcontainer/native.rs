@@ -0,0 +1,831 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use bollard::container::{Config, CreateContainerOptions, ListContainersOptions};
+use bollard::image::{BuildImageOptions, CreateImageOptions};
+use bollard::models::{HostConfig, Mount as DockerMount, MountTypeEnum};
+use bollard::Docker;
+use futures_util::StreamExt;
+use regex::Regex;
+use tauri::AppHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::devcontainer::cli::{self, CliOptions};
+use crate::devcontainer::config::{self, DevcontainerConfig, Mount};
+use crate::devcontainer::discovery;
+use crate::devcontainer::lifecycle::{self, HookTarget};
+use crate::docker::containers;
+use crate::error::{NookError, NookResult};
+use crate::state::AppState;
+use crate::types::{LifecycleHook, LogLevel};
+
+// Labels shared with the devcontainer CLI, so VS Code and `list_containers`
+// recognise containers created here.
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+const CONFIG_FILE_LABEL: &str = "devcontainer.config_file";
+const METADATA_LABEL: &str = "devcontainer.metadata";
+
+/// The keep-alive command the devcontainer CLI uses for `overrideCommand`.
+const KEEP_ALIVE: &str =
+    "echo Container started\ntrap \"exit 0\" 15\nwhile sleep 1000 & wait $!; do :; done";
+
+/// Name a Dockerfile outside the build context is stored under in the archive.
+const OUTSIDE_DOCKERFILE: &str = ".nook.Dockerfile";
+
+/// The pod's build log, where all progress output goes.
+struct BuildLog<'a> {
+    pod_id: &'a str,
+    app: &'a AppHandle,
+    state: &'a AppState,
+}
+
+impl BuildLog<'_> {
+    async fn line(&self, line: &str, level: LogLevel) {
+        cli::emit_build_line(self.app, self.state, self.pod_id, line, level).await;
+    }
+
+    async fn info(&self, line: &str) {
+        self.line(line, LogLevel::Stdout).await;
+    }
+}
+
+/// Bring up a pod's devcontainer through the Docker API, without the
+/// devcontainer CLI. An existing container for the config is reused unless
+/// `rebuild` is set; otherwise the image is pulled or built, the container
+/// created and `postCreateCommand` run. `postStartCommand` runs whenever the
+/// container is started. Progress is written to the pod's build log, hook
+/// output to its lifecycle log; a failing hook leaves a warning on the pod.
+///
+/// Returns the same JSON result `devcontainer up` prints, so callers can
+/// handle both engines alike.
+pub async fn up(
+    project_path: &str,
+    config_path: Option<&str>,
+    rebuild: bool,
+    pod_id: &str,
+    app: &AppHandle,
+    state: &AppState,
+    cancel: CancellationToken,
+) -> NookResult<String> {
+    let (docker, timeout) = {
+        let s = state.lock().await;
+        let docker = s
+            .docker_for(pod_id)
+            .ok_or_else(|| NookError::DockerConnection("Docker not connected".to_string()))?;
+        (docker, CliOptions::from_settings(&s.settings).timeout)
+    };
+    let log = BuildLog { pod_id, app, state };
+
+    let work = cli::with_timeout(
+        timeout,
+        "Container build",
+        bring_up(&docker, project_path, config_path, rebuild, &log),
+    );
+    tokio::select! {
+        _ = cancel.cancelled() => Err(NookError::Cancelled("Build cancelled".to_string())),
+        result = work => result,
+    }
+}
+
+async fn bring_up(
+    docker: &Docker,
+    project_path: &str,
+    config_path: Option<&str>,
+    rebuild: bool,
+    log: &BuildLog<'_>,
+) -> NookResult<String> {
+    let config_file =
+        discovery::resolve_config_path(project_path, config_path).ok_or_else(|| {
+            NookError::ConfigError(format!("No devcontainer.json found in {}", project_path))
+        })?;
+    let config = config::load(project_path, config_path)?;
+    if !config.compose_files().is_empty() {
+        return Err(NookError::DevcontainerUpFailed(
+            "Docker Compose configs are not supported by the native engine; use the devcontainer CLI"
+                .to_string(),
+        ));
+    }
+    let unsupported = unsupported_keys(&config);
+    if !unsupported.is_empty() {
+        log.line(
+            &format!(
+                "Native engine ignores: {} (switch the pod to the devcontainer CLI engine to use them)",
+                unsupported.join(", ")
+            ),
+            LogLevel::Stderr,
+        )
+        .await;
+    }
+
+    let config_file_str = config_file.to_string_lossy().to_string();
+    let workspace_folder = config
+        .workspace_folder
+        .clone()
+        .unwrap_or_else(|| default_workspace_target(project_path));
+
+    let (container_id, created) =
+        match find_container(docker, project_path, &config_file_str).await? {
+            Some(id) if !rebuild => (id, false),
+            existing => {
+                // Image and container first, so a failed build, pull or create
+                // leaves the old container in place
+                let image = prepare_image(docker, &config, &config_file, project_path, log).await?;
+                log.info("Creating container").await;
+                let id = create_container(docker, &config, &image, project_path, &config_file_str)
+                    .await?;
+                if let Some(old) = existing {
+                    log.info("Removing existing container").await;
+                    if let Err(e) = containers::remove_container(docker, &old, false).await {
+                        let _ = containers::remove_container(docker, &id, false).await;
+                        return Err(e);
+                    }
+                }
+                (id, true)
+            }
+        };
+
+    let running = containers::inspect_container(docker, &container_id)
+        .await?
+        .state
+        .and_then(|s| s.running)
+        .unwrap_or(false);
+    if !running {
+        log.info("Starting container").await;
+        containers::start_container(docker, &container_id).await?;
+    }
+
+    let user = config
+        .remote_user
+        .as_deref()
+        .or(config.container_user.as_deref());
+    let target = HookTarget {
+        container_id: &container_id,
+        user,
+        working_dir: Some(&workspace_folder),
+    };
+    let hooks = [
+        (
+            LifecycleHook::PostCreate,
+            &config.post_create_command,
+            created,
+        ),
+        (
+            LifecycleHook::PostStart,
+            &config.post_start_command,
+            !running,
+        ),
+    ];
+    for (hook, command, due) in hooks {
+        if let Some(command) = command.as_ref().filter(|_| due) {
+            lifecycle::run_hook(
+                log.state,
+                log.app,
+                log.pod_id,
+                &target,
+                hook,
+                std::slice::from_ref(command),
+            )
+            .await;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "outcome": "success",
+        "containerId": container_id,
+        "remoteUser": user,
+        "remoteWorkspaceFolder": workspace_folder,
+    })
+    .to_string())
+}
+
+/// Config keys the native engine doesn't apply. Logged so a partially
+/// applied config isn't silent.
+fn unsupported_keys(config: &DevcontainerConfig) -> Vec<&'static str> {
+    let checks = [
+        ("features", !config.features.is_empty()),
+        ("runArgs", !config.run_args.is_empty()),
+        ("initializeCommand", config.initialize_command.is_some()),
+        ("onCreateCommand", config.on_create_command.is_some()),
+        (
+            "updateContentCommand",
+            config.update_content_command.is_some(),
+        ),
+    ];
+    checks
+        .into_iter()
+        .filter(|(_, present)| *present)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// The container created for this project and config, by the native engine
+/// or the devcontainer CLI.
+async fn find_container(
+    docker: &Docker,
+    project_path: &str,
+    config_file: &str,
+) -> NookResult<Option<String>> {
+    let folder_filter = format!("{}={}", LOCAL_FOLDER_LABEL, project_path);
+    let config_filter = format!("{}={}", CONFIG_FILE_LABEL, config_file);
+    let filters: HashMap<&str, Vec<&str>> = HashMap::from([(
+        "label",
+        vec![folder_filter.as_str(), config_filter.as_str()],
+    )]);
+    let containers = docker
+        .list_containers(Some(ListContainersOptions {
+            all: true,
+            filters,
+            ..Default::default()
+        }))
+        .await?;
+    Ok(containers.into_iter().find_map(|c| c.id))
+}
+
+/// Pull `image`, or build the config's Dockerfile. Returns the image to run.
+async fn prepare_image(
+    docker: &Docker,
+    config: &DevcontainerConfig,
+    config_file: &Path,
+    project_path: &str,
+    log: &BuildLog<'_>,
+) -> NookResult<String> {
+    if let Some(dockerfile) = config.dockerfile() {
+        let config_dir = config_file.parent().unwrap_or(Path::new(project_path));
+        let context = config
+            .build
+            .as_ref()
+            .and_then(|b| b.context.as_deref())
+            .or(config.context.as_deref())
+            .unwrap_or(".");
+        let tag = image_tag(project_path, config_file);
+        build_image(
+            docker,
+            config,
+            &config_dir.join(context),
+            &config_dir.join(dockerfile),
+            &tag,
+            log,
+        )
+        .await?;
+        Ok(tag)
+    } else if let Some(image) = &config.image {
+        pull_image(docker, image, log).await?;
+        Ok(image.clone())
+    } else {
+        Err(NookError::ConfigError(
+            "devcontainer.json has neither an image nor a Dockerfile".to_string(),
+        ))
+    }
+}
+
+/// Pull an image unless it's already present locally.
+async fn pull_image(docker: &Docker, image: &str, log: &BuildLog<'_>) -> NookResult<()> {
+    if docker.inspect_image(image).await.is_ok() {
+        return Ok(());
+    }
+    fetch_image(docker, image, log).await
+}
+
+/// Pull the images a pod's config builds on again, even when present
+/// locally, so the next rebuild picks up updated base images: its `image`,
+/// or the `FROM` images of its Dockerfile. Images of Docker Compose configs
+/// are left to Compose. Progress goes to the pod's build log.
+pub async fn pull_base_images(
+    project_path: &str,
+    config_path: Option<&str>,
+    pod_id: &str,
+    app: &AppHandle,
+    state: &AppState,
+) -> NookResult<()> {
+    let docker = state
+        .lock()
+        .await
+        .docker_for(pod_id)
+        .ok_or_else(|| NookError::DockerConnection("Docker not connected".to_string()))?;
+    let log = BuildLog { pod_id, app, state };
+    let config = config::load(project_path, config_path)?;
+    let images = match (&config.image, config.dockerfile()) {
+        (_, Some(dockerfile)) => {
+            let config_file = discovery::resolve_config_path(project_path, config_path)
+                .ok_or_else(|| {
+                    NookError::ConfigError(format!(
+                        "No devcontainer.json found in {}",
+                        project_path
+                    ))
+                })?;
+            let config_dir = config_file.parent().unwrap_or(Path::new(project_path));
+            let content = std::fs::read_to_string(config_dir.join(dockerfile)).map_err(|e| {
+                NookError::ConfigError(format!("Failed to read {}: {}", dockerfile, e))
+            })?;
+            base_images(&content)
+        }
+        (Some(image), None) => vec![image.clone()],
+        (None, None) => Vec::new(),
+    };
+    for image in images {
+        fetch_image(&docker, &image, &log).await?;
+    }
+    Ok(())
+}
+
+/// Images a Dockerfile's stages start from, skipping earlier stages,
+/// `scratch` and names built from build args.
+fn base_images(dockerfile: &str) -> Vec<String> {
+    let mut stages: Vec<String> = Vec::new();
+    let mut images: Vec<String> = Vec::new();
+    for line in dockerfile.lines() {
+        let mut words = line.split_whitespace();
+        if !words.next().is_some_and(|w| w.eq_ignore_ascii_case("FROM")) {
+            continue;
+        }
+        let mut words = words.filter(|w| !w.starts_with("--"));
+        let Some(image) = words.next() else {
+            continue;
+        };
+        let is_stage = stages.contains(&image.to_lowercase());
+        if words.next().is_some_and(|w| w.eq_ignore_ascii_case("AS")) {
+            if let Some(stage) = words.next() {
+                stages.push(stage.to_lowercase());
+            }
+        }
+        if is_stage
+            || image.contains('$')
+            || image.eq_ignore_ascii_case("scratch")
+            || images.iter().any(|i| i == image)
+        {
+            continue;
+        }
+        images.push(image.to_string());
+    }
+    images
+}
+
+/// Pull an image from its registry.
+async fn fetch_image(docker: &Docker, image: &str, log: &BuildLog<'_>) -> NookResult<()> {
+    log.info(&format!("Pulling {}", image)).await;
+    let (from_image, tag) = split_image_tag(image);
+    let options = CreateImageOptions {
+        from_image,
+        tag,
+        ..Default::default()
+    };
+    let mut stream = docker.create_image(Some(options), None, None);
+    while let Some(info) = stream.next().await {
+        let info = info?;
+        if let Some(error) = info.error {
+            return Err(NookError::DevcontainerUpFailed(error));
+        }
+        // Per-layer download progress is too chatty for the build log
+        if info.progress.is_some() {
+            continue;
+        }
+        if let Some(status) = info.status {
+            match info.id {
+                Some(id) => log.info(&format!("{}: {}", id, status)).await,
+                None => log.info(&status).await,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split `repo[:tag]` for the pull API. Digests stay in the name; a missing
+/// tag means `latest`, as with `docker pull`.
+fn split_image_tag(image: &str) -> (&str, &str) {
+    if image.contains('@') {
+        return (image, "");
+    }
+    let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
+/// Tag for a config's Dockerfile build: `nook-<folder>-<hash of config path>`.
+fn image_tag(project_path: &str, config_file: &Path) -> String {
+    // Persisted in image names, so the hash must not change between builds
+    let hash = config::hash_hex(config_file.to_string_lossy().as_bytes());
+    let folder: String = Path::new(project_path)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("nook-{}-{}", folder, &hash[..8])
+}
+
+async fn build_image(
+    docker: &Docker,
+    config: &DevcontainerConfig,
+    context: &Path,
+    dockerfile: &Path,
+    tag: &str,
+    log: &BuildLog<'_>,
+) -> NookResult<()> {
+    log.info(&format!("Building image {}", tag)).await;
+    let (context, dockerfile) = (context.to_path_buf(), dockerfile.to_path_buf());
+    let (archive, dockerfile_name) =
+        tokio::task::spawn_blocking(move || build_context(&context, &dockerfile))
+            .await
+            .map_err(|e| NookError::Other(format!("Build context task failed: {}", e)))??;
+
+    let build = config.build.clone().unwrap_or_default();
+    let options = BuildImageOptions {
+        dockerfile: dockerfile_name,
+        t: tag.to_string(),
+        buildargs: build.args.into_iter().collect(),
+        target: build.target.unwrap_or_default(),
+        cachefrom: build.cache_from.map(|c| c.to_vec()).unwrap_or_default(),
+        rm: true,
+        ..Default::default()
+    };
+    let mut stream = docker.build_image(options, None, Some(archive.into()));
+    while let Some(info) = stream.next().await {
+        let info = info?;
+        if let Some(error) = info.error {
+            return Err(NookError::DevcontainerUpFailed(error));
+        }
+        if let Some(text) = info.stream {
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                log.info(line).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tar up a build context, skipping `.dockerignore` matches. A Dockerfile
+/// outside the context (or ignored by it) is added as `OUTSIDE_DOCKERFILE`.
+/// Returns the archive and the Dockerfile's path inside it.
+fn build_context(context: &Path, dockerfile: &Path) -> NookResult<(Vec<u8>, String)> {
+    let context = context.canonicalize().map_err(|e| {
+        NookError::ConfigError(format!("Build context {}: {}", context.display(), e))
+    })?;
+    let dockerfile = dockerfile.canonicalize().map_err(|e| {
+        NookError::ConfigError(format!("Dockerfile {}: {}", dockerfile.display(), e))
+    })?;
+    let ignore = DockerIgnore::load(&context);
+
+    let mut archive = tar::Builder::new(Vec::new());
+    archive.follow_symlinks(false);
+    add_dir(&mut archive, &context, Path::new(""), &ignore)?;
+
+    let dockerfile_name = match dockerfile.strip_prefix(&context) {
+        Ok(rel) if !ignore.is_ignored(rel) => rel.to_string_lossy().to_string(),
+        _ => {
+            archive.append_path_with_name(&dockerfile, OUTSIDE_DOCKERFILE)?;
+            OUTSIDE_DOCKERFILE.to_string()
+        }
+    };
+    Ok((archive.into_inner()?, dockerfile_name))
+}
+
+fn add_dir(
+    archive: &mut tar::Builder<Vec<u8>>,
+    root: &Path,
+    rel: &Path,
+    ignore: &DockerIgnore,
+) -> NookResult<()> {
+    for entry in std::fs::read_dir(root.join(rel))? {
+        let entry = entry?;
+        let rel_path = rel.join(entry.file_name());
+        if ignore.is_ignored(&rel_path) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            archive.append_dir(&rel_path, entry.path())?;
+            add_dir(archive, root, &rel_path, ignore)?;
+        } else {
+            archive.append_path_with_name(entry.path(), &rel_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// `.dockerignore` patterns with `*`, `?`, `**` and `!` exceptions; the last
+/// matching pattern wins. Ignored directories are skipped whole, so an
+/// exception can't re-include a file below an ignored directory.
+#[derive(Default)]
+struct DockerIgnore {
+    /// (pattern, true to exclude / false for a `!` exception)
+    patterns: Vec<(Regex, bool)>,
+}
+
+impl DockerIgnore {
+    fn load(context: &Path) -> Self {
+        std::fs::read_to_string(context.join(".dockerignore"))
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    fn parse(text: &str) -> Self {
+        let patterns = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .filter_map(|line| {
+                let (exclude, pattern) = match line.strip_prefix('!') {
+                    Some(p) => (false, p.trim()),
+                    None => (true, line),
+                };
+                let pattern = pattern
+                    .trim_start_matches("./")
+                    .trim_start_matches('/')
+                    .trim_end_matches('/');
+                glob_regex(pattern).map(|re| (re, exclude))
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        self.patterns
+            .iter()
+            .rev()
+            .find(|(re, _)| re.is_match(&path))
+            .is_some_and(|(_, exclude)| *exclude)
+    }
+}
+
+/// Anchored regex for a `.dockerignore` glob. A pattern also matches
+/// everything below a matching directory.
+fn glob_regex(pattern: &str) -> Option<Regex> {
+    let mut re = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    re.push_str("(/.*)?$");
+    Regex::new(&re).ok()
+}
+
+async fn create_container(
+    docker: &Docker,
+    config: &DevcontainerConfig,
+    image: &str,
+    project_path: &str,
+    config_file: &str,
+) -> NookResult<String> {
+    let workspace_mount = match &config.workspace_mount {
+        Some(spec) => parse_mount_spec(spec)
+            .ok_or_else(|| NookError::ConfigError(format!("Invalid workspaceMount: {}", spec)))?,
+        None => DockerMount {
+            source: Some(project_path.to_string()),
+            target: Some(default_workspace_target(project_path)),
+            typ: Some(MountTypeEnum::BIND),
+            ..Default::default()
+        },
+    };
+    let mut mounts = vec![workspace_mount];
+    mounts.extend(config.mounts.iter().filter_map(docker_mount));
+
+    let labels = HashMap::from([
+        (LOCAL_FOLDER_LABEL.to_string(), project_path.to_string()),
+        (CONFIG_FILE_LABEL.to_string(), config_file.to_string()),
+        (METADATA_LABEL.to_string(), metadata_label(config)),
+    ]);
+    let env: Vec<String> = config
+        .container_env
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    let (entrypoint, cmd) = if config.override_command.unwrap_or(true) {
+        (
+            Some(vec!["/bin/sh".to_string()]),
+            Some(vec![
+                "-c".to_string(),
+                KEEP_ALIVE.to_string(),
+                "-".to_string(),
+            ]),
+        )
+    } else {
+        (None, None)
+    };
+
+    let host_config = HostConfig {
+        mounts: Some(mounts),
+        init: config.init,
+        privileged: config.privileged,
+        cap_add: Some(config.cap_add.clone()).filter(|c| !c.is_empty()),
+        security_opt: Some(config.security_opt.clone()).filter(|s| !s.is_empty()),
+        ..Default::default()
+    };
+    let container_config = Config {
+        image: Some(image.to_string()),
+        env: Some(env),
+        labels: Some(labels),
+        user: config.container_user.clone(),
+        entrypoint,
+        cmd,
+        host_config: Some(host_config),
+        ..Default::default()
+    };
+    let response = docker
+        .create_container(None::<CreateContainerOptions<String>>, container_config)
+        .await?;
+    Ok(response.id)
+}
+
+/// Where the project is mounted when `workspaceMount` isn't set, matching the
+/// devcontainer CLI: `/workspaces/<folder name>`.
+pub fn default_workspace_target(project_path: &str) -> String {
+    let folder = Path::new(project_path)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("/workspaces/{}", folder)
+}
+
+fn docker_mount(mount: &Mount) -> Option<DockerMount> {
+    match mount {
+        Mount::Spec(spec) => parse_mount_spec(spec),
+        Mount::Object {
+            source,
+            target,
+            mount_type,
+        } => Some(DockerMount {
+            source: source.clone(),
+            target: Some(target.clone()),
+            typ: Some(mount_type_from(mount_type)?),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Parse a `--mount` style spec such as `source=cache,target=/cache,type=volume`.
+/// Options Docker ignores on Linux (like `consistency`) are dropped.
+fn parse_mount_spec(spec: &str) -> Option<DockerMount> {
+    let mut mount = DockerMount::default();
+    for part in spec.split(',') {
+        let (key, value) = part
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .unwrap_or((part.trim(), "true"));
+        match key {
+            "source" | "src" => mount.source = Some(value.to_string()),
+            "target" | "destination" | "dst" => mount.target = Some(value.to_string()),
+            "type" => mount.typ = Some(mount_type_from(value)?),
+            "readonly" | "ro" => mount.read_only = Some(value != "false" && value != "0"),
+            _ => {}
+        }
+    }
+    mount.target.as_ref()?;
+    mount.typ.get_or_insert(MountTypeEnum::VOLUME);
+    Some(mount)
+}
+
+fn mount_type_from(value: &str) -> Option<MountTypeEnum> {
+    match value {
+        "bind" => Some(MountTypeEnum::BIND),
+        "volume" => Some(MountTypeEnum::VOLUME),
+        "tmpfs" => Some(MountTypeEnum::TMPFS),
+        _ => None,
+    }
+}
+
+/// `devcontainer.metadata` in the devcontainer CLI's format: a JSON array of
+/// config fragments, read back by VS Code and `list_containers`.
+fn metadata_label(config: &DevcontainerConfig) -> String {
+    let mut entry = serde_json::json!({
+        "remoteUser": config.remote_user,
+        "containerUser": config.container_user,
+        "remoteWorkspaceFolder": config.workspace_folder,
+        "remoteEnv": config.remote_env,
+        "postCreateCommand": config.post_create_command,
+        "postStartCommand": config.post_start_command,
+        "postAttachCommand": config.post_attach_command,
+    });
+    if let Some(fields) = entry.as_object_mut() {
+        fields.retain(|_, v| !(v.is_null() || v.as_object().is_some_and(|o| o.is_empty())));
+    }
+    serde_json::Value::Array(vec![entry]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_images() {
+        let dockerfile = "\
+ARG VARIANT=20
+FROM node:${VARIANT} AS deps
+FROM --platform=linux/amd64 golang:1.22 AS build
+from build AS tools
+FROM scratch
+FROM mcr.microsoft.com/devcontainers/base:bookworm
+COPY --from=build /go/bin /usr/local/bin
+";
+        assert_eq!(
+            base_images(dockerfile),
+            vec!["golang:1.22", "mcr.microsoft.com/devcontainers/base:bookworm"]
+        );
+    }
+
+    #[test]
+    fn test_split_image_tag() {
+        assert_eq!(split_image_tag("node:20"), ("node", "20"));
+        assert_eq!(split_image_tag("ubuntu"), ("ubuntu", "latest"));
+        assert_eq!(
+            split_image_tag("localhost:5000/team/app"),
+            ("localhost:5000/team/app", "latest")
+        );
+        assert_eq!(
+            split_image_tag("mcr.microsoft.com/devcontainers/base:bookworm"),
+            ("mcr.microsoft.com/devcontainers/base", "bookworm")
+        );
+        assert_eq!(
+            split_image_tag("alpine@sha256:abc"),
+            ("alpine@sha256:abc", "")
+        );
+    }
+
+    #[test]
+    fn test_image_tag_is_valid_docker_name() {
+        let tag = image_tag(
+            "/home/me/My Project",
+            Path::new("/home/me/My Project/.devcontainer/devcontainer.json"),
+        );
+        // Pinned: a different hash orphans every image built before
+        assert_eq!(tag, "nook-my-project-a9cc411f");
+        assert!(tag
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c)));
+    }
+
+    #[test]
+    fn test_parse_mount_spec() {
+        let mount =
+            parse_mount_spec("source=node_modules,target=/workspace/node_modules,type=volume")
+                .unwrap();
+        assert_eq!(mount.source.as_deref(), Some("node_modules"));
+        assert_eq!(mount.target.as_deref(), Some("/workspace/node_modules"));
+        assert_eq!(mount.typ, Some(MountTypeEnum::VOLUME));
+
+        let bind = parse_mount_spec("type=bind,src=/tmp,dst=/host-tmp,consistency=cached,readonly")
+            .unwrap();
+        assert_eq!(bind.typ, Some(MountTypeEnum::BIND));
+        assert_eq!(bind.read_only, Some(true));
+
+        assert!(parse_mount_spec("source=/tmp,type=bind").is_none());
+        assert!(parse_mount_spec("target=/x,type=weird").is_none());
+    }
+
+    #[test]
+    fn test_dockerignore() {
+        let ignore = DockerIgnore::parse("# deps\nnode_modules/\n*.log\n**/*.tmp\n!keep.log\n");
+        assert!(ignore.is_ignored(Path::new("node_modules")));
+        assert!(ignore.is_ignored(Path::new("node_modules/react/index.js")));
+        assert!(ignore.is_ignored(Path::new("debug.log")));
+        assert!(!ignore.is_ignored(Path::new("logs/debug.log")));
+        assert!(ignore.is_ignored(Path::new("a/b/c.tmp")));
+        assert!(ignore.is_ignored(Path::new("c.tmp")));
+        assert!(!ignore.is_ignored(Path::new("keep.log")));
+        assert!(!ignore.is_ignored(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn test_metadata_label_roundtrips() {
+        let config: DevcontainerConfig = serde_json::from_str(
+            r#"{ "image": "node:20", "remoteUser": "node", "workspaceFolder": "/src", "postStartCommand": "npm i" }"#,
+        )
+        .unwrap();
+        let label = metadata_label(&config);
+        assert!(!label.contains("null"));
+        assert!(!label.contains("remoteEnv"));
+        let meta = containers::parse_devcontainer_metadata_label(&label);
+        assert_eq!(meta.remote_user.as_deref(), Some("node"));
+        assert_eq!(meta.remote_workspace_folder.as_deref(), Some("/src"));
+    }
+
+    #[test]
+    fn test_unsupported_keys() {
+        let config: DevcontainerConfig = serde_json::from_str(
+            r#"{ "image": "node:20", "features": { "ghcr.io/devcontainers/features/git:1": {} }, "runArgs": ["--init"] }"#,
+        )
+        .unwrap();
+        assert_eq!(unsupported_keys(&config), vec!["features", "runArgs"]);
+    }
+}
@@ -0,0 +1,257 @@
+use regex::Regex;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::pod_state::PodStateConfig;
+use crate::config::settings::PortAction;
+use crate::devcontainer::config::{self, DevcontainerConfig, OnAutoForward, PortAttributes};
+use crate::network::expose::{self, ExposeTarget};
+use crate::network::scan;
+use crate::state::AppState;
+use crate::types::PortExposedEvent;
+
+#[derive(Clone)]
+enum PortMatcher {
+    Port(u16),
+    Range(u16, u16),
+    /// Non-numeric keys match against the listening process's command line
+    Process(Regex),
+}
+
+/// `portsAttributes` / `otherPortsAttributes` from devcontainer.json, ready
+/// for lookup by port.
+#[derive(Clone, Default)]
+pub struct PortRules {
+    rules: Vec<(PortMatcher, PortAttributes)>,
+    other: Option<PortAttributes>,
+}
+
+impl PortRules {
+    pub fn from_config(config: &DevcontainerConfig) -> Self {
+        let rules = config
+            .ports_attributes
+            .iter()
+            .filter_map(|(key, attrs)| {
+                let matcher = match key.split_once('-') {
+                    Some((start, end)) if config::is_port_pattern(key) => {
+                        PortMatcher::Range(start.parse().ok()?, end.parse().ok()?)
+                    }
+                    None if config::is_port_pattern(key) => PortMatcher::Port(key.parse().ok()?),
+                    _ => PortMatcher::Process(Regex::new(key).ok()?),
+                };
+                Some((matcher, attrs.clone()))
+            })
+            .collect();
+        Self {
+            rules,
+            other: config.other_ports_attributes.clone(),
+        }
+    }
+
+    /// Rules for a pod's devcontainer.json; empty if it can't be read.
+    pub fn load(project_path: &str, config_path: Option<&str>) -> Self {
+        config::load(project_path, config_path)
+            .map(|c| Self::from_config(&c))
+            .unwrap_or_default()
+    }
+
+    /// Whether any `portsAttributes` key matches by process rather than port.
+    pub fn has_process_rules(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|(m, _)| matches!(m, PortMatcher::Process(_)))
+    }
+
+    /// Attributes for a port. An exact port wins over a range, a range over a
+    /// process match, and `otherPortsAttributes` applies when nothing matches.
+    pub fn attributes_for(&self, port: u16, process: &str) -> Option<&PortAttributes> {
+        let exact = self
+            .rules
+            .iter()
+            .find(|(m, _)| matches!(m, PortMatcher::Port(p) if *p == port));
+        let range = || {
+            self.rules.iter().find(
+                |(m, _)| matches!(m, PortMatcher::Range(start, end) if (*start..=*end).contains(&port)),
+            )
+        };
+        let by_process = || {
+            self.rules.iter().find(|(m, _)| {
+                matches!(m, PortMatcher::Process(re) if !process.is_empty() && re.is_match(process))
+            })
+        };
+        exact
+            .or_else(range)
+            .or_else(by_process)
+            .map(|(_, attrs)| attrs)
+            .or(self.other.as_ref())
+    }
+}
+
+/// Map `onAutoForward` onto Nook's port action. Every value that forwards the
+/// port (with or without opening a browser) auto-exposes it.
+pub fn port_action(on_auto_forward: &OnAutoForward) -> PortAction {
+    match on_auto_forward {
+        OnAutoForward::Notify => PortAction::Prompt,
+        OnAutoForward::OpenBrowser
+        | OnAutoForward::OpenBrowserOnce
+        | OnAutoForward::OpenPreview
+        | OnAutoForward::Silent => PortAction::AutoExpose,
+        OnAutoForward::Ignore => PortAction::Ignore,
+    }
+}
+
+/// `forwardPorts` as (service, port) pairs. `service` is `None` for ports on
+/// the pod's own container, including `localhost:port` and the config's
+/// primary compose service. Invalid entries and duplicates are skipped.
+pub fn forward_targets(config: &DevcontainerConfig) -> Vec<(Option<String>, u16)> {
+    let mut targets: Vec<(Option<String>, u16)> = Vec::new();
+    for entry in &config.forward_ports {
+        let Some((host, port)) = entry.target() else {
+            continue;
+        };
+        let service = host
+            .filter(|h| *h != "localhost" && Some(*h) != config.service.as_deref())
+            .map(String::from);
+        if !targets.contains(&(service.clone(), port)) {
+            targets.push((service, port));
+        }
+    }
+    targets
+}
+
+/// Expose every `forwardPorts` entry of a pod's devcontainer.json, applying
+/// labels and protocols from `portsAttributes`, on the host ports the pod
+/// maps them to. Emits `port-exposed` for each.
+pub async fn forward_declared_ports(state: &AppState, app: &AppHandle, pod_id: &str) {
+    let (name, project_path, config_path, container_id, docker) = {
+        let state = state.lock().await;
+        match state.pods.get(pod_id) {
+            Some(pod) => (
+                pod.name.clone(),
+                pod.project_path.clone(),
+                pod.config_path.clone(),
+                pod.container_id.clone(),
+                state.docker_for(pod_id),
+            ),
+            None => return,
+        }
+    };
+    let pod_config = PodStateConfig::load(&name).ok().flatten();
+    let Ok(config) = config::load(&project_path, config_path.as_deref()) else {
+        return;
+    };
+    let rules = PortRules::from_config(&config);
+
+    // Process-keyed rules need whatever is already listening in the pod's
+    // container, as the port scanner sees it
+    let listening = match (docker, container_id) {
+        (Some(docker), Some(container_id)) if rules.has_process_rules() => {
+            scan::scan_container_ports(&docker, &container_id).await
+        }
+        _ => Vec::new(),
+    };
+
+    for (service, port) in forward_targets(&config) {
+        let process = listening
+            .iter()
+            .filter(|_| service.is_none())
+            .find(|lp| lp.port == port)
+            .and_then(|lp| lp.process_name.as_deref())
+            .unwrap_or("");
+        let attrs = rules.attributes_for(port, process);
+        let target = ExposeTarget {
+            container_port: port,
+            host_port: pod_config
+                .as_ref()
+                .map_or(port, |c| c.host_port(service.as_deref(), port)),
+            service: service.clone(),
+            protocol: attrs.and_then(|a| a.protocol.clone()),
+            label: attrs.and_then(|a| a.label.clone()),
+        };
+        match expose::expose_pod_port(state, pod_id, target).await {
+            Ok(exposed) => {
+                let _ = app.emit(
+                    "port-exposed",
+                    &PortExposedEvent {
+                        pod_id: pod_id.to_string(),
+                        port: exposed,
+                    },
+                );
+            }
+            Err(e) => {
+                tracing::warn!("Failed to forward port {} for pod {}: {}", port, pod_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from(json: &str) -> DevcontainerConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_attributes_precedence() {
+        let config = config_from(
+            r#"{
+                "portsAttributes": {
+                    "3000": { "label": "Web" },
+                    "3000-3100": { "label": "Range" },
+                    "node": { "label": "Node" }
+                },
+                "otherPortsAttributes": { "onAutoForward": "ignore" }
+            }"#,
+        );
+        let rules = PortRules::from_config(&config);
+        assert!(rules.has_process_rules());
+        let label = |port, process| {
+            rules
+                .attributes_for(port, process)
+                .and_then(|a| a.label.clone())
+        };
+        assert_eq!(label(3000, "node server.js").as_deref(), Some("Web"));
+        assert_eq!(label(3050, "node server.js").as_deref(), Some("Range"));
+        assert_eq!(label(8080, "node server.js").as_deref(), Some("Node"));
+        let other = rules.attributes_for(9000, "python").unwrap();
+        assert_eq!(other.on_auto_forward, Some(OnAutoForward::Ignore));
+    }
+
+    #[test]
+    fn test_no_rules() {
+        let rules = PortRules::default();
+        assert!(rules.attributes_for(3000, "node").is_none());
+        assert!(!rules.has_process_rules());
+    }
+
+    #[test]
+    fn test_port_action_mapping() {
+        assert_eq!(port_action(&OnAutoForward::Notify), PortAction::Prompt);
+        assert_eq!(port_action(&OnAutoForward::Silent), PortAction::AutoExpose);
+        assert_eq!(
+            port_action(&OnAutoForward::OpenBrowser),
+            PortAction::AutoExpose
+        );
+        assert_eq!(port_action(&OnAutoForward::Ignore), PortAction::Ignore);
+    }
+
+    #[test]
+    fn test_forward_targets() {
+        let config = config_from(
+            r#"{
+                "service": "app",
+                "forwardPorts": [3000, "db:5432", "app:8080", "localhost:9000", 3000, "bad:"]
+            }"#,
+        );
+        assert_eq!(
+            forward_targets(&config),
+            vec![
+                (None, 3000),
+                (Some("db".to_string()), 5432),
+                (None, 8080),
+                (None, 9000),
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use bollard::container::ListContainersOptions;
+use bollard::Docker;
+use futures_util::future::join_all;
+use tauri::{AppHandle, Emitter};
+
+use crate::docker::containers;
+use crate::error::NookResult;
+use crate::state::AppState;
+use crate::types::{PodService, PodServicesUpdate, PodStatus};
+
+pub const PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const SERVICE_LABEL: &str = "com.docker.compose.service";
+
+/// Compose project a container belongs to, from its labels.
+pub fn project_from_labels(labels: &HashMap<String, String>) -> Option<String> {
+    labels.get(PROJECT_LABEL).filter(|p| !p.is_empty()).cloned()
+}
+
+/// All sidecar containers of a compose project, sorted by service name.
+/// Devcontainers (labelled `devcontainer.local_folder`) are excluded.
+pub async fn list_services(docker: &Docker, project: &str) -> NookResult<Vec<PodService>> {
+    let project_filter = format!("{}={}", PROJECT_LABEL, project);
+    let filters: HashMap<&str, Vec<&str>> =
+        HashMap::from([("label", vec![project_filter.as_str()])]);
+    let options = ListContainersOptions {
+        all: true,
+        filters,
+        ..Default::default()
+    };
+
+    let mut services: Vec<PodService> = docker
+        .list_containers(Some(options))
+        .await?
+        .into_iter()
+        .filter_map(|c| {
+            let id = c.id?;
+            let labels = c.labels.unwrap_or_default();
+            // Devcontainers (this pod's or another's) aren't sidecars
+            if labels.contains_key("devcontainer.local_folder") {
+                return None;
+            }
+            let name = labels.get(SERVICE_LABEL).cloned().unwrap_or_default();
+            Some(PodService {
+                name,
+                container_name: c
+                    .names
+                    .and_then(|n| n.first().map(|n| n.trim_start_matches('/').to_string())),
+                image: c.image.unwrap_or_default(),
+                status: containers::status_from_state(c.state.as_deref().unwrap_or_default()),
+                container_id: id,
+                cpu_percent: 0.0,
+                memory_used: 0,
+                memory_limit: 0,
+            })
+        })
+        .collect();
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(services)
+}
+
+/// Re-read a pod's compose services from Docker and emit `pod-services-update`.
+/// Stats of services that are still running are kept.
+pub async fn refresh_services(state: &AppState, app: &AppHandle, pod_id: &str) {
+    let (docker, project) = {
+        let state = state.lock().await;
+        let Some(pod) = state.pods.get(pod_id) else {
+            return;
+        };
+        let (Some(docker), Some(project)) = (state.docker_for(pod_id), pod.compose_project.clone())
+        else {
+            return;
+        };
+        (docker, project)
+    };
+
+    let mut services = match list_services(&docker, &project).await {
+        Ok(services) => services,
+        Err(e) => {
+            tracing::warn!("Failed to list compose services for pod {}: {}", pod_id, e);
+            return;
+        }
+    };
+
+    let mut state = state.lock().await;
+    let Some(pod) = state.pods.get_mut(pod_id) else {
+        return;
+    };
+    for service in &mut services {
+        if service.status != PodStatus::Running {
+            continue;
+        }
+        if let Some(old) = pod
+            .services
+            .iter()
+            .find(|s| s.container_id == service.container_id)
+        {
+            service.cpu_percent = old.cpu_percent;
+            service.memory_used = old.memory_used;
+            service.memory_limit = old.memory_limit;
+        }
+    }
+    pod.services = services.clone();
+
+    let _ = app.emit(
+        "pod-services-update",
+        &PodServicesUpdate {
+            pod_id: pod_id.to_string(),
+            services,
+        },
+    );
+}
+
+/// Start every stopped service container in parallel. Errors are logged, not
+/// returned; `refresh_services` reports what actually came up.
+pub async fn start_services(docker: &Docker, services: &[PodService]) {
+    let stopped: Vec<&PodService> = services
+        .iter()
+        .filter(|s| s.status != PodStatus::Running)
+        .collect();
+    let results = join_all(
+        stopped
+            .iter()
+            .map(|s| containers::start_container(docker, &s.container_id)),
+    )
+    .await;
+    log_failures("start", stopped.into_iter().zip(results));
+}
+
+/// Stop every service container in parallel. Errors are logged, not returned,
+/// so one missing sidecar doesn't keep the rest running.
+pub async fn stop_services(docker: &Docker, services: &[PodService], timeout_secs: u64) {
+    let results = join_all(
+        services
+            .iter()
+            .map(|s| containers::stop_container(docker, &s.container_id, timeout_secs)),
+    )
+    .await;
+    log_failures("stop", services.iter().zip(results));
+}
+
+pub async fn kill_services(docker: &Docker, services: &[PodService]) {
+    let running: Vec<&PodService> = services
+        .iter()
+        .filter(|s| s.status == PodStatus::Running)
+        .collect();
+    let results = join_all(
+        running
+            .iter()
+            .map(|s| containers::kill_container(docker, &s.container_id)),
+    )
+    .await;
+    log_failures("kill", running.into_iter().zip(results));
+}
+
+pub async fn remove_services(docker: &Docker, services: &[PodService], remove_volumes: bool) {
+    let results = join_all(
+        services
+            .iter()
+            .map(|s| containers::remove_container(docker, &s.container_id, remove_volumes)),
+    )
+    .await;
+    log_failures("remove", services.iter().zip(results));
+}
+
+fn log_failures<'a>(action: &str, results: impl Iterator<Item = (&'a PodService, NookResult<()>)>) {
+    for (service, result) in results {
+        if let Err(e) = result {
+            tracing::warn!("Failed to {} service {}: {}", action, service.name, e);
+        }
+    }
+}
+
+/// Mark all services stopped and clear their stats.
+pub fn mark_stopped(services: &mut [PodService]) {
+    for service in services {
+        service.status = PodStatus::Stopped;
+        service.cpu_percent = 0.0;
+        service.memory_used = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, status: PodStatus) -> PodService {
+        PodService {
+            name: name.to_string(),
+            container_id: format!("{}-id", name),
+            container_name: None,
+            image: "postgres:16".to_string(),
+            status,
+            cpu_percent: 12.5,
+            memory_used: 1024,
+            memory_limit: 4096,
+        }
+    }
+
+    #[test]
+    fn test_project_from_labels() {
+        let labels = HashMap::from([(PROJECT_LABEL.to_string(), "myapp_devcontainer".to_string())]);
+        assert_eq!(
+            project_from_labels(&labels).as_deref(),
+            Some("myapp_devcontainer")
+        );
+
+        let empty = HashMap::from([(PROJECT_LABEL.to_string(), String::new())]);
+        assert_eq!(project_from_labels(&empty), None);
+        assert_eq!(project_from_labels(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_mark_stopped() {
+        let mut services = vec![
+            service("db", PodStatus::Running),
+            service("redis", PodStatus::Stopped),
+        ];
+        mark_stopped(&mut services);
+        for s in &services {
+            assert_eq!(s.status, PodStatus::Stopped);
+            assert_eq!(s.cpu_percent, 0.0);
+            assert_eq!(s.memory_used, 0);
+            assert_eq!(s.memory_limit, 4096);
+        }
+    }
+}
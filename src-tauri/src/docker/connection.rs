@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bollard::Docker;
+use tauri::{AppHandle, Emitter};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::pod_state::PodStateConfig;
+use crate::docker::endpoint::{self, DockerEndpoint, EndpointConnection};
+use crate::docker::runtime::RuntimeInfo;
+use crate::docker::{containers, events, monitor};
+use crate::error::NookResult;
+use crate::state::{AppState, AppStateInner, EndpointState};
+use crate::types::{DockerConnection, DockerConnectionStatus, Pod, PodStatus};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Delay before reconnect attempt `attempt` (1-based): 1s, 2s, 4s, ... up to
+/// 30s.
+pub fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(5)).min(MAX_BACKOFF)
+}
+
+/// Start a supervisor for every active endpoint (see `endpoint::active`) and
+/// stop those of endpoints that were deactivated or now point elsewhere.
+/// Pods of deactivated endpoints are dropped from the list; they come back
+/// when the endpoint is enabled again. Called at startup and whenever
+/// settings are saved.
+pub async fn apply_endpoints(state: &AppState, app: &AppHandle) {
+    let wanted = {
+        let settings = state.lock().await.settings.clone();
+        endpoint::active(&settings)
+    };
+
+    let mut s = state.lock().await;
+    let stale: Vec<String> = s
+        .endpoints
+        .values()
+        .filter(|e| !wanted.contains(&e.endpoint))
+        .map(|e| e.endpoint.name.clone())
+        .collect();
+    for name in stale {
+        if let Some(old) = s.endpoints.remove(&name) {
+            old.cancel.cancel();
+        }
+        stop_endpoint_pods(&mut s, &name);
+        if !wanted.iter().any(|e| e.name == name) {
+            tracing::info!("Docker endpoint {} disabled", name);
+            s.pods.retain(|_, p| p.endpoint_name() != name);
+        }
+    }
+
+    for endpoint in wanted {
+        if s.endpoints.contains_key(&endpoint.name) {
+            continue;
+        }
+        let name = endpoint.name.clone();
+        let endpoint_state = EndpointState::new(endpoint);
+        let cancel = endpoint_state.cancel.clone();
+        let _ = app.emit("docker-connection-changed", &endpoint_state.connection);
+        s.endpoints.insert(name.clone(), endpoint_state);
+        tokio::spawn(supervise(state.clone(), app.clone(), name, cancel));
+    }
+}
+
+/// Keep Nook connected to one Docker endpoint until `cancel` fires.
+///
+/// Connects with backoff while the daemon is unreachable. On every
+/// (re)connect the endpoint's pods are rebuilt from Docker and background
+/// tasks are restarted for running pods; the Docker event stream then
+/// doubles as the liveness check, since it ends when the daemon goes away.
+/// Every change is recorded in the endpoint's `EndpointState::connection`
+/// and emitted as `docker-connection-changed`.
+async fn supervise(state: AppState, app: AppHandle, name: String, cancel: CancellationToken) {
+    let mut attempts = 0;
+    loop {
+        let Some(endpoint) = state
+            .lock()
+            .await
+            .endpoints
+            .get(&name)
+            .map(|e| e.endpoint.clone())
+        else {
+            return;
+        };
+        let attempt = tokio::select! {
+            _ = cancel.cancelled() => return,
+            attempt = connect(&endpoint) => attempt,
+        };
+        match attempt {
+            Ok(connection) => {
+                attempts = 0;
+                let docker = connection.docker.clone();
+                let runtime = RuntimeInfo::detect(&docker).await;
+                tracing::info!(
+                    "Connected to Docker endpoint {} ({}, {:?}{})",
+                    name,
+                    endpoint.host,
+                    runtime.runtime,
+                    if runtime.rootless { ", rootless" } else { "" }
+                );
+                recover_pods(&state, &app, &name, &cancel, &docker, runtime).await;
+                set_connection(
+                    &state,
+                    &app,
+                    &cancel,
+                    DockerConnection {
+                        endpoint: name.clone(),
+                        host: endpoint.host.clone(),
+                        runtime: Some(runtime.runtime),
+                        status: DockerConnectionStatus::Connected,
+                        error: None,
+                        attempts: 0,
+                        next_retry_at: None,
+                    },
+                )
+                .await;
+
+                tokio::select! {
+                    // `apply_endpoints` already stopped the endpoint's pods
+                    _ = cancel.cancelled() => return,
+                    _ = events::watch_events(docker.clone(), state.clone(), app.clone()) => {}
+                }
+                if docker.ping().await.is_ok() {
+                    // Only the event stream dropped; resubscribe without spinning
+                    tokio::time::sleep(backoff(1)).await;
+                    continue;
+                }
+                tracing::warn!("Lost connection to Docker endpoint {}", name);
+                disconnect(&state, &name, &cancel).await;
+            }
+            Err(e) => {
+                attempts += 1;
+                let delay = backoff(attempts);
+                if attempts == 1 {
+                    tracing::warn!("Failed to connect to Docker endpoint {}: {}", name, e);
+                }
+                set_connection(
+                    &state,
+                    &app,
+                    &cancel,
+                    DockerConnection {
+                        endpoint: name.clone(),
+                        host: endpoint.host.clone(),
+                        runtime: None,
+                        status: DockerConnectionStatus::Disconnected,
+                        error: Some(e.to_string()),
+                        attempts,
+                        next_retry_at: Some(
+                            chrono::Utc::now().timestamp() + delay.as_secs() as i64,
+                        ),
+                    },
+                )
+                .await;
+                tokio::select! {
+                    _ = cancel.cancelled() => return,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+        }
+    }
+}
+
+async fn connect(endpoint: &DockerEndpoint) -> NookResult<EndpointConnection> {
+    let connection = endpoint.connect().await?;
+    connection.docker.ping().await?;
+    Ok(connection)
+}
+
+/// Record and emit a connection change, unless the supervisor was replaced.
+async fn set_connection(
+    state: &AppState,
+    app: &AppHandle,
+    cancel: &CancellationToken,
+    connection: DockerConnection,
+) {
+    let mut s = state.lock().await;
+    let Some(endpoint) = s.endpoints.get_mut(&connection.endpoint) else {
+        return;
+    };
+    if cancel.is_cancelled() {
+        return;
+    }
+    endpoint.connection = connection.clone();
+    let _ = app.emit("docker-connection-changed", &connection);
+}
+
+/// Drop the endpoint's client and stop its pods' background tasks; they
+/// would only fail until the daemon is back.
+async fn disconnect(state: &AppState, name: &str, cancel: &CancellationToken) {
+    let mut s = state.lock().await;
+    if cancel.is_cancelled() {
+        return;
+    }
+    if let Some(endpoint) = s.endpoints.get_mut(name) {
+        endpoint.docker = None;
+    }
+    stop_endpoint_pods(&mut s, name);
+}
+
+fn stop_endpoint_pods(state: &mut AppStateInner, name: &str) {
+    let pod_ids: Vec<String> = state
+        .pods
+        .values()
+        .filter(|p| p.endpoint_name() == name)
+        .map(|p| p.id.clone())
+        .collect();
+    for pod_id in pod_ids {
+        monitor::stop_monitoring(state, &pod_id);
+    }
+}
+
+/// Rebuild an endpoint's pods from Docker's containers. Pods Nook already
+/// knows keep their id, so the frontend and per-pod state (logs, hook runs)
+/// stay attached; pods whose container disappeared lose it and show as
+/// stopped.
+async fn recover_pods(
+    state: &AppState,
+    app: &AppHandle,
+    name: &str,
+    cancel: &CancellationToken,
+    docker: &Docker,
+    runtime: RuntimeInfo,
+) {
+    let listed = match containers::list_containers(docker, name).await {
+        Ok(pods) => pods,
+        Err(e) => {
+            tracing::warn!("Failed to list containers on {}: {}", name, e);
+            Vec::new()
+        }
+    };
+
+    let mut state_guard = state.lock().await;
+    if cancel.is_cancelled() {
+        return;
+    }
+    let Some(endpoint) = state_guard.endpoints.get_mut(name) else {
+        return;
+    };
+    endpoint.docker = Some(docker.clone());
+    endpoint.runtime = runtime;
+    let mut seen = HashSet::new();
+    for mut pod in listed {
+        apply_saved_config(&mut pod);
+        let existing = state_guard
+            .pods
+            .values()
+            .find(|p| p.container_id.is_some() && p.container_id == pod.container_id)
+            .or_else(|| {
+                state_guard.pods.values().find(|p| {
+                    p.endpoint_name() == name
+                        && p.project_path == pod.project_path
+                        && p.config_path == pod.config_path
+                })
+            })
+            .map(|p| p.id.clone());
+        if let Some(old) = existing.and_then(|id| state_guard.pods.remove(&id)) {
+            pod.id = old.id;
+            pod.hook_runs = old.hook_runs;
+            pod.warning = old.warning;
+            pod.config_drift = old.config_drift;
+            pod.last_exit = old.last_exit;
+            pod.git = old.git;
+        }
+        seen.insert(pod.id.clone());
+        state_guard.pods.insert(pod.id.clone(), pod);
+    }
+    for pod in state_guard.pods.values_mut() {
+        if pod.endpoint_name() == name && !seen.contains(&pod.id) && pod.container_id.take().is_some()
+        {
+            pod.status = PodStatus::Stopped;
+            pod.container_name = None;
+        }
+    }
+    tracing::info!("Loaded {} pods from Docker endpoint {}", seen.len(), name);
+
+    let pods: Vec<(String, bool)> = state_guard
+        .pods
+        .values()
+        .filter(|p| p.endpoint_name() == name)
+        .map(|p| {
+            let running = p.status == PodStatus::Running && p.container_id.is_some();
+            (p.id.clone(), running)
+        })
+        .collect();
+    for (pod_id, running) in pods {
+        if running {
+            monitor::start_monitoring(&mut state_guard, state, app, &pod_id);
+            tracing::info!("Started monitoring for running pod {}", pod_id);
+        } else {
+            monitor::stop_monitoring(&mut state_guard, &pod_id);
+        }
+        // Flag pods whose config changed while Nook wasn't watching
+        crate::devcontainer::drift::watch(&mut state_guard, state, app, &pod_id);
+        crate::git::watch(&mut state_guard, state, app, &pod_id);
+    }
+}
+
+/// Merge a pod listed from Docker with its saved config by project path and
+/// devcontainer.json, or save a default config for a pod seen for the first
+/// time.
+fn apply_saved_config(pod: &mut Pod) {
+    if let Ok(Some(saved)) = PodStateConfig::find_by_project_and_config(
+        &pod.project_path,
+        pod.config_path.as_deref().unwrap_or(""),
+    ) {
+        pod.name = saved.name;
+        if !saved.alias.is_empty() {
+            pod.alias = Some(saved.alias);
+        }
+        if !saved.shell.is_empty() {
+            pod.default_shell = saved.shell;
+        }
+        if pod.remote_user.is_none() && !saved.remote_user.is_empty() {
+            pod.remote_user = Some(saved.remote_user);
+        }
+        if pod.remote_workspace_folder.is_none() && !saved.working_dir.is_empty() {
+            pod.remote_workspace_folder = Some(saved.working_dir);
+        }
+        return;
+    }
+
+    if let Ok(name) = PodStateConfig::unique_name(&pod.name) {
+        pod.name = name;
+    }
+    let config = PodStateConfig {
+        name: pod.name.clone(),
+        project_path: pod.project_path.clone(),
+        config_path: pod.config_path.clone().unwrap_or_default(),
+        endpoint: pod.endpoint.clone(),
+        ..Default::default()
+    };
+    if let Err(e) = config.save() {
+        tracing::warn!("Failed to save default pod config: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let secs: Vec<u64> = (1..=8).map(|a| backoff(a).as_secs()).collect();
+        assert_eq!(secs, vec![1, 2, 4, 8, 16, 30, 30, 30]);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -2,9 +2,11 @@ use bollard::container::{
     InspectContainerOptions, KillContainerOptions, ListContainersOptions,
     RemoveContainerOptions, StartContainerOptions, StopContainerOptions,
 };
+use bollard::models::PortMap;
 use bollard::Docker;
 use std::collections::HashMap;
 
+use crate::docker::compose;
 use crate::error::{NookError, NookResult};
 use crate::types::{Pod, PodStatus};
 
@@ -44,101 +46,25 @@ pub fn parse_devcontainer_metadata_label(metadata_json: &str) -> DevcontainerMet
     result
 }
 
-/// Read remoteUser and remoteWorkspaceFolder from .devcontainer/devcontainer.json
-pub fn read_devcontainer_json(project_path: &str) -> DevcontainerMeta {
-    let mut result = DevcontainerMeta {
-        remote_user: None,
-        remote_workspace_folder: None,
-    };
-
-    // Try .devcontainer/devcontainer.json, then .devcontainer.json
-    let candidates = [
-        std::path::Path::new(project_path).join(".devcontainer/devcontainer.json"),
-        std::path::Path::new(project_path).join(".devcontainer.json"),
-    ];
-
-    for path in &candidates {
-        if let Ok(content) = std::fs::read_to_string(path) {
-            // Strip JSON comments (// and /* */) before parsing
-            let cleaned = strip_json_comments(&content);
-            if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cleaned) {
-                if let Some(u) = val.get("remoteUser").and_then(|v| v.as_str()) {
-                    result.remote_user = Some(u.to_string());
-                }
-                if let Some(f) = val.get("workspaceFolder").and_then(|v| v.as_str()) {
-                    result.remote_workspace_folder = Some(f.to_string());
-                }
-                break;
-            }
-        }
-    }
-
-    result
-}
-
-/// Minimal JSON comment stripper (handles // line comments and /* block comments */)
-fn strip_json_comments(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-    let mut in_string = false;
-
-    while let Some(&c) = chars.peek() {
-        if in_string {
-            out.push(c);
-            chars.next();
-            if c == '\\' {
-                // skip escaped char
-                if let Some(&next) = chars.peek() {
-                    out.push(next);
-                    chars.next();
-                }
-            } else if c == '"' {
-                in_string = false;
-            }
-        } else if c == '"' {
-            in_string = true;
-            out.push(c);
-            chars.next();
-        } else if c == '/' {
-            chars.next();
-            match chars.peek() {
-                Some(&'/') => {
-                    // line comment — skip until newline
-                    for ch in chars.by_ref() {
-                        if ch == '\n' {
-                            out.push('\n');
-                            break;
-                        }
-                    }
-                }
-                Some(&'*') => {
-                    // block comment — skip until */
-                    chars.next();
-                    loop {
-                        match chars.next() {
-                            Some('*') if chars.peek() == Some(&'/') => {
-                                chars.next();
-                                break;
-                            }
-                            Some('\n') => out.push('\n'),
-                            None => break,
-                            _ => {}
-                        }
-                    }
-                }
-                _ => {
-                    out.push('/');
-                }
+/// Read remoteUser and remoteWorkspaceFolder from the pod's devcontainer.json
+/// (the bound `config_path`, or the default locations).
+pub fn read_devcontainer_json(project_path: &str, config_path: Option<&str>) -> DevcontainerMeta {
+    match crate::devcontainer::config::load(project_path, config_path) {
+        Ok(config) => DevcontainerMeta {
+            remote_user: config.remote_user,
+            remote_workspace_folder: config.workspace_folder,
+        },
+        Err(e) => {
+            tracing::debug!("Could not read devcontainer.json for {}: {}", project_path, e);
+            DevcontainerMeta {
+                remote_user: None,
+                remote_workspace_folder: None,
             }
-        } else {
-            out.push(c);
-            chars.next();
         }
     }
-    out
 }
 
-pub async fn list_containers(docker: &Docker) -> NookResult<Vec<Pod>> {
+pub async fn list_containers(docker: &Docker, endpoint: &str) -> NookResult<Vec<Pod>> {
     let filters: HashMap<&str, Vec<&str>> = HashMap::from([
         ("label", vec!["devcontainer.local_folder"]),
     ]);
@@ -161,26 +87,28 @@ pub async fn list_containers(docker: &Docker) -> NookResult<Vec<Pod>> {
             .unwrap_or_else(|| id[..12].to_string());
         let image = container.image.unwrap_or_default();
         let state = container.state.unwrap_or_default();
+        let health = container.status.as_deref().and_then(health_from_status);
 
         let labels = container.labels.unwrap_or_default();
         let project_path = labels
             .get("devcontainer.local_folder")
             .cloned()
             .unwrap_or_default();
+        let config_path = labels
+            .get("devcontainer.config_file")
+            .map(|f| crate::devcontainer::discovery::relative_config_path(&project_path, f))
+            .filter(|c| !c.is_empty());
 
-        let status = match state.as_str() {
-            "running" => PodStatus::Running,
-            "exited" | "dead" | "created" => PodStatus::Stopped,
-            _ => PodStatus::Stopped,
-        };
+        let status = status_from_state(&state);
 
         let container_name = Some(name.clone());
 
         // Derive pod name from project_path directory name (same as add_pod)
-        let pod_name = std::path::Path::new(&project_path)
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| name.clone());
+        let pod_name = if project_path.is_empty() {
+            name.clone()
+        } else {
+            crate::devcontainer::discovery::pod_name_for(&project_path, config_path.as_deref())
+        };
 
         // Generate a stable UUID for the pod (not the Docker container ID)
         let pod_id = uuid::Uuid::new_v4().to_string();
@@ -197,7 +125,7 @@ pub async fn list_containers(docker: &Docker) -> NookResult<Vec<Pod>> {
         // Fall back to reading devcontainer.json from project_path
         let json_meta = if label_meta.remote_user.is_none() || label_meta.remote_workspace_folder.is_none() {
             if !project_path.is_empty() {
-                read_devcontainer_json(&project_path)
+                read_devcontainer_json(&project_path, config_path.as_deref())
             } else {
                 DevcontainerMeta {
                     remote_user: None,
@@ -214,6 +142,15 @@ pub async fn list_containers(docker: &Docker) -> NookResult<Vec<Pod>> {
         let remote_user = label_meta.remote_user.or(json_meta.remote_user);
         let remote_workspace_folder = label_meta.remote_workspace_folder.or(json_meta.remote_workspace_folder);
 
+        // Sidecars of compose-based devcontainers share the compose project label
+        let compose_project = compose::project_from_labels(&labels);
+        let services = match &compose_project {
+            Some(project) => compose::list_services(docker, project)
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
         pods.push(Pod {
             id: pod_id,
             name: pod_name,
@@ -235,12 +172,40 @@ pub async fn list_containers(docker: &Docker) -> NookResult<Vec<Pod>> {
             remote_user,
             remote_workspace_folder,
             container_name,
+            config_path,
+            compose_project,
+            services,
+            hook_runs: Vec::new(),
+            warning: None,
+            config_drift: None,
+            health,
+            last_exit: None,
+            endpoint: endpoint.to_string(),
+            git: None,
         });
     }
 
     Ok(pods)
 }
 
+/// Health check status from a container's status line, e.g.
+/// `Up 2 hours (healthy)`. `None` for containers without a health check.
+pub fn health_from_status(status: &str) -> Option<String> {
+    let (_, rest) = status.rsplit_once('(')?;
+    let health = rest.strip_suffix(')')?;
+    let health = health.strip_prefix("health: ").unwrap_or(health);
+    matches!(health, "starting" | "healthy" | "unhealthy").then(|| health.to_string())
+}
+
+/// Map a Docker container state (`running`, `exited`, ...) to a pod status.
+pub fn status_from_state(state: &str) -> PodStatus {
+    match state {
+        "running" => PodStatus::Running,
+        "exited" | "dead" | "created" => PodStatus::Stopped,
+        _ => PodStatus::Stopped,
+    }
+}
+
 pub async fn start_container(docker: &Docker, container_id: &str) -> NookResult<()> {
     docker
         .start_container(container_id, None::<StartContainerOptions<String>>)
@@ -254,8 +219,16 @@ pub async fn start_container(docker: &Docker, container_id: &str) -> NookResult<
     Ok(())
 }
 
-pub async fn stop_container(docker: &Docker, container_id: &str) -> NookResult<()> {
-    let options = StopContainerOptions { t: 10 };
+/// Stop a container with its own stop signal, killing it after
+/// `timeout_secs`.
+pub async fn stop_container(
+    docker: &Docker,
+    container_id: &str,
+    timeout_secs: u64,
+) -> NookResult<()> {
+    let options = StopContainerOptions {
+        t: timeout_secs as i64,
+    };
     docker
         .stop_container(container_id, Some(options))
         .await
@@ -291,7 +264,12 @@ pub async fn remove_container(
 }
 
 pub async fn kill_container(docker: &Docker, container_id: &str) -> NookResult<()> {
-    let options = KillContainerOptions { signal: "SIGKILL" };
+    signal_container(docker, container_id, "SIGKILL").await
+}
+
+/// Send `signal` (e.g. `SIGINT`, `TERM` or `15`) to a container's main process.
+pub async fn signal_container(docker: &Docker, container_id: &str, signal: &str) -> NookResult<()> {
+    let options = KillContainerOptions { signal };
     docker
         .kill_container(container_id, Some(options))
         .await
@@ -320,10 +298,123 @@ pub async fn inspect_container(
     Ok(response)
 }
 
+/// IP address the host can reach the container on: the default bridge IP, or
+/// the first attached network's IP for containers on user-defined networks.
+/// Address the host reaches a container's port at. Rootless daemons (Docker
+/// or Podman) keep containers in their own network namespace, so there only
+/// published ports are reachable; otherwise it is the container's IP. Podman
+/// leaves the top-level IP empty and reports it per network.
+pub async fn container_address(
+    docker: &Docker,
+    container_id: &str,
+    container_port: u16,
+    rootless: bool,
+) -> NookResult<(String, u16)> {
+    let info = inspect_container(docker, container_id).await?;
+    let ns = info.network_settings;
+    if rootless {
+        return ns
+            .as_ref()
+            .and_then(|n| n.ports.as_ref())
+            .and_then(|ports| published_address(ports, container_port))
+            .ok_or_else(|| {
+                NookError::Other(format!(
+                    "Port {} is not published; containers of a rootless daemon are only reachable through published ports",
+                    container_port
+                ))
+            });
+    }
+    let direct_ip = ns
+        .as_ref()
+        .and_then(|n| n.ip_address.as_ref())
+        .filter(|ip| !ip.is_empty())
+        .cloned();
+    let network_ip = ns
+        .as_ref()
+        .and_then(|n| n.networks.as_ref())
+        .and_then(|nets| {
+            nets.values()
+                .filter_map(|net| net.ip_address.clone())
+                .find(|ip| !ip.is_empty())
+        });
+    direct_ip
+        .or(network_ip)
+        .map(|ip| (ip, container_port))
+        .ok_or_else(|| NookError::Other("Could not determine container IP".to_string()))
+}
+
+/// Host address a container's TCP port is published on. Wildcard bindings
+/// are reached over loopback.
+fn published_address(ports: &PortMap, container_port: u16) -> Option<(String, u16)> {
+    ports
+        .get(&format!("{}/tcp", container_port))?
+        .as_ref()?
+        .iter()
+        .find_map(|binding| {
+            let port = binding.host_port.as_deref()?.parse().ok()?;
+            let ip = match binding.host_ip.as_deref() {
+                None | Some("") | Some("0.0.0.0") => "127.0.0.1",
+                Some("::") => "::1",
+                Some(ip) => ip,
+            };
+            Some((ip.to_string(), port))
+        })
+}
+
+/// Find the container running `service` in the same Docker Compose project as
+/// `container_id`. Returns `None` if the container isn't part of a compose
+/// project or the service has no container.
+pub async fn find_compose_service(
+    docker: &Docker,
+    container_id: &str,
+    service: &str,
+) -> NookResult<Option<String>> {
+    let info = inspect_container(docker, container_id).await?;
+    let Some(project) = info
+        .config
+        .and_then(|c| c.labels)
+        .and_then(|labels| compose::project_from_labels(&labels))
+    else {
+        return Ok(None);
+    };
+
+    let project_filter = format!("{}={}", compose::PROJECT_LABEL, project);
+    let service_filter = format!("{}={}", compose::SERVICE_LABEL, service);
+    let filters: HashMap<&str, Vec<&str>> =
+        HashMap::from([("label", vec![project_filter.as_str(), service_filter.as_str()])]);
+    let containers = docker
+        .list_containers(Some(ListContainersOptions {
+            filters,
+            ..Default::default()
+        }))
+        .await?;
+
+    Ok(containers.into_iter().find_map(|c| c.id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_published_address() {
+        // NetworkSettings.Ports of a rootless Podman container
+        let ports: PortMap = serde_json::from_str(
+            r#"{"3000/tcp":[{"HostIp":"","HostPort":"3000"}],"5432/tcp":[{"HostIp":"127.0.0.1","HostPort":"15432"}],"9229/tcp":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            published_address(&ports, 3000),
+            Some(("127.0.0.1".to_string(), 3000))
+        );
+        assert_eq!(
+            published_address(&ports, 5432),
+            Some(("127.0.0.1".to_string(), 15432))
+        );
+        assert_eq!(published_address(&ports, 9229), None);
+        assert_eq!(published_address(&ports, 8080), None);
+    }
+
     #[test]
     fn test_parse_devcontainer_metadata_label_with_remote_user() {
         let metadata = r#"[{"remoteUser":"vscode"},{"remoteWorkspaceFolder":"/workspaces/my-project"}]"#;
@@ -348,6 +439,17 @@ mod tests {
         assert_eq!(result.remote_workspace_folder, None);
     }
 
+    #[test]
+    fn test_health_from_status() {
+        assert_eq!(health_from_status("Up 2 hours (healthy)").as_deref(), Some("healthy"));
+        assert_eq!(
+            health_from_status("Up 3 seconds (health: starting)").as_deref(),
+            Some("starting")
+        );
+        assert_eq!(health_from_status("Up 2 hours"), None);
+        assert_eq!(health_from_status("Exited (0) 5 minutes ago"), None);
+    }
+
     #[test]
     fn test_parse_devcontainer_metadata_label_invalid_json() {
         let metadata = "not json";
@@ -355,27 +457,4 @@ mod tests {
         assert_eq!(result.remote_user, None);
         assert_eq!(result.remote_workspace_folder, None);
     }
-
-    #[test]
-    fn test_strip_json_comments() {
-        let input = r#"{
-  // This is a comment
-  "remoteUser": "vscode",
-  /* block comment */
-  "workspaceFolder": "/workspaces/app"
-}"#;
-        let cleaned = strip_json_comments(input);
-        let val: serde_json::Value = serde_json::from_str(&cleaned).unwrap();
-        assert_eq!(val.get("remoteUser").unwrap().as_str().unwrap(), "vscode");
-        assert_eq!(val.get("workspaceFolder").unwrap().as_str().unwrap(), "/workspaces/app");
-    }
-
-    #[test]
-    fn test_strip_json_comments_in_strings() {
-        // Comments inside strings should NOT be stripped
-        let input = r#"{"url": "http://example.com"}"#;
-        let cleaned = strip_json_comments(input);
-        let val: serde_json::Value = serde_json::from_str(&cleaned).unwrap();
-        assert_eq!(val.get("url").unwrap().as_str().unwrap(), "http://example.com");
-    }
 }
@@ -0,0 +1,483 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bollard::{Docker, API_DEFAULT_VERSION};
+use serde::{Deserialize, Serialize};
+use tokio::process::{Child, Command};
+
+use crate::config::settings::Settings;
+use crate::error::{NookError, NookResult};
+
+/// Name of the endpoint pods use unless they are bound to another one.
+pub const DEFAULT_ENDPOINT: &str = "default";
+
+/// Request timeout for Docker API calls, in seconds (bollard's default).
+const TIMEOUT_SECS: u64 = 120;
+
+/// Remote socket an `ssh://` endpoint forwards to when the URL has no path.
+const DEFAULT_REMOTE_SOCKET: &str = "/var/run/docker.sock";
+
+/// A Docker daemon Nook can talk to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerEndpoint {
+    /// `default`, a Docker context name, `rootless` or `podman`
+    pub name: String,
+    /// `unix://`, `npipe://`, `tcp://` or `ssh://` address
+    pub host: String,
+    /// Directory holding `ca.pem`, `cert.pem` and `key.pem` for TLS over tcp
+    pub tls_dir: Option<String>,
+    /// Docker context the endpoint comes from, if any
+    pub context: Option<String>,
+}
+
+impl DockerEndpoint {
+    fn new(name: &str, host: String) -> Self {
+        Self {
+            name: name.to_string(),
+            host,
+            tls_dir: None,
+            context: None,
+        }
+    }
+
+    /// Connect to the daemon. `ssh://` endpoints are reached through an SSH
+    /// tunnel to the remote socket, which lives as long as the connection.
+    pub async fn connect(&self) -> NookResult<EndpointConnection> {
+        let (scheme, rest) = self.host.split_once("://").unwrap_or(("unix", &self.host));
+        let connection = |docker| EndpointConnection {
+            docker,
+            _tunnel: None,
+        };
+        match scheme {
+            "unix" => Ok(connection(Docker::connect_with_unix(
+                rest,
+                TIMEOUT_SECS,
+                API_DEFAULT_VERSION,
+            )?)),
+            #[cfg(windows)]
+            "npipe" => Ok(connection(Docker::connect_with_named_pipe(
+                &self.host,
+                TIMEOUT_SECS,
+                API_DEFAULT_VERSION,
+            )?)),
+            "tcp" | "http" | "https" => match &self.tls_dir {
+                Some(dir) => {
+                    let dir = Path::new(dir);
+                    Ok(connection(Docker::connect_with_ssl(
+                        &self.host,
+                        &dir.join("key.pem"),
+                        &dir.join("cert.pem"),
+                        &dir.join("ca.pem"),
+                        TIMEOUT_SECS,
+                        API_DEFAULT_VERSION,
+                    )?))
+                }
+                None => Ok(connection(Docker::connect_with_http(
+                    &self.host,
+                    TIMEOUT_SECS,
+                    API_DEFAULT_VERSION,
+                )?)),
+            },
+            "ssh" => {
+                let tunnel = SshTunnel::open(&self.name, rest).await?;
+                let docker = Docker::connect_with_unix(
+                    &tunnel.socket.to_string_lossy(),
+                    TIMEOUT_SECS,
+                    API_DEFAULT_VERSION,
+                )?;
+                Ok(EndpointConnection {
+                    docker,
+                    _tunnel: Some(tunnel),
+                })
+            }
+            other => Err(NookError::DockerConnection(format!(
+                "Unsupported Docker host scheme {}:// for endpoint {}",
+                other, self.name
+            ))),
+        }
+    }
+
+    /// Environment that points the docker and devcontainer CLIs at this
+    /// endpoint. Context endpoints are selected by name so the CLI applies
+    /// the context's own TLS and SSH settings.
+    pub fn cli_env(&self) -> Vec<(String, String)> {
+        if let Some(context) = &self.context {
+            return vec![("DOCKER_CONTEXT".to_string(), context.clone())];
+        }
+        let mut env = vec![("DOCKER_HOST".to_string(), self.host.clone())];
+        if let Some(dir) = &self.tls_dir {
+            env.push(("DOCKER_TLS_VERIFY".to_string(), "1".to_string()));
+            env.push(("DOCKER_CERT_PATH".to_string(), dir.clone()));
+        }
+        env
+    }
+
+    /// Global `docker` CLI flags selecting this endpoint, for commands that
+    /// are run as a string (e.g. inside a terminal emulator).
+    pub fn docker_cli_args(&self) -> Vec<String> {
+        if let Some(context) = &self.context {
+            return vec!["--context".to_string(), context.clone()];
+        }
+        let mut args = vec!["-H".to_string(), self.host.clone()];
+        if let Some(dir) = &self.tls_dir {
+            let dir = Path::new(dir);
+            args.push("--tlsverify".to_string());
+            for (flag, file) in [
+                ("--tlscacert", "ca.pem"),
+                ("--tlscert", "cert.pem"),
+                ("--tlskey", "key.pem"),
+            ] {
+                args.push(flag.to_string());
+                args.push(dir.join(file).to_string_lossy().to_string());
+            }
+        }
+        args
+    }
+}
+
+/// A live client for an endpoint. Holds the SSH tunnel, if any, open.
+pub struct EndpointConnection {
+    pub docker: Docker,
+    _tunnel: Option<SshTunnel>,
+}
+
+/// The endpoint pods use by default, resolved the way the docker CLI does:
+/// the socket configured in Settings, then `DOCKER_HOST`, then the current
+/// Docker context, then the first local socket that exists (Docker, Docker
+/// Desktop, rootless Docker, Podman).
+pub fn default_endpoint(settings: &Settings, docker_config_dir: &Path) -> DockerEndpoint {
+    let configured = settings.docker_socket_path.trim();
+    if !configured.is_empty() {
+        return DockerEndpoint::new(DEFAULT_ENDPOINT, normalize_host(configured));
+    }
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if !host.is_empty() {
+            let mut endpoint = DockerEndpoint::new(DEFAULT_ENDPOINT, normalize_host(&host));
+            if std::env::var("DOCKER_TLS_VERIFY").is_ok_and(|v| !v.is_empty() && v != "0") {
+                let cert_path = std::env::var("DOCKER_CERT_PATH")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| docker_config_dir.to_path_buf());
+                endpoint.tls_dir = Some(cert_path.to_string_lossy().to_string());
+            }
+            return endpoint;
+        }
+    }
+    let current = std::env::var("DOCKER_CONTEXT")
+        .ok()
+        .filter(|c| !c.is_empty())
+        .or_else(|| current_context(docker_config_dir));
+    if let Some(current) = current.filter(|c| c != "default") {
+        if let Some(context) = contexts(docker_config_dir)
+            .into_iter()
+            .find(|c| c.name == current)
+        {
+            return DockerEndpoint {
+                name: DEFAULT_ENDPOINT.to_string(),
+                ..context
+            };
+        }
+    }
+    let host = local_sockets()
+        .into_iter()
+        .find(|(_, path)| path.exists())
+        .map(|(_, path)| format!("unix://{}", path.display()))
+        .unwrap_or_else(platform_default_host);
+    DockerEndpoint::new(DEFAULT_ENDPOINT, host)
+}
+
+/// Every endpoint Nook knows about: the default one, each Docker context and
+/// any rootless Docker or Podman socket, without duplicate hosts.
+pub fn discover(settings: &Settings, docker_config_dir: &Path) -> Vec<DockerEndpoint> {
+    let mut endpoints = vec![default_endpoint(settings, docker_config_dir)];
+    let local = local_sockets()
+        .into_iter()
+        .filter(|(name, path)| *name != "docker" && path.exists())
+        .map(|(name, path)| DockerEndpoint::new(name, format!("unix://{}", path.display())));
+    for endpoint in contexts(docker_config_dir).into_iter().chain(local) {
+        let known = endpoints
+            .iter()
+            .any(|e| e.name == endpoint.name || e.host == endpoint.host);
+        if !known {
+            endpoints.push(endpoint);
+        }
+    }
+    endpoints
+}
+
+/// Endpoints to connect to: the default one plus those named in
+/// `Settings::docker_endpoints`.
+pub fn active(settings: &Settings) -> Vec<DockerEndpoint> {
+    let config_dir = docker_config_dir();
+    discover(settings, &config_dir)
+        .into_iter()
+        .filter(|e| e.name == DEFAULT_ENDPOINT || settings.docker_endpoints.contains(&e.name))
+        .collect()
+}
+
+/// `~/.docker`, or `DOCKER_CONFIG` if set.
+pub fn docker_config_dir() -> PathBuf {
+    std::env::var("DOCKER_CONFIG")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".docker")))
+        .unwrap_or_else(|| PathBuf::from(".docker"))
+}
+
+/// A bare socket path becomes a `unix://` URL; anything else is kept.
+fn normalize_host(host: &str) -> String {
+    if host.contains("://") {
+        host.to_string()
+    } else {
+        format!("unix://{}", host)
+    }
+}
+
+fn platform_default_host() -> String {
+    if cfg!(windows) {
+        "npipe:////./pipe/docker_engine".to_string()
+    } else {
+        "unix:///var/run/docker.sock".to_string()
+    }
+}
+
+/// Well-known local daemon sockets, in order of preference.
+fn local_sockets() -> Vec<(&'static str, PathBuf)> {
+    let mut sockets = vec![("docker", PathBuf::from("/var/run/docker.sock"))];
+    if let Some(home) = dirs::home_dir() {
+        // Docker Desktop on macOS and Linux
+        sockets.push(("docker", home.join(".docker/run/docker.sock")));
+    }
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        let runtime_dir = PathBuf::from(runtime_dir);
+        sockets.push(("rootless", runtime_dir.join("docker.sock")));
+        sockets.push(("podman", runtime_dir.join("podman/podman.sock")));
+    }
+    sockets.push(("podman", PathBuf::from("/run/podman/podman.sock")));
+    sockets
+}
+
+/// `currentContext` from the docker CLI's config.json.
+fn current_context(docker_config_dir: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(docker_config_dir.join("config.json")).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&text).ok()?;
+    config
+        .get("currentContext")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(String::from)
+}
+
+/// Docker contexts from `contexts/meta/<id>/meta.json`, with TLS material
+/// from `contexts/tls/<id>/docker` when present.
+fn contexts(docker_config_dir: &Path) -> Vec<DockerEndpoint> {
+    let contexts_dir = docker_config_dir.join("contexts");
+    let Ok(entries) = std::fs::read_dir(contexts_dir.join("meta")) else {
+        return Vec::new();
+    };
+    let mut contexts: Vec<DockerEndpoint> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let text = std::fs::read_to_string(entry.path().join("meta.json")).ok()?;
+            let mut endpoint = parse_context_meta(&text)?;
+            let tls_dir = contexts_dir
+                .join("tls")
+                .join(entry.file_name())
+                .join("docker");
+            if endpoint.host.starts_with("tcp://") && tls_dir.join("ca.pem").is_file() {
+                endpoint.tls_dir = Some(tls_dir.to_string_lossy().to_string());
+            }
+            Some(endpoint)
+        })
+        .collect();
+    contexts.sort_by(|a, b| a.name.cmp(&b.name));
+    contexts
+}
+
+fn parse_context_meta(text: &str) -> Option<DockerEndpoint> {
+    let meta: serde_json::Value = serde_json::from_str(text).ok()?;
+    let name = meta.get("Name")?.as_str()?;
+    let host = meta
+        .get("Endpoints")?
+        .get("docker")?
+        .get("Host")?
+        .as_str()
+        .filter(|h| !h.is_empty())?;
+    Some(DockerEndpoint {
+        name: name.to_string(),
+        host: normalize_host(host),
+        tls_dir: None,
+        context: Some(name.to_string()),
+    })
+}
+
+/// Split `[user@]host[:port][/remote/socket]` into the ssh destination,
+/// port and remote socket path.
+fn parse_ssh_target(target: &str) -> (String, Option<String>, String) {
+    let (authority, socket) = match target.find('/') {
+        Some(i) => (&target[..i], target[i..].to_string()),
+        None => (target, DEFAULT_REMOTE_SOCKET.to_string()),
+    };
+    let (destination, port) = match authority.rsplit_once(':') {
+        Some((dest, port)) if port.parse::<u16>().is_ok() => (dest, Some(port.to_string())),
+        _ => (authority, None),
+    };
+    (destination.to_string(), port, socket)
+}
+
+/// `ssh -L` forwarding a local socket to the remote daemon's socket.
+struct SshTunnel {
+    socket: PathBuf,
+    _child: Child,
+}
+
+impl SshTunnel {
+    async fn open(name: &str, target: &str) -> NookResult<Self> {
+        let (destination, port, remote_socket) = parse_ssh_target(target);
+        let socket =
+            std::env::temp_dir().join(format!("nook-ssh-{}-{}.sock", name, uuid::Uuid::new_v4()));
+        let mut cmd = Command::new("ssh");
+        cmd.args([
+            "-nNT",
+            "-o",
+            "ExitOnForwardFailure=yes",
+            "-o",
+            "BatchMode=yes",
+        ]);
+        if let Some(port) = port {
+            cmd.args(["-p", &port]);
+        }
+        cmd.arg("-L")
+            .arg(format!("{}:{}", socket.display(), remote_socket))
+            .arg(&destination)
+            .kill_on_drop(true);
+        let mut child = cmd.spawn().map_err(|e| {
+            NookError::DockerConnection(format!("Failed to start ssh for {}: {}", name, e))
+        })?;
+
+        // The forward is ready once ssh has created the local socket
+        for _ in 0..100 {
+            if socket.exists() {
+                return Ok(Self {
+                    socket,
+                    _child: child,
+                });
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(NookError::DockerConnection(format!(
+                    "ssh to {} exited with {}",
+                    destination, status
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Err(NookError::DockerConnection(format!(
+            "Timed out opening an SSH tunnel to {}",
+            destination
+        )))
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(
+            normalize_host("/run/user/1000/docker.sock"),
+            "unix:///run/user/1000/docker.sock"
+        );
+        assert_eq!(normalize_host("tcp://10.0.0.2:2376"), "tcp://10.0.0.2:2376");
+    }
+
+    #[test]
+    fn test_parse_context_meta() {
+        let meta = r#"{"Name":"build-box","Metadata":{},"Endpoints":{"docker":{"Host":"ssh://dev@build-box","SkipTLSVerify":false}}}"#;
+        let endpoint = parse_context_meta(meta).unwrap();
+        assert_eq!(endpoint.name, "build-box");
+        assert_eq!(endpoint.host, "ssh://dev@build-box");
+        assert_eq!(endpoint.context.as_deref(), Some("build-box"));
+        assert_eq!(
+            endpoint.cli_env(),
+            vec![("DOCKER_CONTEXT".to_string(), "build-box".to_string())]
+        );
+        assert!(parse_context_meta(r#"{"Name":"k8s","Endpoints":{}}"#).is_none());
+    }
+
+    #[test]
+    fn test_parse_ssh_target() {
+        assert_eq!(
+            parse_ssh_target("dev@host"),
+            (
+                "dev@host".to_string(),
+                None,
+                DEFAULT_REMOTE_SOCKET.to_string()
+            )
+        );
+        assert_eq!(
+            parse_ssh_target("dev@host:2222/run/user/1000/docker.sock"),
+            (
+                "dev@host".to_string(),
+                Some("2222".to_string()),
+                "/run/user/1000/docker.sock".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_tls_cli_flags() {
+        let endpoint = DockerEndpoint {
+            tls_dir: Some("/certs".to_string()),
+            ..DockerEndpoint::new("remote", "tcp://10.0.0.2:2376".to_string())
+        };
+        assert_eq!(
+            endpoint.docker_cli_args(),
+            vec![
+                "-H",
+                "tcp://10.0.0.2:2376",
+                "--tlsverify",
+                "--tlscacert",
+                "/certs/ca.pem",
+                "--tlscert",
+                "/certs/cert.pem",
+                "--tlskey",
+                "/certs/key.pem",
+            ]
+        );
+        assert_eq!(endpoint.cli_env()[1].0, "DOCKER_TLS_VERIFY");
+    }
+
+    #[test]
+    fn test_contexts_from_config_dir() {
+        let dir = std::env::temp_dir().join(format!("nook-docker-{}", uuid::Uuid::new_v4()));
+        let meta = dir.join("contexts/meta/abc123");
+        std::fs::create_dir_all(&meta).unwrap();
+        std::fs::write(
+            meta.join("meta.json"),
+            r#"{"Name":"remote","Endpoints":{"docker":{"Host":"tcp://10.0.0.2:2376"}}}"#,
+        )
+        .unwrap();
+        let tls = dir.join("contexts/tls/abc123/docker");
+        std::fs::create_dir_all(&tls).unwrap();
+        std::fs::write(tls.join("ca.pem"), "").unwrap();
+        std::fs::write(dir.join("config.json"), r#"{"currentContext":"remote"}"#).unwrap();
+
+        let found = contexts(&dir);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].tls_dir.as_deref(),
+            Some(tls.to_string_lossy().as_ref())
+        );
+        assert_eq!(current_context(&dir).as_deref(), Some("remote"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
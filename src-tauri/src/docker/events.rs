@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use bollard::system::EventsOptions;
+use bollard::Docker;
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter};
+
+use crate::docker::restart::{self, RestartDecision};
+use crate::docker::{containers, monitor};
+use crate::state::AppState;
+use crate::types::{ContainerExit, PodStatus, PodStatusChanged, ProcessListUpdate};
+
+/// Container events that change a pod's state.
+#[derive(Debug, Clone, PartialEq)]
+enum ContainerEvent {
+    Start,
+    /// A signal sent through `docker stop` or `docker kill`, before `Die`
+    Kill,
+    Die,
+    Stop,
+    Destroy,
+    Health(String),
+}
+
+impl ContainerEvent {
+    /// Parse a Docker event action. Health events carry their status in the
+    /// action itself, e.g. `health_status: healthy`.
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "start" => Some(Self::Start),
+            "kill" => Some(Self::Kill),
+            "die" => Some(Self::Die),
+            "stop" => Some(Self::Stop),
+            "destroy" => Some(Self::Destroy),
+            _ => action
+                .strip_prefix("health_status: ")
+                .map(|h| Self::Health(h.to_string())),
+        }
+    }
+}
+
+/// Follow Docker's event stream and reconcile pods with containers started,
+/// stopped or removed outside of Nook (docker CLI, VS Code, crashes, OOM).
+/// Monitoring tasks are started or cancelled to match, and every change is
+/// emitted as `pod-status-changed`.
+///
+/// Returns when the stream ends, e.g. because the daemon went away.
+pub async fn watch_events(docker: Docker, state: AppState, app: AppHandle) {
+    let filters = HashMap::from([
+        ("type".to_string(), vec!["container".to_string()]),
+        (
+            "label".to_string(),
+            vec!["devcontainer.local_folder".to_string()],
+        ),
+    ]);
+    let mut stream = docker.events(Some(EventsOptions {
+        filters,
+        ..Default::default()
+    }));
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Docker event stream failed: {}", e);
+                break;
+            }
+        };
+        let Some(event) = message.action.as_deref().and_then(ContainerEvent::parse) else {
+            continue;
+        };
+        let Some(actor) = message.actor else {
+            continue;
+        };
+        let Some(container_id) = actor.id else {
+            continue;
+        };
+        let exit_code = actor
+            .attributes
+            .as_ref()
+            .and_then(|a| a.get("exitCode"))
+            .and_then(|c| c.parse().ok());
+        handle_event(&docker, &state, &app, &container_id, event, exit_code).await;
+    }
+}
+
+async fn handle_event(
+    docker: &Docker,
+    state: &AppState,
+    app: &AppHandle,
+    container_id: &str,
+    event: ContainerEvent,
+    exit_code: Option<i64>,
+) {
+    let oom_killed = if event == ContainerEvent::Die {
+        containers::inspect_container(docker, container_id)
+            .await
+            .ok()
+            .and_then(|info| info.state)
+            .and_then(|s| s.oom_killed)
+            .unwrap_or(false)
+    } else {
+        false
+    };
+
+    let mut s = state.lock().await;
+    let Some(pod) = s
+        .pods
+        .values_mut()
+        .find(|p| p.container_id.as_deref() == Some(container_id))
+    else {
+        return;
+    };
+    let pod_id = pod.id.clone();
+
+    // Nook's own start, stop and rebuild commands update the pod themselves
+    let busy = matches!(pod.status, PodStatus::Starting | PodStatus::Stopping);
+    let mut exit = None;
+    let mut restart_delay = None;
+    match event {
+        ContainerEvent::Health(health) => {
+            pod.health = Some(health);
+        }
+        _ if busy => return,
+        ContainerEvent::Start => {
+            if pod.status == PodStatus::Running {
+                return;
+            }
+            pod.status = PodStatus::Running;
+            pod.started_at = Some(chrono::Utc::now().timestamp());
+            pod.error_message = None;
+            pod.last_exit = None;
+            monitor::start_monitoring(&mut s, state, app, &pod_id);
+        }
+        ContainerEvent::Kill => {
+            if pod.status == PodStatus::Running {
+                restart::on_stop_requested(&mut s, &pod_id);
+            }
+            return;
+        }
+        ContainerEvent::Die | ContainerEvent::Stop | ContainerEvent::Destroy => {
+            if event == ContainerEvent::Destroy {
+                // The pod stays; its next start creates a new container
+                pod.container_id = None;
+                pod.container_name = None;
+            } else if pod.status != PodStatus::Running {
+                // Already handled, e.g. `stop` right after `die`
+                return;
+            }
+            if pod.status == PodStatus::Running {
+                if event == ContainerEvent::Die {
+                    exit = Some(ContainerExit {
+                        exit_code: exit_code.unwrap_or(0),
+                        oom_killed,
+                        finished_at: chrono::Utc::now().timestamp(),
+                    });
+                    pod.last_exit = exit.clone();
+                }
+                let ran_secs = pod
+                    .started_at
+                    .map(|started| chrono::Utc::now().timestamp() - started);
+                pod.status = if oom_killed {
+                    PodStatus::Error
+                } else {
+                    PodStatus::Stopped
+                };
+                pod.error_message = oom_killed
+                    .then(|| "Container was killed after running out of memory".to_string());
+                pod.health = None;
+                pod.exposed_ports.clear();
+                pod.detected_ports.clear();
+                pod.processes.clear();
+                pod.cpu_percent = 0.0;
+                pod.memory_used = 0;
+                pod.started_at = None;
+                monitor::stop_monitoring(&mut s, &pod_id);
+                if let Some(exit) = exit.clone() {
+                    match restart::on_exit(&mut s, &pod_id, exit, ran_secs) {
+                        RestartDecision::Skip => {}
+                        RestartDecision::Restart(delay) => restart_delay = Some(delay),
+                        RestartDecision::GiveUp(message) => {
+                            if let Some(pod) = s.pods.get_mut(&pod_id) {
+                                pod.status = PodStatus::Error;
+                                pod.error_message = Some(message);
+                            }
+                        }
+                    }
+                }
+                let _ = app.emit(
+                    "process-list-update",
+                    &ProcessListUpdate {
+                        pod_id: pod_id.clone(),
+                        processes: Vec::new(),
+                    },
+                );
+            }
+        }
+    }
+
+    let Some(pod) = s.pods.get(&pod_id) else {
+        return;
+    };
+    tracing::info!(
+        "Container of pod {} changed outside Nook: {:?}",
+        pod.name,
+        pod.status
+    );
+    let _ = app.emit(
+        "pod-status-changed",
+        &PodStatusChanged {
+            pod_id: pod_id.clone(),
+            status: pod.status.clone(),
+            error_message: pod.error_message.clone(),
+            exit,
+            health: pod.health.clone(),
+        },
+    );
+    if let Some(delay) = restart_delay {
+        restart::schedule(&mut s, app, &pod_id, delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event() {
+        assert_eq!(ContainerEvent::parse("die"), Some(ContainerEvent::Die));
+        assert_eq!(ContainerEvent::parse("kill"), Some(ContainerEvent::Kill));
+        assert_eq!(
+            ContainerEvent::parse("health_status: unhealthy"),
+            Some(ContainerEvent::Health("unhealthy".to_string()))
+        );
+        assert_eq!(ContainerEvent::parse("exec_start: sh"), None);
+        assert_eq!(ContainerEvent::parse("attach"), None);
+    }
+}
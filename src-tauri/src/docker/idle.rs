@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bollard::Docker;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::pod_state::PodStateConfig;
+use crate::docker::containers;
+use crate::state::AppState;
+use crate::types::{PodIdleNotice, PodStatus};
+
+/// How often running pods are checked for idleness.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleAction {
+    None,
+    /// The pod will be stopped unless it gets busy again
+    Warn {
+        stops_in_secs: u64,
+    },
+    Stop,
+}
+
+/// When a running pod was last seen busy: CPU above the idle threshold, a
+/// terminal session open or traffic through its port proxies.
+#[derive(Debug)]
+pub struct IdleTracker {
+    busy_at: i64,
+    warned: bool,
+}
+
+impl IdleTracker {
+    pub fn new(now: i64) -> Self {
+        Self {
+            busy_at: now,
+            warned: false,
+        }
+    }
+
+    /// The pod was busy at `at`; a warning already sent no longer holds.
+    pub fn busy(&mut self, at: i64) {
+        if at > self.busy_at {
+            self.busy_at = at;
+            self.warned = false;
+        }
+    }
+
+    pub fn idle_secs(&self, now: i64) -> u64 {
+        (now - self.busy_at).max(0) as u64
+    }
+
+    /// Whether to warn about or stop the pod after `timeout_secs` idle,
+    /// warning once `warning_secs` before.
+    pub fn check(&mut self, now: i64, timeout_secs: u64, warning_secs: u64) -> IdleAction {
+        let idle = self.idle_secs(now);
+        if idle >= timeout_secs {
+            IdleAction::Stop
+        } else if !self.warned && idle + warning_secs >= timeout_secs {
+            self.warned = true;
+            IdleAction::Warn {
+                stops_in_secs: timeout_secs - idle,
+            }
+        } else {
+            IdleAction::None
+        }
+    }
+}
+
+/// Count a stats sample of one of the pod's containers towards its idle
+/// time.
+pub async fn record_cpu(state: &AppState, pod_id: &str, cpu_percent: f64) {
+    let mut s = state.lock().await;
+    if cpu_percent < s.settings.idle_cpu_threshold {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp();
+    s.idle_trackers
+        .entry(pod_id.to_string())
+        .or_insert_with(|| IdleTracker::new(now))
+        .busy(now);
+}
+
+/// Check running pods with an idle timeout every `CHECK_INTERVAL` for the
+/// life of the app, warning with `pod-idle` before stopping them.
+pub async fn watch(state: AppState, app: AppHandle) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        check_all(&state, &app).await;
+    }
+}
+
+async fn check_all(state: &AppState, app: &AppHandle) {
+    let now = chrono::Utc::now().timestamp();
+    let (pods, warning_secs) = {
+        let mut s = state.lock().await;
+        let running: HashSet<String> = s
+            .pods
+            .values()
+            .filter(|p| p.status == PodStatus::Running)
+            .map(|p| p.id.clone())
+            .collect();
+        // A pod starts over when it runs again
+        s.idle_trackers.retain(|id, _| running.contains(id));
+        let pods: Vec<_> = s
+            .pods
+            .values()
+            .filter(|p| running.contains(&p.id))
+            .map(|p| {
+                (
+                    p.id.clone(),
+                    p.name.clone(),
+                    p.container_id.clone(),
+                    s.docker_for(&p.id),
+                    s.proxy_activity.get(&p.id).cloned(),
+                )
+            })
+            .collect();
+        (pods, s.settings.idle_warning)
+    };
+
+    for (pod_id, name, container_id, docker, activity) in pods {
+        let minutes = PodStateConfig::load(&name)
+            .ok()
+            .flatten()
+            .map(|c| c.idle_stop_minutes)
+            .unwrap_or(0);
+        if minutes == 0 {
+            continue;
+        }
+
+        // Open connections through an exposed port keep the pod up however
+        // long they sit quiet
+        let connected = activity.as_ref().is_some_and(|a| a.open_connections() > 0);
+        let in_terminal = match (&docker, &container_id) {
+            (Some(docker), Some(cid)) => has_terminal_session(docker, cid).await,
+            _ => false,
+        };
+
+        let (action, idle_secs) = {
+            let mut s = state.lock().await;
+            let tracker = s
+                .idle_trackers
+                .entry(pod_id.clone())
+                .or_insert_with(|| IdleTracker::new(now));
+            if let Some(at) = activity.as_ref().and_then(|a| a.last_traffic()) {
+                tracker.busy(at);
+            }
+            if connected || in_terminal {
+                tracker.busy(now);
+            }
+            let action = tracker.check(now, u64::from(minutes) * 60, warning_secs);
+            (action, tracker.idle_secs(now))
+        };
+
+        match action {
+            IdleAction::None => {}
+            IdleAction::Warn { stops_in_secs } => {
+                let _ = app.emit(
+                    "pod-idle",
+                    &PodIdleNotice {
+                        pod_id,
+                        idle_secs,
+                        stops_in_secs,
+                        stopped: false,
+                    },
+                );
+            }
+            IdleAction::Stop => {
+                tracing::info!("Stopping pod {} after {}s idle", name, idle_secs);
+                let result = crate::commands::stop_single_pod(
+                    app.state::<AppState>(),
+                    app.clone(),
+                    pod_id.clone(),
+                )
+                .await;
+                match result {
+                    Ok(()) => {
+                        let _ = app.emit(
+                            "pod-idle",
+                            &PodIdleNotice {
+                                pod_id,
+                                idle_secs,
+                                stops_in_secs: 0,
+                                stopped: true,
+                            },
+                        );
+                    }
+                    Err(e) => tracing::warn!("Failed to stop idle pod {}: {}", name, e),
+                }
+            }
+        }
+    }
+}
+
+/// Whether the container has an interactive `docker exec` session running,
+/// such as a terminal opened from Nook.
+async fn has_terminal_session(docker: &Docker, container_id: &str) -> bool {
+    let Ok(info) = containers::inspect_container(docker, container_id).await else {
+        return false;
+    };
+    for exec_id in info.exec_ids.unwrap_or_default() {
+        if let Ok(exec) = docker.inspect_exec(&exec_id).await {
+            if exec.running == Some(true) && exec.open_stdin == Some(true) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warns_then_stops() {
+        let mut tracker = IdleTracker::new(0);
+        assert_eq!(tracker.check(60, 600, 300), IdleAction::None);
+        assert_eq!(
+            tracker.check(330, 600, 300),
+            IdleAction::Warn { stops_in_secs: 270 }
+        );
+        // Warned once only
+        assert_eq!(tracker.check(360, 600, 300), IdleAction::None);
+        assert_eq!(tracker.check(600, 600, 300), IdleAction::Stop);
+    }
+
+    #[test]
+    fn test_activity_resets_idle_time() {
+        let mut tracker = IdleTracker::new(0);
+        assert!(matches!(
+            tracker.check(400, 600, 300),
+            IdleAction::Warn { .. }
+        ));
+        tracker.busy(450);
+        assert_eq!(tracker.idle_secs(500), 50);
+        assert_eq!(tracker.check(500, 600, 300), IdleAction::None);
+        // Older activity doesn't move it back
+        tracker.busy(100);
+        assert!(matches!(
+            tracker.check(760, 600, 300),
+            IdleAction::Warn { .. }
+        ));
+    }
+}
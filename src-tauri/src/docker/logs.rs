@@ -7,22 +7,26 @@ use tokio_util::sync::CancellationToken;
 use crate::state::AppState;
 use crate::types::{LogBatchEvent, LogEntry, LogLevel, LogSource};
 
+/// Number of existing log lines to fetch when a stream starts.
+const TAIL_LINES: u64 = 100;
+
 /// Stream container logs in real-time, batching into events.
-/// Follows the same cancellation pattern as `stream_stats`.
+/// Follows the same cancellation pattern as `stream_stats`. Entries from a
+/// compose sidecar are tagged with its `service` name.
 pub async fn stream_logs(
     docker: Docker,
     container_id: String,
     pod_id: String,
+    service: Option<String>,
     app: AppHandle,
     state: AppState,
     cancel: CancellationToken,
-    tail_lines: u64,
 ) {
     let options = LogsOptions::<String> {
         follow: true,
         stdout: true,
         stderr: true,
-        tail: tail_lines.to_string(),
+        tail: TAIL_LINES.to_string(),
         ..Default::default()
     };
 
@@ -68,6 +72,7 @@ pub async fn stream_logs(
                                 message: line.to_string(),
                                 source: LogSource::Container,
                                 level: level.clone(),
+                                service: service.clone(),
                             });
                         }
 
@@ -97,8 +102,11 @@ async fn flush_batch(
     pod_id: &str,
     batch: &mut Vec<LogEntry>,
 ) {
-    let entries: Vec<LogEntry> = std::mem::take(batch);
+    append_logs(app, state, pod_id, std::mem::take(batch)).await;
+}
 
+/// Store entries in the pod's `LogBuffer` and emit them as `pod-log-update`.
+pub async fn append_logs(app: &AppHandle, state: &AppState, pod_id: &str, entries: Vec<LogEntry>) {
     // Store in LogBuffer
     {
         let mut state = state.lock().await;
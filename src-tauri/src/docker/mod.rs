@@ -1,5 +1,18 @@
+pub mod compose;
+pub mod connection;
 pub mod containers;
+pub mod endpoint;
+pub mod events;
+pub mod idle;
 pub mod logs;
+pub mod monitor;
 pub mod ports;
 pub mod processes;
+pub mod restart;
+pub mod runtime;
+pub mod shutdown;
+pub mod snapshots;
+#[cfg(test)]
+pub(crate) mod stand_in;
 pub mod stats;
+pub mod volumes;
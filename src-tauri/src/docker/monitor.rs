@@ -0,0 +1,183 @@
+use tauri::AppHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::pod_state::PodStateConfig;
+use crate::devcontainer::ports::PortRules;
+use crate::network::scan::PortScanConfig;
+use crate::state::{AppState, AppStateInner};
+use crate::types::PodStatus;
+
+/// Cancel a pod's background tasks and its port proxies.
+pub fn stop_monitoring(state: &mut AppStateInner, pod_id: &str) {
+    if let Some(cancel) = state.cancellation_tokens.remove(pod_id) {
+        cancel.cancel();
+    }
+    state.proxy_activity.remove(pod_id);
+    let proxy_keys: Vec<(String, u16)> = state
+        .port_proxy_tokens
+        .keys()
+        .filter(|(pid, _)| pid == pod_id)
+        .cloned()
+        .collect();
+    for key in proxy_keys {
+        if let Some(cancel) = state.port_proxy_tokens.remove(&key) {
+            cancel.cancel();
+        }
+    }
+}
+
+/// Spawn the background tasks that follow a running pod (stats, logs,
+/// processes, ports) and register their cancellation token. Any tasks
+/// already running for the pod are cancelled first.
+///
+/// Running compose sidecars get their own stats, log and port tasks under
+/// the same token; process polling covers the pod's own container only.
+///
+/// Does nothing if Docker isn't connected or the pod has no container.
+pub fn start_monitoring(
+    state: &mut AppStateInner,
+    app_state: &AppState,
+    app: &AppHandle,
+    pod_id: &str,
+) {
+    let Some(docker) = state.docker_for(pod_id) else {
+        return;
+    };
+    let Some(pod) = state.pods.get(pod_id) else {
+        return;
+    };
+    let Some(container_id) = pod.container_id.clone() else {
+        return;
+    };
+    let rules = PortRules::load(&pod.project_path, pod.config_path.as_deref());
+    let pod_config = PodStateConfig::load(&pod.name).ok().flatten();
+    let services: Vec<(String, String)> = pod
+        .services
+        .iter()
+        .filter(|s| s.status == PodStatus::Running)
+        .map(|s| (s.name.clone(), s.container_id.clone()))
+        .collect();
+
+    let cancel = CancellationToken::new();
+    if let Some(old) = state
+        .cancellation_tokens
+        .insert(pod_id.to_string(), cancel.clone())
+    {
+        old.cancel();
+    }
+    let interval = state.settings.process_scan_interval;
+    let ps_args = state.runtime_for(pod_id).ps_args();
+    let port_interval = state.settings.ports_scan_interval;
+    let port_overrides = state.settings.port_protocols.clone();
+    let port_config = |service: Option<String>| PortScanConfig {
+        interval_ms: port_interval,
+        port_overrides: port_overrides.clone(),
+        rules: rules.clone(),
+        service,
+        pod_config: pod_config.clone(),
+    };
+
+    for (service, service_container) in services {
+        let docker_c = docker.clone();
+        let cid_c = service_container.clone();
+        let id_c = pod_id.to_string();
+        let service_c = Some(service.clone());
+        let app_c = app.clone();
+        let state_c = app_state.clone();
+        let cancel_c = cancel.clone();
+        tokio::spawn(async move {
+            crate::docker::stats::stream_stats(
+                docker_c, cid_c, id_c, service_c, app_c, state_c, cancel_c,
+            )
+            .await;
+        });
+
+        let docker_c = docker.clone();
+        let cid_c = service_container.clone();
+        let id_c = pod_id.to_string();
+        let service_c = Some(service.clone());
+        let app_c = app.clone();
+        let state_c = app_state.clone();
+        let cancel_c = cancel.clone();
+        tokio::spawn(async move {
+            crate::docker::logs::stream_logs(
+                docker_c, cid_c, id_c, service_c, app_c, state_c, cancel_c,
+            )
+            .await;
+        });
+
+        let docker_c = docker.clone();
+        let id_c = pod_id.to_string();
+        let app_c = app.clone();
+        let state_c = app_state.clone();
+        let cancel_c = cancel.clone();
+        let config = port_config(Some(service));
+        tokio::spawn(async move {
+            crate::network::scan::poll_ports(
+                docker_c,
+                service_container,
+                id_c,
+                app_c,
+                state_c,
+                cancel_c,
+                config,
+            )
+            .await;
+        });
+    }
+
+    // Stats streaming
+    let docker_c = docker.clone();
+    let cid_c = container_id.clone();
+    let id_c = pod_id.to_string();
+    let app_c = app.clone();
+    let state_c = app_state.clone();
+    let cancel_c = cancel.clone();
+    tokio::spawn(async move {
+        crate::docker::stats::stream_stats(docker_c, cid_c, id_c, None, app_c, state_c, cancel_c)
+            .await;
+    });
+
+    // Container log streaming
+    let docker_c = docker.clone();
+    let cid_c = container_id.clone();
+    let id_c = pod_id.to_string();
+    let app_c = app.clone();
+    let cancel_c = cancel.clone();
+    let state_c = app_state.clone();
+    tokio::spawn(async move {
+        crate::docker::logs::stream_logs(docker_c, cid_c, id_c, None, app_c, state_c, cancel_c)
+            .await;
+    });
+
+    // Process polling
+    let docker_c = docker.clone();
+    let cid_c = container_id.clone();
+    let id_c = pod_id.to_string();
+    let app_c = app.clone();
+    let cancel_c = cancel.clone();
+    tokio::spawn(async move {
+        crate::docker::processes::poll_processes(
+            docker_c, cid_c, id_c, app_c, cancel_c, interval, ps_args,
+        )
+        .await;
+    });
+
+    // Port scanning
+    let port_config = port_config(None);
+    let id_c = pod_id.to_string();
+    let app_c = app.clone();
+    let state_c = app_state.clone();
+    tokio::spawn(async move {
+        crate::network::scan::poll_ports(
+            docker,
+            container_id,
+            id_c,
+            app_c,
+            state_c,
+            cancel,
+            port_config,
+        )
+        .await;
+    });
+}
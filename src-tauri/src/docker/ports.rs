@@ -40,6 +40,8 @@ pub async fn get_container_port_bindings(
                             protocol: String::new(),
                             status: crate::types::PortStatus::Active,
                             auto_expose: false,
+                            label: None,
+                            service: None,
                         });
                     }
                 }
@@ -5,6 +5,8 @@ use tokio_util::sync::CancellationToken;
 
 use crate::types::{Process, ProcessListUpdate};
 
+/// Poll a container's processes as `process-list-update` events. `ps_args`
+/// come from `RuntimeInfo::ps_args`, as Podman doesn't accept `ps` options.
 pub async fn poll_processes(
     docker: Docker,
     container_id: String,
@@ -12,6 +14,7 @@ pub async fn poll_processes(
     app: AppHandle,
     cancel: CancellationToken,
     interval_ms: u64,
+    ps_args: &'static str,
 ) {
     let interval = tokio::time::Duration::from_millis(interval_ms);
 
@@ -26,7 +29,7 @@ pub async fn poll_processes(
                     tracing::debug!("Process polling cancelled (post-sleep) for pod {}", pod_id);
                     break;
                 }
-                match get_processes(&docker, &container_id).await {
+                match get_processes(&docker, &container_id, ps_args).await {
                     Ok(processes) => {
                         let update = ProcessListUpdate {
                             pod_id: pod_id.clone(),
@@ -48,17 +51,26 @@ pub async fn poll_processes(
 async fn get_processes(
     docker: &Docker,
     container_id: &str,
+    ps_args: &str,
 ) -> Result<Vec<Process>, bollard::errors::Error> {
-    let options = TopOptions { ps_args: "aux" };
+    let options = TopOptions { ps_args };
     let top = docker.top_processes(container_id, Some(options)).await?;
 
-    let titles = top.titles.unwrap_or_default();
-    let processes_raw = top.processes.unwrap_or_default();
+    Ok(parse_top(
+        &top.titles.unwrap_or_default(),
+        top.processes.unwrap_or_default(),
+    ))
+}
 
+fn parse_top(titles: &[String], processes_raw: Vec<Vec<String>>) -> Vec<Process> {
     let pid_idx = titles.iter().position(|t| t == "PID").unwrap_or(1);
     let cmd_idx = titles.iter().position(|t| t == "COMMAND").unwrap_or(titles.len().saturating_sub(1));
     let cpu_idx = titles.iter().position(|t| t == "%CPU").unwrap_or(2);
-    let mem_idx = titles.iter().position(|t| t == "RSS" || t == "VSZ").unwrap_or(5);
+    let mem_idx = titles
+        .iter()
+        .position(|t| t == "RSS")
+        .or_else(|| titles.iter().position(|t| t == "VSZ"))
+        .unwrap_or(5);
 
     let mut processes = Vec::new();
     for row in processes_raw {
@@ -71,7 +83,7 @@ async fn get_processes(
             .unwrap_or(0.0);
         let memory_bytes = row
             .get(mem_idx)
-            .and_then(|s| s.parse::<u64>().ok())
+            .and_then(|s| s.trim_end_matches(" kB").parse::<u64>().ok())
             .unwrap_or(0)
             * 1024; // RSS is in KB
 
@@ -83,5 +95,34 @@ async fn get_processes(
         });
     }
 
-    Ok(processes)
+    processes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(row: &[&str]) -> Vec<String> {
+        row.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_top_docker_and_podman() {
+        let docker = parse_top(
+            &strings(&["USER", "PID", "%CPU", "%MEM", "VSZ", "RSS", "TTY", "STAT", "START", "TIME", "COMMAND"]),
+            vec![strings(&["vscode", "27", "2.1", "0.6", "1203112", "98304", "?", "Sl", "10:15", "0:01", "node server.js"])],
+        );
+        // Recorded from Podman 4.9 with `ps_args=pid,pcpu,rss,args`
+        let podman = parse_top(
+            &strings(&["PID", "%CPU", "RSS", "COMMAND"]),
+            vec![strings(&["27", "2.100", "98304", "node server.js"])],
+        );
+        for processes in [docker, podman] {
+            assert_eq!(processes.len(), 1);
+            assert_eq!(processes[0].pid, 27);
+            assert_eq!(processes[0].name, "node");
+            assert_eq!(processes[0].cpu_percent, 2.1);
+            assert_eq!(processes[0].memory_bytes, 98304 * 1024);
+        }
+    }
 }
@@ -0,0 +1,361 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::pod_state::{PodStateConfig, RestartPolicy};
+use crate::config::settings::Settings;
+use crate::state::{AppState, AppStateInner};
+use crate::types::{ContainerExit, RestartRecord};
+
+const MAX_HISTORY: usize = 50;
+const MAX_BACKOFF_SECS: u64 = 300;
+/// How long after a `docker stop` or `docker kill` an exit still counts as
+/// caused by it. `docker stop` waits 10s before killing by default.
+const MANUAL_STOP_SECS: i64 = 60;
+
+/// When a pod's container is restarted after it exits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestartRules {
+    pub policy: RestartPolicy,
+    /// 0 means no limit
+    pub max_retries: u32,
+    /// 0 disables crash-loop detection
+    pub crash_loop_exits: u32,
+    pub crash_loop_window_secs: u64,
+}
+
+impl RestartRules {
+    pub fn resolve(settings: &Settings, pod_config: Option<&PodStateConfig>) -> Self {
+        Self {
+            policy: pod_config.map(|c| c.restart_policy).unwrap_or_default(),
+            max_retries: pod_config.map(|c| c.restart_max_retries).unwrap_or(0),
+            crash_loop_exits: settings.crash_loop_exits,
+            crash_loop_window_secs: settings.crash_loop_window,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestartDecision {
+    /// The policy doesn't restart after this exit
+    Skip,
+    Restart(Duration),
+    /// Restarting was given up; the pod should move to `Error` with this message
+    GiveUp(String),
+}
+
+/// Exits and restarts of one pod.
+#[derive(Default)]
+pub struct RestartTracker {
+    history: VecDeque<RestartRecord>,
+    /// Restarts since the container last ran for a full crash-loop window
+    retries: u32,
+    /// Exits at or before this time don't count towards a crash loop
+    since: Option<i64>,
+    /// Restart waiting out its backoff
+    pending: Option<CancellationToken>,
+    /// When the container was last sent a signal from outside Nook
+    stop_requested: Option<i64>,
+}
+
+impl RestartTracker {
+    pub fn history(&self) -> Vec<RestartRecord> {
+        self.history.iter().cloned().collect()
+    }
+
+    /// Note a `docker stop` or `docker kill` of the container at `at`. The
+    /// exit that follows is left alone, whatever the policy.
+    pub fn on_stop_requested(&mut self, at: i64) {
+        self.stop_requested = Some(at);
+    }
+
+    /// Record an exit and decide whether to restart. `ran_secs` is how long
+    /// the container was up; a container that stayed up for the whole
+    /// crash-loop window starts over with the shortest backoff.
+    pub fn on_exit(
+        &mut self,
+        exit: ContainerExit,
+        ran_secs: Option<i64>,
+        rules: &RestartRules,
+    ) -> RestartDecision {
+        let window = rules.crash_loop_window_secs as i64;
+        if ran_secs.is_some_and(|secs| secs >= window) {
+            self.retries = 0;
+        }
+        let failed = exit.exit_code != 0 || exit.oom_killed;
+        let stopped = self
+            .stop_requested
+            .take()
+            .is_some_and(|at| exit.finished_at - at <= MANUAL_STOP_SECS);
+        let wants_restart = match rules.policy {
+            _ if stopped => false,
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => failed,
+            RestartPolicy::Always => true,
+        };
+
+        let recent = 1 + self
+            .history
+            .iter()
+            .filter(|r| self.since.is_none_or(|since| r.exit.finished_at > since))
+            .filter(|r| r.exit.finished_at > exit.finished_at - window)
+            .count();
+        let decision = if !wants_restart {
+            RestartDecision::Skip
+        } else if rules.crash_loop_exits > 0 && recent >= rules.crash_loop_exits as usize {
+            RestartDecision::GiveUp(format!(
+                "Crash loop: container exited {} times within {}s (last exit code {})",
+                recent, window, exit.exit_code
+            ))
+        } else if rules.policy == RestartPolicy::OnFailure
+            && rules.max_retries > 0
+            && self.retries >= rules.max_retries
+        {
+            RestartDecision::GiveUp(format!(
+                "Gave up after {} restarts (last exit code {})",
+                self.retries, exit.exit_code
+            ))
+        } else {
+            let delay = backoff(self.retries);
+            self.retries += 1;
+            RestartDecision::Restart(delay)
+        };
+
+        if let RestartDecision::GiveUp(_) = decision {
+            // A manual start gets a fresh budget
+            self.retries = 0;
+            self.since = Some(exit.finished_at);
+        }
+        if self.history.len() >= MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(RestartRecord {
+            exit,
+            restart_delay_secs: match decision {
+                RestartDecision::Restart(delay) => Some(delay.as_secs()),
+                _ => None,
+            },
+        });
+        decision
+    }
+}
+
+/// 1s, 2s, 4s, ... up to five minutes.
+fn backoff(retries: u32) -> Duration {
+    Duration::from_secs(
+        2u64.checked_pow(retries)
+            .unwrap_or(MAX_BACKOFF_SECS)
+            .min(MAX_BACKOFF_SECS),
+    )
+}
+
+/// Record an exit of a pod's container that Nook didn't cause, under the
+/// pod's restart policy.
+pub fn on_exit(
+    state: &mut AppStateInner,
+    pod_id: &str,
+    exit: ContainerExit,
+    ran_secs: Option<i64>,
+) -> RestartDecision {
+    let pod_config = state
+        .pods
+        .get(pod_id)
+        .and_then(|p| PodStateConfig::load(&p.name).ok().flatten());
+    let rules = RestartRules::resolve(&state.settings, pod_config.as_ref());
+    state
+        .restart_trackers
+        .entry(pod_id.to_string())
+        .or_default()
+        .on_exit(exit, ran_secs, &rules)
+}
+
+/// Record that the pod's container was sent a signal from outside Nook, so
+/// its exit isn't restarted.
+pub fn on_stop_requested(state: &mut AppStateInner, pod_id: &str) {
+    state
+        .restart_trackers
+        .entry(pod_id.to_string())
+        .or_default()
+        .on_stop_requested(chrono::Utc::now().timestamp());
+}
+
+/// Start the pod again through `start_pod` once `delay` has passed, unless
+/// the restart is cancelled first.
+pub fn schedule(state: &mut AppStateInner, app: &AppHandle, pod_id: &str, delay: Duration) {
+    let cancel = CancellationToken::new();
+    let tracker = state
+        .restart_trackers
+        .entry(pod_id.to_string())
+        .or_default();
+    if let Some(old) = tracker.pending.replace(cancel.clone()) {
+        old.cancel();
+    }
+
+    let app = app.clone();
+    let pod_id = pod_id.to_string();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        let state = app.state::<AppState>();
+        {
+            let mut s = state.lock().await;
+            if cancel.is_cancelled() {
+                return;
+            }
+            if let Some(tracker) = s.restart_trackers.get_mut(&pod_id) {
+                tracker.pending = None;
+            }
+        }
+        tracing::info!("Restarting pod {} after its container exited", pod_id);
+        if let Err(e) = crate::commands::start_pod(state, app.clone(), pod_id.clone()).await {
+            tracing::warn!("Failed to restart pod {}: {}", pod_id, e);
+        }
+    });
+}
+
+/// Drop a restart still waiting out its backoff.
+pub fn cancel_pending(state: &mut AppStateInner, pod_id: &str) {
+    if let Some(cancel) = state
+        .restart_trackers
+        .get_mut(pod_id)
+        .and_then(|t| t.pending.take())
+    {
+        cancel.cancel();
+    }
+}
+
+/// Forget the pod's restart count and earlier exits for crash-loop
+/// detection, e.g. after the user stopped it. The history is kept.
+pub fn reset(state: &mut AppStateInner, pod_id: &str) {
+    cancel_pending(state, pod_id);
+    if let Some(tracker) = state.restart_trackers.get_mut(pod_id) {
+        tracker.retries = 0;
+        tracker.since = Some(chrono::Utc::now().timestamp());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(exit_code: i64, finished_at: i64) -> ContainerExit {
+        ContainerExit {
+            exit_code,
+            oom_killed: false,
+            finished_at,
+        }
+    }
+
+    fn rules(policy: RestartPolicy, max_retries: u32) -> RestartRules {
+        RestartRules {
+            policy,
+            max_retries,
+            crash_loop_exits: 5,
+            crash_loop_window_secs: 120,
+        }
+    }
+
+    #[test]
+    fn test_on_failure_backs_off_and_gives_up() {
+        let rules = rules(RestartPolicy::OnFailure, 3);
+        let mut tracker = RestartTracker::default();
+
+        // A clean exit is left alone but still recorded
+        assert_eq!(
+            tracker.on_exit(exit(0, 0), None, &rules),
+            RestartDecision::Skip
+        );
+
+        // Exits far apart, so only the retry limit applies
+        let delays: Vec<_> = (1..=3)
+            .map(|n| tracker.on_exit(exit(1, n * 1000), Some(10), &rules))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                RestartDecision::Restart(Duration::from_secs(1)),
+                RestartDecision::Restart(Duration::from_secs(2)),
+                RestartDecision::Restart(Duration::from_secs(4)),
+            ]
+        );
+        assert_eq!(
+            tracker.on_exit(exit(1, 4000), Some(10), &rules),
+            RestartDecision::GiveUp("Gave up after 3 restarts (last exit code 1)".to_string())
+        );
+
+        let history = tracker.history();
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[1].restart_delay_secs, Some(1));
+        assert_eq!(history[4].restart_delay_secs, None);
+    }
+
+    #[test]
+    fn test_long_run_resets_backoff() {
+        let rules = rules(RestartPolicy::Always, 0);
+        let mut tracker = RestartTracker::default();
+        tracker.on_exit(exit(0, 1000), Some(5), &rules);
+        tracker.on_exit(exit(0, 2000), Some(5), &rules);
+        assert_eq!(
+            tracker.on_exit(exit(0, 3000), Some(600), &rules),
+            RestartDecision::Restart(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_manual_stop_is_not_restarted() {
+        let rules = rules(RestartPolicy::Always, 0);
+        let mut tracker = RestartTracker::default();
+        tracker.on_stop_requested(1000);
+        assert_eq!(
+            tracker.on_exit(exit(143, 1010), Some(600), &rules),
+            RestartDecision::Skip
+        );
+        assert_eq!(tracker.history()[0].restart_delay_secs, None);
+
+        // Only the exit right after the stop is left alone
+        assert_eq!(
+            tracker.on_exit(exit(1, 2000), Some(600), &rules),
+            RestartDecision::Restart(Duration::from_secs(1))
+        );
+        tracker.on_stop_requested(3000);
+        assert_eq!(
+            tracker.on_exit(exit(1, 4000), Some(600), &rules),
+            RestartDecision::Restart(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_crash_loop() {
+        let rules = rules(RestartPolicy::Always, 0);
+        let mut tracker = RestartTracker::default();
+        for t in 0..4 {
+            assert!(matches!(
+                tracker.on_exit(exit(137, t * 10), Some(2), &rules),
+                RestartDecision::Restart(_)
+            ));
+        }
+        assert_eq!(
+            tracker.on_exit(exit(137, 40), Some(2), &rules),
+            RestartDecision::GiveUp(
+                "Crash loop: container exited 5 times within 120s (last exit code 137)".to_string()
+            )
+        );
+        // Earlier exits don't count against a manual start
+        assert_eq!(
+            tracker.on_exit(exit(137, 50), Some(2), &rules),
+            RestartDecision::Restart(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(8), Duration::from_secs(256));
+        assert_eq!(backoff(9), Duration::from_secs(MAX_BACKOFF_SECS));
+        assert_eq!(backoff(64), Duration::from_secs(MAX_BACKOFF_SECS));
+    }
+}
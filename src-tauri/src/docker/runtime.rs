@@ -0,0 +1,213 @@
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+
+use crate::docker::containers;
+use crate::docker::endpoint::DockerEndpoint;
+
+/// `ps` arguments for `top_processes`. Podman doesn't run `ps` in the
+/// container; it takes format descriptors, whose titles match Docker's.
+const DOCKER_PS_ARGS: &str = "aux";
+const PODMAN_PS_ARGS: &str = "pid,pcpu,rss,args";
+
+/// The daemon behind a Docker endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    /// Podman's Docker-compatible API
+    Podman,
+}
+
+/// What Nook adapts to per endpoint, detected on connect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeInfo {
+    pub runtime: ContainerRuntime,
+    /// The daemon runs without root; container IPs are then not reachable
+    /// from the host
+    pub rootless: bool,
+}
+
+impl RuntimeInfo {
+    /// Ask the daemon what it is. Falls back to rootful Docker if it won't say.
+    pub async fn detect(docker: &Docker) -> Self {
+        let runtime = match docker.version().await {
+            Ok(version) => {
+                let components = version.components.unwrap_or_default();
+                runtime_from_components(components.iter().map(|c| c.name.as_str()))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read Docker version: {}", e);
+                ContainerRuntime::Docker
+            }
+        };
+        let rootless = match docker.info().await {
+            Ok(info) => is_rootless(&info.security_options.unwrap_or_default()),
+            Err(e) => {
+                tracing::warn!("Failed to read Docker info: {}", e);
+                false
+            }
+        };
+        Self { runtime, rootless }
+    }
+
+    pub fn is_podman(self) -> bool {
+        self.runtime == ContainerRuntime::Podman
+    }
+
+    pub fn ps_args(self) -> &'static str {
+        match self.runtime {
+            ContainerRuntime::Docker => DOCKER_PS_ARGS,
+            ContainerRuntime::Podman => PODMAN_PS_ARGS,
+        }
+    }
+
+    /// User to exec as when none is configured. Under Podman's `keep-id`
+    /// user namespace the container runs as the host user's `uid:gid`,
+    /// which owns the bind-mounted workspace; name it explicitly so execs
+    /// can write there. `None` leaves the choice to the daemon.
+    pub async fn default_exec_user(self, docker: &Docker, container_id: &str) -> Option<String> {
+        if !self.is_podman() {
+            return None;
+        }
+        let info = containers::inspect_container(docker, container_id)
+            .await
+            .ok()?;
+        keep_id_user(
+            info.host_config.and_then(|h| h.userns_mode).as_deref(),
+            info.config.and_then(|c| c.user).as_deref(),
+        )
+    }
+
+    /// Program and global flags for running `<cli> exec` against `endpoint`
+    /// from a terminal.
+    pub fn exec_cli(self, endpoint: Option<&DockerEndpoint>) -> Vec<String> {
+        match self.runtime {
+            ContainerRuntime::Docker => std::iter::once("docker".to_string())
+                .chain(endpoint.map(|e| e.docker_cli_args()).unwrap_or_default())
+                .collect(),
+            ContainerRuntime::Podman => {
+                let mut cli = vec!["podman".to_string()];
+                if let Some(endpoint) = endpoint {
+                    cli.push("--url".to_string());
+                    cli.push(endpoint.host.clone());
+                }
+                cli
+            }
+        }
+    }
+}
+
+/// Podman reports itself as a `Podman Engine` component of `/version`.
+fn runtime_from_components<'a>(names: impl IntoIterator<Item = &'a str>) -> ContainerRuntime {
+    if names
+        .into_iter()
+        .any(|name| name.to_lowercase().contains("podman"))
+    {
+        ContainerRuntime::Podman
+    } else {
+        ContainerRuntime::Docker
+    }
+}
+
+fn keep_id_user(userns_mode: Option<&str>, config_user: Option<&str>) -> Option<String> {
+    if !userns_mode?.starts_with("keep-id") {
+        return None;
+    }
+    config_user.filter(|u| !u.is_empty()).map(String::from)
+}
+
+/// Rootless Docker and Podman both list `name=rootless` in `/info`.
+fn is_rootless(security_options: &[String]) -> bool {
+    security_options
+        .iter()
+        .any(|o| o.split(',').any(|kv| kv == "name=rootless"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::docker::stand_in::{self, Reply, StandIn};
+
+    // Recorded from Podman 4.9 (rootless) via the Docker-compatible socket
+    const PODMAN_VERSION: &str = r#"{"Platform":{"Name":"linux/amd64/fedora-39"},"Components":[{"Name":"Podman Engine","Version":"4.9.4","Details":{"APIVersion":"4.9.4","Arch":"amd64","BuildTime":"2024-04-01T00:00:00Z","Experimental":"false","GitCommit":"","GoVersion":"go1.21.9","KernelVersion":"6.8.5-201.fc39.x86_64","MinAPIVersion":"4.0.0","Os":"linux"}},{"Name":"Conmon","Version":"conmon version 2.1.10","Details":{"Package":"conmon-2.1.10-1.fc39.x86_64"}},{"Name":"OCI Runtime (crun)","Version":"crun version 1.14.4","Details":{"Package":"crun-1.14.4-1.fc39.x86_64"}}],"Version":"4.9.4","ApiVersion":"1.41","MinAPIVersion":"1.24","GitCommit":"","GoVersion":"go1.21.9","Os":"linux","Arch":"amd64","KernelVersion":"6.8.5-201.fc39.x86_64","BuildTime":"2024-04-01T00:00:00+00:00"}"#;
+    const PODMAN_INFO: &str = r#"{"ID":"a2e5b6c1-7d2f-4a3e-9b0c-1f2e3d4c5b6a","Containers":2,"ContainersRunning":1,"ContainersPaused":0,"ContainersStopped":1,"Images":5,"Driver":"overlay","DriverStatus":[["Backing Filesystem","btrfs"]],"MemoryLimit":true,"SwapLimit":true,"CpuCfsPeriod":true,"CpuCfsQuota":true,"CPUShares":true,"CPUSet":true,"PidsLimit":true,"IPv4Forwarding":true,"Debug":false,"NFd":0,"OomKillDisable":false,"NGoroutines":0,"SystemTime":"2024-05-02T10:15:03.123456789+02:00","LoggingDriver":"k8s-file","CgroupDriver":"systemd","NEventsListener":0,"KernelVersion":"6.8.5-201.fc39.x86_64","OperatingSystem":"fedora","OSVersion":"39","OSType":"linux","Architecture":"amd64","NCPU":8,"MemTotal":33324122112,"DockerRootDir":"/home/dev/.local/share/containers/storage","Name":"workstation","ServerVersion":"4.9.4","Runtimes":{"crun":{"path":"/usr/bin/crun"}},"DefaultRuntime":"crun","SecurityOptions":["name=seccomp,profile=default","name=rootless","name=selinux"],"CgroupVersion":"2"}"#;
+    const DOCKER_VERSION: &str = r#"{"Platform":{"Name":"Docker Engine - Community"},"Components":[{"Name":"Engine","Version":"26.1.1","Details":{"ApiVersion":"1.45","Arch":"amd64","MinAPIVersion":"1.24","Os":"linux"}},{"Name":"containerd","Version":"1.6.31","Details":{"GitCommit":"e377cd56a71523140ca6ae87e30244719194a521"}},{"Name":"runc","Version":"1.1.12","Details":{"GitCommit":"v1.1.12-0-g51d5e94"}}],"Version":"26.1.1","ApiVersion":"1.45","MinAPIVersion":"1.24","Os":"linux","Arch":"amd64"}"#;
+    const DOCKER_INFO: &str = r#"{"ID":"3b4c","Containers":0,"Driver":"overlay2","NCPU":8,"CgroupVersion":"2","SecurityOptions":["name=apparmor","name=seccomp,profile=builtin","name=cgroupns"]}"#;
+
+    /// Serve recorded `/version` and `/info` responses, as a stand-in for a
+    /// daemon.
+    fn stand_in(version: &'static str, info: &'static str) -> StandIn {
+        stand_in::serve(move |request| {
+            if request.ends_with("/version") {
+                Reply::ok(version)
+            } else if request.ends_with("/info") {
+                Reply::ok(info)
+            } else {
+                Reply::ok("{}")
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_detect_podman() {
+        let daemon = stand_in(PODMAN_VERSION, PODMAN_INFO);
+        let info = RuntimeInfo::detect(&daemon.docker).await;
+        assert_eq!(
+            info,
+            RuntimeInfo {
+                runtime: ContainerRuntime::Podman,
+                rootless: true,
+            }
+        );
+        assert_eq!(info.ps_args(), PODMAN_PS_ARGS);
+    }
+
+    #[tokio::test]
+    async fn test_detect_docker() {
+        let daemon = stand_in(DOCKER_VERSION, DOCKER_INFO);
+        assert_eq!(
+            RuntimeInfo::detect(&daemon.docker).await,
+            RuntimeInfo::default()
+        );
+    }
+
+    #[test]
+    fn test_keep_id_user() {
+        assert_eq!(
+            keep_id_user(Some("keep-id:uid=1000,gid=1000"), Some("1000:1000")),
+            Some("1000:1000".to_string())
+        );
+        assert_eq!(keep_id_user(Some("private"), Some("1000:1000")), None);
+        assert_eq!(keep_id_user(None, Some("vscode")), None);
+        assert_eq!(keep_id_user(Some("keep-id"), Some("")), None);
+    }
+
+    #[test]
+    fn test_exec_cli() {
+        let endpoint = DockerEndpoint {
+            name: "podman".to_string(),
+            host: "unix:///run/user/1000/podman/podman.sock".to_string(),
+            tls_dir: None,
+            context: None,
+        };
+        let podman = RuntimeInfo {
+            runtime: ContainerRuntime::Podman,
+            rootless: true,
+        };
+        assert_eq!(
+            podman.exec_cli(Some(&endpoint)),
+            vec![
+                "podman",
+                "--url",
+                "unix:///run/user/1000/podman/podman.sock"
+            ]
+        );
+        assert_eq!(
+            RuntimeInfo::default().exec_cli(Some(&endpoint)),
+            vec!["docker", "-H", "unix:///run/user/1000/podman/podman.sock"]
+        );
+    }
+}
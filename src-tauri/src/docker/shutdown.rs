@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use bollard::Docker;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::pod_state::PodStateConfig;
+use crate::config::settings::Settings;
+use crate::docker::containers;
+use crate::error::{NookError, NookResult};
+use crate::types::PodStopProgress;
+
+/// How often a stopping container is checked, and progress emitted.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a pod's container is asked to shut down before it is killed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StopPolicy {
+    /// Signal sent first, e.g. `SIGTERM` or `SIGINT`
+    pub signal: String,
+    /// Seconds to wait for the container to exit before sending `SIGKILL`
+    pub timeout_secs: u64,
+}
+
+impl StopPolicy {
+    /// The pod's own stop signal and timeout, falling back to the global
+    /// defaults for anything it leaves unset.
+    pub fn resolve(settings: &Settings, pod_config: Option<&PodStateConfig>) -> Self {
+        let signal = pod_config
+            .map(|c| c.stop_signal.trim())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(settings.stop_signal.trim());
+        let signal = if signal.is_empty() { "SIGTERM" } else { signal };
+        Self {
+            signal: signal.to_string(),
+            timeout_secs: pod_config
+                .and_then(|c| c.stop_timeout)
+                .unwrap_or(settings.stop_timeout),
+        }
+    }
+}
+
+/// Stop a container in two phases: send the policy's signal, wait up to its
+/// timeout for the container to exit, then `SIGKILL` it. Emits
+/// `pod-stop-progress` every second while waiting and once on escalation.
+///
+/// Signals the daemon rejects fall back to Docker's own stop with the same
+/// timeout, so a typo in the settings can't leave a pod running.
+pub async fn stop_gracefully(
+    docker: &Docker,
+    app: &AppHandle,
+    pod_id: &str,
+    container_id: &str,
+    policy: &StopPolicy,
+) -> NookResult<()> {
+    if !is_running(docker, container_id).await? {
+        return Ok(());
+    }
+
+    let progress = |waited_secs: u64, escalated: bool| {
+        let _ = app.emit(
+            "pod-stop-progress",
+            &PodStopProgress {
+                pod_id: pod_id.to_string(),
+                signal: policy.signal.clone(),
+                waited_secs,
+                timeout_secs: policy.timeout_secs,
+                escalated,
+            },
+        );
+    };
+
+    match containers::signal_container(docker, container_id, &policy.signal).await {
+        Ok(()) => {}
+        Err(NookError::ContainerNotFound(_)) => return Ok(()),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to send {} to {}, stopping it instead: {}",
+                policy.signal,
+                container_id,
+                e
+            );
+            progress(0, false);
+            return containers::stop_container(docker, container_id, policy.timeout_secs).await;
+        }
+    }
+    progress(0, false);
+
+    let mut waited = Duration::ZERO;
+    while waited < Duration::from_secs(policy.timeout_secs) {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        waited += POLL_INTERVAL;
+        if !is_running(docker, container_id).await? {
+            return Ok(());
+        }
+        progress(waited.as_secs(), false);
+    }
+
+    tracing::info!(
+        "Container {} ignored {} for {}s, killing it",
+        container_id,
+        policy.signal,
+        policy.timeout_secs
+    );
+    progress(waited.as_secs(), true);
+    match containers::kill_container(docker, container_id).await {
+        Ok(()) | Err(NookError::ContainerNotFound(_)) => Ok(()),
+        // It may have exited between the last check and the kill
+        Err(e) if !is_running(docker, container_id).await? => {
+            tracing::debug!("Kill after exit of {}: {}", container_id, e);
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn is_running(docker: &Docker, container_id: &str) -> NookResult<bool> {
+    match containers::inspect_container(docker, container_id).await {
+        Ok(info) => Ok(info.state.and_then(|s| s.running).unwrap_or(false)),
+        Err(NookError::ContainerNotFound(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod_config(stop_signal: &str, stop_timeout: Option<u64>) -> PodStateConfig {
+        PodStateConfig {
+            name: "db".to_string(),
+            project_path: "/tmp/db".to_string(),
+            stop_signal: stop_signal.to_string(),
+            stop_timeout,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_stop_policy() {
+        let settings = Settings::default();
+        assert_eq!(
+            StopPolicy::resolve(&settings, None),
+            StopPolicy {
+                signal: "SIGTERM".to_string(),
+                timeout_secs: 10,
+            }
+        );
+
+        let config = pod_config("SIGINT", Some(60));
+        assert_eq!(
+            StopPolicy::resolve(&settings, Some(&config)),
+            StopPolicy {
+                signal: "SIGINT".to_string(),
+                timeout_secs: 60,
+            }
+        );
+
+        // Unset pod fields fall back to the global defaults
+        let settings = Settings {
+            stop_signal: "SIGQUIT".to_string(),
+            stop_timeout: 30,
+            ..Settings::default()
+        };
+        let config = pod_config(" ", None);
+        assert_eq!(
+            StopPolicy::resolve(&settings, Some(&config)),
+            StopPolicy {
+                signal: "SIGQUIT".to_string(),
+                timeout_secs: 30,
+            }
+        );
+    }
+}
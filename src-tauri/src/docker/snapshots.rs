@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use bollard::container::{
+    Config, CreateContainerOptions, NetworkingConfig, RenameContainerOptions,
+};
+use bollard::image::{CommitContainerOptions, RemoveImageOptions};
+use bollard::models::EndpointSettings;
+use bollard::Docker;
+
+use crate::config::snapshots::PodSnapshot;
+use crate::docker::containers;
+use crate::error::{NookError, NookResult};
+
+const SNAPSHOT_REPO: &str = "nook-snapshot";
+const POD_LABEL: &str = "nook.snapshot.pod";
+const CREATED_LABEL: &str = "nook.snapshot.created";
+const NOTE_LABEL: &str = "nook.snapshot.note";
+
+/// Image repository for a pod's snapshots. Docker only allows lowercase
+/// letters, digits and separators in repository names.
+fn image_repo(pod_name: &str) -> String {
+    let name: String = pod_name
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '-',
+        })
+        .collect();
+    let name = name.trim_matches(|c| matches!(c, '.' | '_' | '-'));
+    format!(
+        "{}/{}",
+        SNAPSHOT_REPO,
+        if name.is_empty() { "pod" } else { name }
+    )
+}
+
+/// Commit the container to an image tagged with the time to the millisecond,
+/// labelled with the pod, time and note. A running container is paused while it is written.
+pub async fn commit(
+    docker: &Docker,
+    container_id: &str,
+    pod_name: &str,
+    note: &str,
+) -> NookResult<PodSnapshot> {
+    let running = containers::inspect_container(docker, container_id)
+        .await?
+        .state
+        .and_then(|s| s.running)
+        .unwrap_or(false);
+    let now = chrono::Local::now();
+    // Milliseconds, so snapshots taken in the same second don't share a tag
+    let tag = now.format("%Y%m%d-%H%M%S%3f").to_string();
+    let repo = image_repo(pod_name);
+    let labels = HashMap::from([
+        (POD_LABEL.to_string(), pod_name.to_string()),
+        (CREATED_LABEL.to_string(), now.to_rfc3339()),
+        (NOTE_LABEL.to_string(), note.to_string()),
+    ]);
+    let options = CommitContainerOptions {
+        container: container_id.to_string(),
+        repo: repo.clone(),
+        tag: tag.clone(),
+        comment: note.to_string(),
+        author: "Nook".to_string(),
+        pause: running,
+        changes: None,
+    };
+    let config = Config {
+        labels: Some(labels),
+        ..Default::default()
+    };
+    docker
+        .commit_container(options, config)
+        .await
+        .map_err(|e| match e {
+            bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            } => NookError::ContainerNotFound(container_id.to_string()),
+            other => NookError::Docker(other),
+        })?;
+    Ok(PodSnapshot {
+        id: tag.clone(),
+        image: format!("{}:{}", repo, tag),
+        created_at: now.timestamp(),
+        note: note.to_string(),
+    })
+}
+
+/// Replace a stopped container with one created from `image`, keeping its
+/// name, labels, mounts, networks and host settings so `list_containers` and the
+/// devcontainer CLI still find it. Returns the new container's id.
+pub async fn recreate(docker: &Docker, container_id: &str, image: &str) -> NookResult<String> {
+    let info = containers::inspect_container(docker, container_id).await?;
+    let mut config: Config<String> = info
+        .config
+        .map(Config::from)
+        .ok_or_else(|| NookError::DockerConnection("Container has no config".to_string()))?;
+    config.image = Some(image.to_string());
+    config.host_config = info.host_config;
+    config.networking_config = info
+        .network_settings
+        .and_then(|s| s.networks)
+        .map(|networks| NetworkingConfig {
+            endpoints_config: networks
+                .into_iter()
+                .map(|(name, endpoint)| (name, endpoint_config(endpoint)))
+                .collect(),
+        });
+
+    // Created before the old one goes, so a bad image leaves the pod as it was
+    let created = docker
+        .create_container(None::<CreateContainerOptions<String>>, config)
+        .await?;
+    if let Err(e) = containers::remove_container(docker, container_id, false).await {
+        let _ = containers::remove_container(docker, &created.id, false).await;
+        return Err(e);
+    }
+    if let Some(name) = info.name.as_deref().map(|n| n.trim_start_matches('/')) {
+        let options = RenameContainerOptions { name };
+        if let Err(e) = docker.rename_container(&created.id, options).await {
+            tracing::warn!("Failed to keep container name {}: {}", name, e);
+        }
+    }
+    Ok(created.id)
+}
+
+/// What a new container needs of an inspected network endpoint. Addresses
+/// Docker assigned are left out so it can assign them again.
+fn endpoint_config(endpoint: EndpointSettings) -> EndpointSettings {
+    EndpointSettings {
+        ipam_config: endpoint.ipam_config,
+        links: endpoint.links,
+        aliases: endpoint.aliases,
+        driver_opts: endpoint.driver_opts,
+        ..Default::default()
+    }
+}
+
+/// Remove a snapshot's image. One already gone counts as removed.
+pub async fn remove_image(docker: &Docker, image: &str) -> NookResult<()> {
+    match docker
+        .remove_image(image, None::<RemoveImageOptions>, None)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(()),
+        Err(e) => Err(NookError::Docker(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_repo() {
+        assert_eq!(image_repo("api"), "nook-snapshot/api");
+        assert_eq!(image_repo("My App (2)"), "nook-snapshot/my-app--2");
+        assert_eq!(image_repo("---"), "nook-snapshot/pod");
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use bollard::Docker;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// How the stand-in answers a request.
+pub(crate) enum Reply {
+    /// A JSON body with an HTTP status such as `200 OK`
+    Json(&'static str, String),
+    /// Upgrade the connection, as `exec/start` does, and echo what is sent
+    Echo,
+}
+
+impl Reply {
+    pub(crate) fn ok(body: &str) -> Self {
+        Self::Json("200 OK", body.to_string())
+    }
+}
+
+/// A running stand-in. Its socket is removed when it is dropped.
+pub(crate) struct StandIn {
+    pub docker: Docker,
+    socket: PathBuf,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StandIn {
+    /// Requests received so far, as `METHOD path body`.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// Start a stand-in that answers each request with `reply`, which gets the
+/// request as `METHOD path`.
+pub(crate) fn serve(reply: impl Fn(&str) -> Reply + Send + Sync + 'static) -> StandIn {
+    let socket = std::env::temp_dir().join(format!("nook-daemon-{}.sock", uuid::Uuid::new_v4()));
+    let listener = UnixListener::bind(&socket).unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    let reply = Arc::new(reply);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let seen = seen.clone();
+            let reply = reply.clone();
+            tokio::spawn(async move {
+                let mut stream = BufReader::new(stream);
+                let Some((request, body)) = read_request(&mut stream).await else {
+                    return;
+                };
+                seen.lock().unwrap().push(format!("{} {}", request, body));
+                let stream = stream.get_mut();
+                match reply(&request) {
+                    Reply::Json(status, body) => {
+                        let response = format!(
+                            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            status,
+                            body.len(),
+                            body
+                        );
+                        let _ = stream.write_all(response.as_bytes()).await;
+                    }
+                    Reply::Echo => echo(stream).await,
+                }
+            });
+        }
+    });
+    let docker =
+        Docker::connect_with_unix(&socket.to_string_lossy(), 5, bollard::API_DEFAULT_VERSION)
+            .unwrap();
+    StandIn {
+        docker,
+        socket,
+        requests,
+    }
+}
+
+/// The request line as `METHOD path`, and the body.
+async fn read_request(stream: &mut BufReader<UnixStream>) -> Option<(String, String)> {
+    let mut request_line = String::new();
+    stream.read_line(&mut request_line).await.ok()?;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        stream.read_line(&mut header).await.ok()?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await.ok()?;
+    let mut parts = request_line.split_whitespace();
+    let request = format!("{} {}", parts.next()?, parts.next()?);
+    Some((request, String::from_utf8_lossy(&body).to_string()))
+}
+
+async fn echo(stream: &mut UnixStream) {
+    let upgraded = "HTTP/1.1 101 UPGRADED\r\n\
+                    Content-Type: application/vnd.docker.raw-stream\r\n\
+                    Connection: Upgrade\r\nUpgrade: tcp\r\n\r\n";
+    if stream.write_all(upgraded.as_bytes()).await.is_err() {
+        return;
+    }
+    let mut buf = vec![0; 1024];
+    while let Ok(n) = stream.read(&mut buf).await {
+        if n == 0 || stream.write_all(&buf[..n]).await.is_err() {
+            break;
+        }
+    }
+}
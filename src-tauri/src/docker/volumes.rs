@@ -0,0 +1,387 @@
+use std::path::{Path, PathBuf};
+
+use bollard::container::{DownloadFromContainerOptions, UploadToContainerOptions};
+use bollard::Docker;
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::docker::containers;
+use crate::error::{NookError, NookResult};
+use crate::types::{BackedUpVolume, PodMount, VolumeBackup, VolumeTransferProgress};
+
+/// Describes a backup, next to its archives.
+const MANIFEST: &str = "backup.toml";
+
+/// Bytes between two progress events of the same volume.
+const PROGRESS_STEP: u64 = 4 * 1024 * 1024;
+
+const READ_CHUNK: usize = 256 * 1024;
+
+/// The container's volume and bind mounts, as `docker inspect` lists them.
+pub async fn list_mounts(docker: &Docker, container_id: &str) -> NookResult<Vec<PodMount>> {
+    let info = containers::inspect_container(docker, container_id).await?;
+    Ok(info
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| PodMount {
+            kind: m.typ.map(|t| t.to_string()).unwrap_or_default(),
+            name: m.name.filter(|n| !n.is_empty()),
+            source: m.source.unwrap_or_default(),
+            destination: m.destination.unwrap_or_default(),
+            read_only: m.rw == Some(false),
+        })
+        .collect())
+}
+
+/// Reports a backup's or restore's progress as `volume-transfer-progress`.
+#[derive(Clone)]
+struct Transfer {
+    app: AppHandle,
+    pod_id: String,
+    backup_id: String,
+    restoring: bool,
+}
+
+impl Transfer {
+    fn report(&self, volume: &str, bytes: u64, total: Option<u64>, done: bool) {
+        let _ = self.app.emit(
+            "volume-transfer-progress",
+            &VolumeTransferProgress {
+                pod_id: self.pod_id.clone(),
+                backup_id: self.backup_id.clone(),
+                volume: volume.to_string(),
+                restoring: self.restoring,
+                bytes,
+                total,
+                done,
+            },
+        );
+    }
+}
+
+/// Whether `bytes` crossed into a new `PROGRESS_STEP` since `before`.
+fn crossed_step(before: u64, bytes: u64) -> bool {
+    before / PROGRESS_STEP != bytes / PROGRESS_STEP
+}
+
+/// `<root>/<pod>/<id>/`. Both come from the frontend or a manifest, so
+/// anything that could lead outside `root` is rejected.
+fn backup_dir(root: &Path, pod_name: &str, id: &str) -> NookResult<PathBuf> {
+    Ok(root.join(path_part(pod_name)?).join(path_part(id)?))
+}
+
+/// `part` if it is a single file name.
+fn path_part(part: &str) -> NookResult<&str> {
+    if part.is_empty() || part.contains(['/', '\\']) || part.contains("..") {
+        return Err(NookError::Other(format!("Invalid backup path: {}", part)));
+    }
+    Ok(part)
+}
+
+/// Archive the named volumes of a pod's container into
+/// `<root>/<pod>/<time>/`, one tar per volume. The container may be running,
+/// but files written meanwhile may be caught half way.
+pub async fn backup(
+    docker: &Docker,
+    container_id: &str,
+    root: &Path,
+    pod_name: &str,
+    app: &AppHandle,
+    pod_id: &str,
+) -> NookResult<VolumeBackup> {
+    let volumes: Vec<PodMount> = list_mounts(docker, container_id)
+        .await?
+        .into_iter()
+        .filter(|m| m.kind == "volume" && m.name.is_some())
+        .collect();
+    if volumes.is_empty() {
+        return Err(NookError::Other(
+            "The pod has no volumes to back up".to_string(),
+        ));
+    }
+
+    let now = chrono::Local::now();
+    let id = now.format("%Y%m%d-%H%M%S%3f").to_string();
+    let dir = backup_dir(root, pod_name, &id)?;
+    if let Some(pod_dir) = dir.parent() {
+        tokio::fs::create_dir_all(pod_dir).await?;
+    }
+    // Never write into another backup, however close together they are taken
+    tokio::fs::create_dir(&dir).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => {
+            NookError::Other(format!("Backup {} already exists", id))
+        }
+        _ => e.into(),
+    })?;
+    let transfer = Transfer {
+        app: app.clone(),
+        pod_id: pod_id.to_string(),
+        backup_id: id.clone(),
+        restoring: false,
+    };
+
+    let mut backup = VolumeBackup {
+        id,
+        pod_name: pod_name.to_string(),
+        created_at: now.timestamp(),
+        volumes: Vec::new(),
+    };
+    for volume in volumes {
+        let name = volume.name.unwrap_or_default();
+        let file = format!("{}.tar", name);
+        match export(
+            docker,
+            container_id,
+            &volume.destination,
+            &dir.join(&file),
+            &name,
+            &transfer,
+        )
+        .await
+        {
+            Ok(size) => backup.volumes.push(BackedUpVolume {
+                name,
+                destination: volume.destination,
+                file,
+                size,
+            }),
+            Err(e) => {
+                // Half a backup would restore as if it were whole
+                let _ = tokio::fs::remove_dir_all(&dir).await;
+                return Err(e);
+            }
+        }
+    }
+
+    let manifest = toml::to_string_pretty(&backup)
+        .map_err(|e| NookError::Serialization(format!("Failed to serialize backup: {}", e)))?;
+    tokio::fs::write(dir.join(MANIFEST), manifest).await?;
+    Ok(backup)
+}
+
+/// Write the tar of `path` in the container to `file`; returns its size.
+async fn export(
+    docker: &Docker,
+    container_id: &str,
+    path: &str,
+    file: &Path,
+    volume: &str,
+    transfer: &Transfer,
+) -> NookResult<u64> {
+    let mut out = tokio::fs::File::create(file).await?;
+    let mut stream =
+        docker.download_from_container(container_id, Some(DownloadFromContainerOptions { path }));
+    let mut bytes = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        out.write_all(&chunk).await?;
+        let before = bytes;
+        bytes += chunk.len() as u64;
+        if crossed_step(before, bytes) {
+            transfer.report(volume, bytes, None, false);
+        }
+    }
+    out.flush().await?;
+    transfer.report(volume, bytes, None, true);
+    Ok(bytes)
+}
+
+/// Unpack a backup into the volumes mounted at the same paths in the
+/// container, e.g. one of a fresh or rebuilt pod. Files the archives don't
+/// have are left in place. Nothing is restored unless every volume has a
+/// mount to go to.
+pub async fn restore(
+    docker: &Docker,
+    container_id: &str,
+    root: &Path,
+    backup: &VolumeBackup,
+    app: &AppHandle,
+    pod_id: &str,
+) -> NookResult<()> {
+    let mounts = list_mounts(docker, container_id).await?;
+    let missing: Vec<&str> = backup
+        .volumes
+        .iter()
+        .filter(|v| {
+            !mounts
+                .iter()
+                .any(|m| m.kind == "volume" && m.destination == v.destination)
+        })
+        .map(|v| v.destination.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(NookError::Other(format!(
+            "No volume mounted at {}",
+            missing.join(", ")
+        )));
+    }
+
+    let dir = backup_dir(root, &backup.pod_name, &backup.id)?;
+    let transfer = Transfer {
+        app: app.clone(),
+        pod_id: pod_id.to_string(),
+        backup_id: backup.id.clone(),
+        restoring: true,
+    };
+    for volume in &backup.volumes {
+        import(
+            docker,
+            container_id,
+            volume,
+            &dir.join(path_part(&volume.file)?),
+            &transfer,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn import(
+    docker: &Docker,
+    container_id: &str,
+    volume: &BackedUpVolume,
+    file: &Path,
+    transfer: &Transfer,
+) -> NookResult<()> {
+    let file = tokio::fs::File::open(file).await?;
+    let total = file.metadata().await?.len();
+    // The archive holds the mount point's directory itself
+    let parent = Path::new(&volume.destination)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "/".to_string());
+
+    let name = volume.name.clone();
+    let progress = transfer.clone();
+    let body = futures_util::stream::unfold((file, 0u64), move |(mut file, sent)| {
+        let name = name.clone();
+        let progress = progress.clone();
+        async move {
+            let mut buf = vec![0u8; READ_CHUNK];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    let bytes = sent + n as u64;
+                    if crossed_step(sent, bytes) {
+                        progress.report(&name, bytes, Some(total), false);
+                    }
+                    Some((buf.into(), (file, bytes)))
+                }
+                Err(e) => {
+                    // Docker rejects the cut-off archive
+                    tracing::warn!("Failed to read backup of volume {}: {}", name, e);
+                    None
+                }
+            }
+        }
+    });
+    let options = UploadToContainerOptions {
+        path: parent,
+        ..Default::default()
+    };
+    docker
+        .upload_to_container_streaming(container_id, Some(options), body)
+        .await?;
+    transfer.report(&volume.name, total, Some(total), true);
+    Ok(())
+}
+
+/// Backups of every pod, newest first. Directories without a readable
+/// manifest are skipped.
+pub fn list_backups(root: &Path) -> NookResult<Vec<VolumeBackup>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for pod_dir in std::fs::read_dir(root)?.flatten() {
+        let Ok(entries) = std::fs::read_dir(pod_dir.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let manifest = entry.path().join(MANIFEST);
+            let Ok(content) = std::fs::read_to_string(&manifest) else {
+                continue;
+            };
+            match toml::from_str::<VolumeBackup>(&content) {
+                Ok(backup) => backups.push(backup),
+                Err(e) => tracing::warn!("Skipping backup {}: {}", manifest.display(), e),
+            }
+        }
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+pub fn load_backup(root: &Path, pod_name: &str, id: &str) -> NookResult<VolumeBackup> {
+    let manifest = backup_dir(root, pod_name, id)?.join(MANIFEST);
+    let content = std::fs::read_to_string(&manifest)
+        .map_err(|e| NookError::ConfigError(format!("Failed to read backup {}: {}", id, e)))?;
+    toml::from_str(&content)
+        .map_err(|e| NookError::ConfigError(format!("Failed to parse backup {}: {}", id, e)))
+}
+
+pub fn delete_backup(root: &Path, pod_name: &str, id: &str) -> NookResult<()> {
+    let dir = backup_dir(root, pod_name, id)?;
+    if dir.join(MANIFEST).exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_backups() {
+        let root = std::env::temp_dir().join(format!("nook-backups-{}", uuid::Uuid::new_v4()));
+        assert!(list_backups(&root).unwrap().is_empty());
+
+        for (id, created_at) in [("20240503-084500123", 10), ("20240504-090000456", 20)] {
+            let backup = VolumeBackup {
+                id: id.to_string(),
+                pod_name: "api".to_string(),
+                created_at,
+                volumes: vec![BackedUpVolume {
+                    name: "api-pgdata".to_string(),
+                    destination: "/var/lib/postgresql/data".to_string(),
+                    file: "api-pgdata.tar".to_string(),
+                    size: 1024,
+                }],
+            };
+            let dir = backup_dir(&root, "api", id).unwrap();
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(MANIFEST), toml::to_string_pretty(&backup).unwrap()).unwrap();
+        }
+        // Left behind by a failed backup
+        std::fs::create_dir_all(backup_dir(&root, "api", "20240505-000000").unwrap()).unwrap();
+
+        let backups = list_backups(&root).unwrap();
+        let ids: Vec<&str> = backups.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["20240504-090000456", "20240503-084500123"]);
+        assert_eq!(
+            load_backup(&root, "api", "20240503-084500123").unwrap(),
+            backups[1]
+        );
+
+        delete_backup(&root, "api", "20240503-084500123").unwrap();
+        assert_eq!(list_backups(&root).unwrap().len(), 1);
+
+        // Nothing outside the backup directory can be reached
+        for (pod_name, id) in [("..", "api"), ("api", "../.."), ("a/b", "1"), ("api", "")] {
+            assert!(delete_backup(&root, pod_name, id).is_err());
+            assert!(load_backup(&root, pod_name, id).is_err());
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_crossed_step() {
+        assert!(!crossed_step(0, 1024));
+        assert!(crossed_step(PROGRESS_STEP - 1, PROGRESS_STEP));
+        assert!(!crossed_step(PROGRESS_STEP, PROGRESS_STEP + 1));
+    }
+}
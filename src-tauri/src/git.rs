@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::error::{NookError, NookResult};
+use crate::state::{AppState, AppStateInner};
+use crate::types::{GitCommit, GitInfo, PodGitEvent};
+
+/// Files in the git directory that change on checkout, commit, staging,
+/// fetch and reset. Commits show up in `logs/HEAD`.
+const WATCHED_FILES: [&str; 5] = ["HEAD", "index", "FETCH_HEAD", "ORIG_HEAD", "packed-refs"];
+
+/// Quiet period after a change before re-reading, so a rebase results in a
+/// single read.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// Run `git -C <dir> <args>` and return its trimmed stdout.
+async fn git(dir: &Path, args: &[&str]) -> NookResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        // Keeps `git status` from rewriting the index, which the watcher
+        // would see as a change
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => NookError::Other("git not found on PATH".to_string()),
+            _ => NookError::Io(e),
+        })?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(NookError::Other(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Root of the working tree `dir` is in.
+pub async fn toplevel(dir: &Path) -> NookResult<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"])
+        .await
+        .map(PathBuf::from)
+}
+
+/// Check `branch` out into a new worktree of the repository at `repo`. A
+/// branch that only exists on a remote is tracked under the same name.
+pub async fn add_worktree(repo: &Path, path: &Path, branch: &str) -> NookResult<()> {
+    let path = path.to_string_lossy();
+    git(repo, &["worktree", "add", &path, branch]).await?;
+    Ok(())
+}
+
+/// Branch, distance to upstream, dirty flag and last commit of the working
+/// tree `dir` is in.
+pub async fn info(dir: &Path) -> NookResult<GitInfo> {
+    let status = git(dir, &["status", "--porcelain=v2", "--branch"]).await?;
+    let (mut info, has_commits) = parse_status(&status);
+    if has_commits {
+        let log = git(dir, &["log", "-1", "--format=%H%x1f%s%x1f%an%x1f%ct"]).await?;
+        info.last_commit = parse_commit(&log);
+    }
+    Ok(info)
+}
+
+/// Read `git status --porcelain=v2 --branch`. Also returns whether the
+/// branch has any commits yet.
+fn parse_status(status: &str) -> (GitInfo, bool) {
+    let mut info = GitInfo::default();
+    let mut has_commits = false;
+    for line in status.lines() {
+        let Some(header) = line.strip_prefix("# ") else {
+            // Every other line is a changed or untracked file
+            info.dirty |= !line.is_empty();
+            continue;
+        };
+        let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+        match key {
+            "branch.oid" => has_commits = value != "(initial)",
+            "branch.head" if value != "(detached)" => info.branch = Some(value.to_string()),
+            "branch.ab" => {
+                for count in value.split(' ') {
+                    if let Some(n) = count.strip_prefix('+') {
+                        info.ahead = n.parse().unwrap_or(0);
+                    } else if let Some(n) = count.strip_prefix('-') {
+                        info.behind = n.parse().unwrap_or(0);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    (info, has_commits)
+}
+
+fn parse_commit(log: &str) -> Option<GitCommit> {
+    let mut fields = log.split('\u{1f}');
+    Some(GitCommit {
+        hash: fields.next()?.to_string(),
+        summary: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        committed_at: fields.next()?.parse().ok()?,
+    })
+}
+
+/// Directories holding the files in `WATCHED_FILES` for the working tree at
+/// `dir`. A worktree has its own git directory next to the shared one.
+async fn watched_dirs(dir: &Path) -> NookResult<HashSet<PathBuf>> {
+    let args = [
+        "rev-parse",
+        "--path-format=absolute",
+        "--git-dir",
+        "--git-common-dir",
+    ];
+    let dirs = git(dir, &args).await?;
+    let mut dirs = dirs.lines().map(PathBuf::from);
+    let git_dir = dirs
+        .next()
+        .ok_or_else(|| NookError::Other("git rev-parse printed no git directory".to_string()))?;
+    Ok([git_dir.join("logs"), git_dir]
+        .into_iter()
+        .chain(dirs)
+        .filter(|d| d.is_dir())
+        .collect())
+}
+
+/// Watches a pod's git directory. Dropping it stops the watcher and its
+/// refresh task.
+pub struct GitWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Read a pod's git state now, then again whenever its HEAD, index or refs
+/// change. Replaces any existing watcher for the pod. Edits that aren't
+/// staged yet are only picked up by `check`.
+pub fn watch(state: &mut AppStateInner, app_state: &AppState, app: &AppHandle, pod_id: &str) {
+    state.git_watchers.remove(pod_id);
+    let Some(pod) = state.pods.get(pod_id) else {
+        return;
+    };
+    let project_path = PathBuf::from(&pod.project_path);
+    let app_state = app_state.clone();
+    let app = app.clone();
+    let pod_id = pod_id.to_string();
+    tokio::spawn(async move {
+        check(&app_state, &app, &pod_id).await;
+        // Not in a working tree, or no git
+        let Ok(dirs) = watched_dirs(&project_path).await else {
+            return;
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let handler = move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let relevant = event.paths.iter().any(|p| {
+                p.file_name()
+                    .is_some_and(|n| WATCHED_FILES.iter().any(|f| n == *f))
+            });
+            if relevant {
+                let _ = tx.send(());
+            }
+        };
+        let mut watcher = match notify::recommended_watcher(handler) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!("Failed to watch git state of pod {}: {}", pod_id, e);
+                return;
+            }
+        };
+        for dir in dirs {
+            if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                tracing::warn!("Failed to watch {}: {}", dir.display(), e);
+            }
+        }
+        {
+            let mut state = app_state.lock().await;
+            if !state.pods.contains_key(&pod_id) {
+                return;
+            }
+            state
+                .git_watchers
+                .insert(pod_id.clone(), GitWatcher { _watcher: watcher });
+        }
+
+        // Ends when the watcher, and with it the sender, is dropped
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(SETTLE).await;
+            while rx.try_recv().is_ok() {}
+            check(&app_state, &app, &pod_id).await;
+        }
+    });
+}
+
+/// Re-read a pod's git state, updating `pod.git` and emitting `pod-git`
+/// when it changes.
+pub async fn check(state: &AppState, app: &AppHandle, pod_id: &str) {
+    let project_path = {
+        let state = state.lock().await;
+        match state.pods.get(pod_id) {
+            Some(pod) => pod.project_path.clone(),
+            None => return,
+        }
+    };
+    let git = match info(Path::new(&project_path)).await {
+        Ok(info) => Some(info),
+        Err(e) => {
+            tracing::debug!("No git state for {}: {}", project_path, e);
+            None
+        }
+    };
+
+    let mut state = state.lock().await;
+    let Some(pod) = state.pods.get_mut(pod_id) else {
+        return;
+    };
+    if pod.git == git {
+        return;
+    }
+    pod.git = git.clone();
+    let _ = app.emit(
+        "pod-git",
+        &PodGitEvent {
+            pod_id: pod_id.to_string(),
+            git,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let status = "# branch.oid 3f2a9c1d\n\
+                      # branch.head feature/login\n\
+                      # branch.upstream origin/feature/login\n\
+                      # branch.ab +2 -1\n\
+                      1 .M N... 100644 100644 100644 aaaa bbbb src/main.rs\n\
+                      ? notes.txt";
+        let (info, has_commits) = parse_status(status);
+        assert!(has_commits);
+        assert_eq!(info.branch.as_deref(), Some("feature/login"));
+        assert_eq!((info.ahead, info.behind), (2, 1));
+        assert!(info.dirty);
+
+        let (info, has_commits) = parse_status("# branch.oid (initial)\n# branch.head (detached)");
+        assert!(!has_commits);
+        assert_eq!(info.branch, None);
+        assert!(!info.dirty);
+    }
+
+    #[test]
+    fn test_parse_commit() {
+        let commit = parse_commit("3f2a9c1d\u{1f}Fix login\u{1f}Ada\u{1f}1714723200").unwrap();
+        assert_eq!(commit.summary, "Fix login");
+        assert_eq!(commit.author, "Ada");
+        assert_eq!(commit.committed_at, 1714723200);
+        assert!(parse_commit("3f2a9c1d").is_none());
+    }
+}
@@ -1,15 +1,200 @@
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
 
+use crate::docker::containers;
 use crate::error::{NookError, NookResult};
+use crate::state::AppState;
+use crate::types::{ExposedPort, PortStatus};
+
+/// What to expose for a pod.
+pub struct ExposeTarget {
+    pub container_port: u16,
+    pub host_port: u16,
+    /// Compose service to forward to instead of the pod's own container
+    pub service: Option<String>,
+    /// Overrides the protocol detected for the port
+    pub protocol: Option<String>,
+    pub label: Option<String>,
+}
+
+/// Connections and traffic through a pod's port proxies, for idle
+/// detection.
+#[derive(Debug, Default)]
+pub struct ProxyActivity {
+    open: AtomicUsize,
+    /// Unix time of the last byte forwarded either way
+    last_traffic: AtomicI64,
+}
+
+impl ProxyActivity {
+    pub fn open_connections(&self) -> usize {
+        self.open.load(Ordering::Relaxed)
+    }
+
+    /// `None` if nothing was forwarded yet
+    pub fn last_traffic(&self) -> Option<i64> {
+        Some(self.last_traffic.load(Ordering::Relaxed)).filter(|t| *t > 0)
+    }
+
+    fn touch(&self) {
+        self.last_traffic
+            .store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+    }
+}
+
+/// Counts a proxied connection as open until dropped.
+struct OpenConnection(Arc<ProxyActivity>);
+
+impl OpenConnection {
+    fn new(activity: Arc<ProxyActivity>) -> Self {
+        activity.open.fetch_add(1, Ordering::Relaxed);
+        activity.touch();
+        Self(activity)
+    }
+}
+
+impl Drop for OpenConnection {
+    fn drop(&mut self) {
+        self.0.open.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Expose a pod's container port on the host and record it on the pod.
+/// A port that is already exposed is returned as-is.
+pub async fn expose_pod_port(
+    state: &AppState,
+    pod_id: &str,
+    target: ExposeTarget,
+) -> NookResult<ExposedPort> {
+    let (docker, container_id, rootless, activity) = {
+        let mut state = state.lock().await;
+        let pod = state
+            .pods
+            .get(pod_id)
+            .ok_or_else(|| NookError::Other(format!("Pod not found: {}", pod_id)))?;
+        if let Some(existing) = pod
+            .exposed_ports
+            .iter()
+            .find(|p| {
+                p.container_port == target.container_port
+                    && p.service == target.service
+                    && p.status == PortStatus::Active
+            })
+        {
+            return Ok(existing.clone());
+        }
+        let container_id = pod
+            .container_id
+            .clone()
+            .ok_or_else(|| NookError::Other("No container ID for pod".to_string()))?;
+        let docker = state
+            .docker_for(pod_id)
+            .ok_or_else(|| NookError::DockerConnection("Docker not connected".to_string()))?;
+        let activity = state
+            .proxy_activity
+            .entry(pod_id.to_string())
+            .or_default()
+            .clone();
+        (docker, container_id, state.runtime_for(pod_id).rootless, activity)
+    };
+
+    let target_container = match &target.service {
+        Some(service) => containers::find_compose_service(&docker, &container_id, service)
+            .await?
+            .ok_or_else(|| NookError::Other(format!("Compose service not found: {}", service)))?,
+        None => container_id,
+    };
+    let (container_ip, target_port) =
+        containers::container_address(&docker, &target_container, target.container_port, rootless)
+            .await?;
+
+    let cancel = CancellationToken::new();
+    let host_port = target.host_port;
+    let container_port = target.container_port;
+
+    // Test bind first to detect busy port without spawning
+    let status = match std::net::TcpListener::bind(format!("0.0.0.0:{}", host_port)) {
+        Ok(listener) => {
+            // Port is free, drop the test listener and spawn the async proxy
+            drop(listener);
+            let cancel_c = cancel.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    start_port_proxy(host_port, container_ip, target_port, activity, cancel_c)
+                        .await
+                {
+                    tracing::error!("Port proxy error: {}", e);
+                }
+            });
+            PortStatus::Active
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            tracing::warn!("Host port {} is busy", host_port);
+            PortStatus::HostPortBusy
+        }
+        Err(e) => {
+            return Err(NookError::Other(format!(
+                "Failed to bind port {}: {}",
+                host_port, e
+            )));
+        }
+    };
+
+    let mut state = state.lock().await;
+    let pod = state.pods.get_mut(pod_id).ok_or_else(|| {
+        cancel.cancel();
+        NookError::Other(format!("Pod not found: {}", pod_id))
+    })?;
+
+    // Fall back to the protocol and label found by port detection
+    let detected = pod
+        .detected_ports
+        .iter()
+        .find(|p| p.container_port == container_port && p.service == target.service);
+    let protocol = target
+        .protocol
+        .or_else(|| detected.map(|p| p.protocol.clone()))
+        .unwrap_or_default();
+    let label = target.label.or_else(|| detected.and_then(|p| p.label.clone()));
+
+    let exposed = ExposedPort {
+        container_port,
+        host_port,
+        protocol,
+        status,
+        auto_expose: true,
+        label,
+        service: target.service,
+    };
+
+    // Remove from detected
+    let service = exposed.service.clone();
+    pod.detected_ports
+        .retain(|p| !(p.container_port == container_port && p.service == service));
+    pod.exposed_ports
+        .retain(|p| !(p.container_port == container_port && p.service == service));
+    pod.exposed_ports.push(exposed.clone());
+
+    // Store the cancellation token for this proxy
+    if exposed.status == PortStatus::Active {
+        state
+            .port_proxy_tokens
+            .insert((pod_id.to_string(), host_port), cancel);
+    }
+
+    Ok(exposed)
+}
 
 /// Start a TCP proxy from host_port to container_ip:container_port
 pub async fn start_port_proxy(
     host_port: u16,
     container_ip: String,
     container_port: u16,
+    activity: Arc<ProxyActivity>,
     cancel: CancellationToken,
 ) -> NookResult<()> {
     let addr: SocketAddr = format!("0.0.0.0:{}", host_port)
@@ -42,8 +227,9 @@ pub async fn start_port_proxy(
                     Ok((client_stream, _)) => {
                         let target = format!("{}:{}", container_ip, container_port);
                         let cancel_clone = cancel.clone();
+                        let open = OpenConnection::new(activity.clone());
                         tokio::spawn(async move {
-                            if let Err(e) = proxy_connection(client_stream, &target, cancel_clone).await {
+                            if let Err(e) = proxy_connection(client_stream, &target, &open.0, cancel_clone).await {
                                 tracing::debug!("Proxy connection error: {}", e);
                             }
                         });
@@ -62,6 +248,7 @@ pub async fn start_port_proxy(
 async fn proxy_connection(
     mut client: tokio::net::TcpStream,
     target: &str,
+    activity: &ProxyActivity,
     cancel: CancellationToken,
 ) -> NookResult<()> {
     let mut server = tokio::net::TcpStream::connect(target)
@@ -81,6 +268,7 @@ async fn proxy_connection(
                 match result {
                     Ok(0) => break,
                     Ok(n) => {
+                        activity.touch();
                         if server_write.write_all(&client_buf[..n]).await.is_err() {
                             break;
                         }
@@ -92,6 +280,7 @@ async fn proxy_connection(
                 match result {
                     Ok(0) => break,
                     Ok(n) => {
+                        activity.touch();
                         if client_write.write_all(&server_buf[..n]).await.is_err() {
                             break;
                         }
@@ -6,22 +6,44 @@ use futures_util::StreamExt;
 use tauri::{AppHandle, Emitter};
 use tokio_util::sync::CancellationToken;
 
+use crate::config::pod_state::PodStateConfig;
+use crate::config::settings::PortAction;
+use crate::devcontainer::ports::{self, PortRules};
+use crate::network::expose::{self, ExposeTarget};
 use crate::network::heuristics;
 use crate::network::polling;
-use crate::types::{Confidence, DetectedPort, DetectionMethod, PortDetectedEvent};
+use crate::state::AppState;
+use crate::types::{
+    Confidence, DetectedPort, DetectionMethod, PortDetectedEvent, PortExposedEvent,
+};
+
+/// Settings for a pod's port scanner.
+pub struct PortScanConfig {
+    pub interval_ms: u64,
+    pub port_overrides: HashMap<String, String>,
+    /// `portsAttributes` from the pod's devcontainer.json
+    pub rules: PortRules,
+    /// Compose service of the scanned container; `None` for the pod's own container
+    pub service: Option<String>,
+    /// The pod's saved settings, for the host ports it maps
+    pub pod_config: Option<PodStateConfig>,
+}
 
 /// Periodically scan for listening ports inside a container and emit
-/// `port-detected` events for newly discovered ports.
+/// `port-detected` events for newly discovered ports. Ports whose action
+/// (from `portsAttributes` or the global setting) is auto-expose are exposed
+/// right away and reported via `port-exposed`; ignored ports are skipped.
 pub async fn poll_ports(
     docker: Docker,
     container_id: String,
     pod_id: String,
     app: AppHandle,
+    state: AppState,
     cancel: CancellationToken,
-    interval_ms: u64,
-    port_overrides: HashMap<String, String>,
+    config: PortScanConfig,
 ) {
-    let interval = tokio::time::Duration::from_millis(interval_ms);
+    let interval = tokio::time::Duration::from_millis(config.interval_ms);
+    let port_overrides = &config.port_overrides;
     let mut known_ports: HashSet<u16> = HashSet::new();
 
     loop {
@@ -44,19 +66,72 @@ pub async fn poll_ports(
                     known_ports.insert(lp.port);
 
                     let process_name = lp.process_name.clone().unwrap_or_default();
+                    let attrs = config.rules.attributes_for(lp.port, &process_name);
+                    let action = attrs
+                        .and_then(|a| a.on_auto_forward.as_ref())
+                        .map(ports::port_action);
+
+                    let (global_action, already_exposed) = {
+                        let state = state.lock().await;
+                        let exposed = state.pods.get(&pod_id).is_some_and(|pod| {
+                            pod.exposed_ports.iter().any(|p| {
+                                p.container_port == lp.port && p.service == config.service
+                            })
+                        });
+                        (state.settings.port_action.clone(), exposed)
+                    };
+                    let effective = action.clone().unwrap_or(global_action);
+                    if already_exposed || effective == PortAction::Ignore {
+                        continue;
+                    }
 
-                    // Determine protocol via heuristics
-                    let protocol = heuristics::protocol_for_port(lp.port, &port_overrides)
-                        .or_else(|| heuristics::protocol_for_process(&process_name))
-                        .unwrap_or_else(|| "unknown".to_string());
+                    // Determine protocol: devcontainer.json first, then heuristics
+                    let declared = attrs.and_then(|a| a.protocol.clone());
+                    let by_port = heuristics::protocol_for_port(lp.port, port_overrides);
+                    let by_process = heuristics::protocol_for_process(&process_name);
 
-                    let (method, confidence) = if heuristics::protocol_for_port(lp.port, &port_overrides).is_some() {
+                    let (method, confidence) = if declared.is_some() {
+                        (DetectionMethod::Config, Confidence::High)
+                    } else if by_port.is_some() {
                         (DetectionMethod::WellKnown, Confidence::High)
-                    } else if heuristics::protocol_for_process(&process_name).is_some() {
+                    } else if by_process.is_some() {
                         (DetectionMethod::ProcessName, Confidence::High)
                     } else {
                         (DetectionMethod::Unknown, Confidence::Low)
                     };
+                    let protocol = declared
+                        .or(by_port)
+                        .or(by_process)
+                        .unwrap_or_else(|| "unknown".to_string());
+
+                    let label = attrs.and_then(|a| a.label.clone());
+
+                    if effective == PortAction::AutoExpose {
+                        let target = ExposeTarget {
+                            container_port: lp.port,
+                            host_port: config.pod_config.as_ref().map_or(lp.port, |c| {
+                                c.host_port(config.service.as_deref(), lp.port)
+                            }),
+                            service: config.service.clone(),
+                            protocol: Some(protocol.clone()),
+                            label: label.clone(),
+                        };
+                        match expose::expose_pod_port(&state, &pod_id, target).await {
+                            Ok(exposed) => {
+                                let event = PortExposedEvent {
+                                    pod_id: pod_id.clone(),
+                                    port: exposed,
+                                };
+                                if let Err(e) = app.emit("port-exposed", &event) {
+                                    tracing::error!("Failed to emit port-exposed: {}", e);
+                                }
+                                continue;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Failed to auto-expose port {}: {}", lp.port, e);
+                            }
+                        }
+                    }
 
                     let detected = DetectedPort {
                         container_port: lp.port,
@@ -64,8 +139,18 @@ pub async fn poll_ports(
                         process_name,
                         detection_method: method,
                         confidence,
+                        label,
+                        action,
+                        service: config.service.clone(),
                     };
 
+                    {
+                        let mut state = state.lock().await;
+                        if let Some(pod) = state.pods.get_mut(&pod_id) {
+                            pod.detected_ports.push(detected.clone());
+                        }
+                    }
+
                     let event = PortDetectedEvent {
                         pod_id: pod_id.clone(),
                         port: detected,
@@ -82,7 +167,7 @@ pub async fn poll_ports(
 
 /// Run `ss -tlnp` inside the container and parse the output.
 /// Falls back to reading `/proc/net/tcp` if ss is not available.
-async fn scan_container_ports(
+pub(crate) async fn scan_container_ports(
     docker: &Docker,
     container_id: &str,
 ) -> Vec<polling::ListeningPort> {
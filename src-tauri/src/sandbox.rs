@@ -0,0 +1,292 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::pod_state::{self, PodStateConfig};
+use crate::devcontainer::{config, ports};
+use crate::error::{NookError, NookResult};
+use crate::git;
+use crate::types::Pod;
+
+/// Create a clone's project at `target`: a git worktree of `branch`, or a
+/// copy of the project directory. Returns the clone's project path, which is
+/// inside `target` when the project is a subdirectory of its repository.
+pub async fn checkout(
+    project_path: &str,
+    target: &Path,
+    branch: Option<&str>,
+) -> NookResult<PathBuf> {
+    let project = std::fs::canonicalize(project_path)?;
+    if target.exists() && std::fs::read_dir(target)?.next().is_some() {
+        return Err(NookError::Other(format!(
+            "{} already exists and is not empty",
+            target.display()
+        )));
+    }
+    match branch {
+        Some(branch) => {
+            let root = git::toplevel(&project).await?;
+            let inner = project
+                .strip_prefix(&root)
+                .unwrap_or(Path::new(""))
+                .to_path_buf();
+            git::add_worktree(&root, target, branch).await?;
+            Ok(target.join(inner))
+        }
+        None => {
+            if target.starts_with(&project) {
+                return Err(NookError::Other(
+                    "A copy can't go inside the project it copies".to_string(),
+                ));
+            }
+            let to = target.to_path_buf();
+            tokio::task::spawn_blocking(move || copy_dir(&project, &to))
+                .await
+                .map_err(|e| NookError::Other(format!("Copy failed: {}", e)))??;
+            Ok(target.to_path_buf())
+        }
+    }
+}
+
+/// Copy a directory tree, keeping symlinks as links where the platform
+/// allows.
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let dest = to.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        copy_dir(from, to)
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// A pod name suffix for a branch, e.g. `feature-login` for
+/// `feature/login`.
+pub fn name_suffix(branch: Option<&str>) -> String {
+    let suffix: String = branch
+        .unwrap_or("copy")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let suffix = suffix.trim_matches(|c| c == '-' || c == '.');
+    if suffix.is_empty() {
+        "copy".to_string()
+    } else {
+        suffix.to_string()
+    }
+}
+
+/// Container ports the pod puts on the host, as (service, port) pairs: its
+/// `forwardPorts`, the ports it has exposed and the ones its config maps or
+/// exposes.
+pub fn host_facing_ports(pod: &Pod, source: &PodStateConfig) -> Vec<(Option<String>, u16)> {
+    let mut ports = config::load(&pod.project_path, pod.config_path.as_deref())
+        .map(|c| ports::forward_targets(&c))
+        .unwrap_or_default();
+    ports.extend(
+        pod.exposed_ports
+            .iter()
+            .map(|p| (p.service.clone(), p.container_port)),
+    );
+    ports.extend(
+        source
+            .port_mappings
+            .keys()
+            .filter_map(|k| pod_state::parse_port_mapping_key(k)),
+    );
+    ports.extend(
+        source
+            .auto_expose_ports
+            .iter()
+            .chain(&source.manual_expose_ports)
+            .map(|p| (None, p.container_port)),
+    );
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Host ports other pods use or would use: their mappings, their exposed
+/// ports, and unmapped `ports` which keep their own number.
+pub fn taken_host_ports(
+    configs: &[PodStateConfig],
+    pods: &[&Pod],
+    ports: &[(Option<String>, u16)],
+) -> HashSet<u16> {
+    let mut taken: HashSet<u16> = ports.iter().map(|(_, port)| *port).collect();
+    for config in configs {
+        taken.extend(config.port_mappings.values());
+        taken.extend(
+            config
+                .auto_expose_ports
+                .iter()
+                .chain(&config.manual_expose_ports)
+                .map(|p| p.host_port),
+        );
+    }
+    for pod in pods {
+        taken.extend(pod.exposed_ports.iter().map(|p| p.host_port));
+    }
+    taken
+}
+
+/// Give each of `ports` a host port of its own: the next one up that isn't
+/// `taken` and `is_free` on the host. Keyed like `port_mappings`.
+pub fn remap_ports(
+    ports: &[(Option<String>, u16)],
+    taken: &HashSet<u16>,
+    is_free: impl Fn(u16) -> bool,
+) -> HashMap<String, u16> {
+    let mut used = taken.clone();
+    let mut mappings = HashMap::new();
+    for (service, port) in ports {
+        let port = *port;
+        let host = (port.saturating_add(1)..=u16::MAX)
+            .chain(1024..port)
+            .find(|p| !used.contains(p) && is_free(*p));
+        if let Some(host) = host {
+            used.insert(host);
+            mappings.insert(pod_state::port_mapping_key(service.as_deref(), port), host);
+        }
+    }
+    mappings
+}
+
+/// Whether a host port can be bound right now.
+pub fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+/// The config of a clone of `source`: the same settings under a new name
+/// and project, with its own host ports. It starts from a fresh container,
+/// isn't started on launch and doesn't run the source's schedules.
+pub fn clone_config(
+    source: &PodStateConfig,
+    name: &str,
+    project_path: &str,
+    port_mappings: HashMap<String, u16>,
+) -> PodStateConfig {
+    let mut config = source.clone();
+    config.name = name.to_string();
+    config.project_path = project_path.to_string();
+    config.alias = String::new();
+    config.config_hash = String::new();
+    config.config_snapshot = None;
+    config.autostart = false;
+    config.schedules.clear();
+    for port in config
+        .auto_expose_ports
+        .iter_mut()
+        .chain(config.manual_expose_ports.iter_mut())
+    {
+        let key = pod_state::port_mapping_key(None, port.container_port);
+        if let Some(&host) = port_mappings.get(&key) {
+            port.host_port = host;
+        }
+    }
+    config.port_mappings = port_mappings;
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::pod_state::AutoExposePort;
+
+    #[test]
+    fn test_remap_ports() {
+        let taken: HashSet<u16> = [3000, 3001, 5432, 5433].into_iter().collect();
+        let ports = [
+            (None, 3000),
+            (None, 3001),
+            (Some("db".to_string()), 5432),
+            (Some("replica".to_string()), 5432),
+        ];
+        let mappings = remap_ports(&ports, &taken, |p| p != 3002);
+        assert_eq!(mappings.get("3000"), Some(&3003));
+        assert_eq!(mappings.get("3001"), Some(&3004));
+        assert_eq!(mappings.get("db:5432"), Some(&5434));
+        assert_eq!(mappings.get("replica:5432"), Some(&5435));
+    }
+
+    #[test]
+    fn test_clone_config() {
+        let source = PodStateConfig {
+            name: "api".to_string(),
+            project_path: "/src/api".to_string(),
+            alias: "API".to_string(),
+            autostart: true,
+            manual_expose_ports: vec![AutoExposePort {
+                container_port: 3000,
+                host_port: 3000,
+                protocol: "http".to_string(),
+            }],
+            ..Default::default()
+        };
+        let mappings = HashMap::from([("3000".to_string(), 3001)]);
+        let config = clone_config(&source, "api-feature", "/src/api-feature", mappings);
+        assert_eq!(config.name, "api-feature");
+        assert_eq!(config.project_path, "/src/api-feature");
+        assert_eq!(config.alias, "");
+        assert!(!config.autostart);
+        assert_eq!(config.manual_expose_ports[0].host_port, 3001);
+        assert_eq!(config.host_port(None, 3000), 3001);
+        assert_eq!(config.restart_max_retries, 5);
+    }
+
+    #[test]
+    fn test_name_suffix() {
+        assert_eq!(name_suffix(Some("feature/login")), "feature-login");
+        assert_eq!(name_suffix(Some("pr-42")), "pr-42");
+        assert_eq!(name_suffix(Some("//")), "copy");
+        assert_eq!(name_suffix(None), "copy");
+    }
+
+    #[test]
+    fn test_copy_dir() {
+        let root = std::env::temp_dir().join(format!("nook-sandbox-{}", uuid::Uuid::new_v4()));
+        let from = root.join("app");
+        std::fs::create_dir_all(from.join(".devcontainer")).unwrap();
+        std::fs::write(from.join(".devcontainer/devcontainer.json"), "{}").unwrap();
+        std::fs::write(from.join("README.md"), "hello").unwrap();
+
+        let to = root.join("app-copy");
+        copy_dir(&from, &to).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(to.join(".devcontainer/devcontainer.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(to.join("README.md")).unwrap(),
+            "hello"
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
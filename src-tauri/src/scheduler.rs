@@ -0,0 +1,383 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::pod_state::{PodSchedule, PodStateConfig, ScheduleAction};
+use crate::error::{NookError, NookResult};
+use crate::state::AppState;
+use crate::types::{PodStatus, ScheduleOutcome, ScheduleRun};
+
+/// How often due schedules are looked for.
+const TICK: Duration = Duration::from_secs(30);
+
+/// A run found this much past its time was missed while the machine slept
+/// or Nook wasn't running.
+const LATE_AFTER_SECS: i64 = 120;
+
+const MAX_HISTORY: usize = 20;
+
+/// Runs the pods' schedules and remembers how their runs went. Saved to
+/// `pods/schedules/history.toml`, so catch-up and history survive a restart.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Scheduler {
+    /// Unix time up to which due runs have been handled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checked_until: Option<i64>,
+    /// Recent runs by schedule id, oldest first
+    #[serde(default)]
+    history: HashMap<String, VecDeque<ScheduleRun>>,
+}
+
+impl Scheduler {
+    fn path() -> NookResult<PathBuf> {
+        Ok(PodStateConfig::pods_dir()?
+            .join("schedules")
+            .join("history.toml"))
+    }
+
+    /// The saved scheduler state; empty if there is none.
+    pub fn load() -> NookResult<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            NookError::ConfigError(format!("Failed to read schedule history: {}", e))
+        })?;
+        toml::from_str(&content).map_err(|e| {
+            NookError::ConfigError(format!("Failed to parse schedule history: {}", e))
+        })
+    }
+
+    fn save(&self) -> NookResult<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                NookError::ConfigError(format!("Failed to create schedules dir: {}", e))
+            })?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            NookError::Serialization(format!("Failed to serialize schedule history: {}", e))
+        })?;
+        std::fs::write(&path, content).map_err(|e| {
+            NookError::ConfigError(format!("Failed to write schedule history: {}", e))
+        })
+    }
+
+    /// Save, logging rather than failing: a lost write only costs history.
+    fn persist(&self) {
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save schedule history: {}", e);
+        }
+    }
+
+    pub fn history(&self, schedule_id: &str) -> Vec<ScheduleRun> {
+        self.history
+            .get(schedule_id)
+            .map(|h| h.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn record(&mut self, schedule_id: &str, run: ScheduleRun) {
+        let history = self.history.entry(schedule_id.to_string()).or_default();
+        if history.len() >= MAX_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(run);
+        self.persist();
+    }
+}
+
+pub fn parse_time(time: &str) -> NookResult<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| {
+        NookError::ConfigError(format!(
+            "Invalid schedule time \"{}\", expected HH:MM",
+            time
+        ))
+    })
+}
+
+/// Times in `(from, to]` the schedule was due, oldest first. A time skipped
+/// by a daylight saving change isn't due that day.
+pub fn due_times<Tz: TimeZone>(
+    schedule: &PodSchedule,
+    from: &DateTime<Tz>,
+    to: &DateTime<Tz>,
+) -> Vec<DateTime<Tz>> {
+    let Ok(time) = parse_time(&schedule.time) else {
+        return Vec::new();
+    };
+    let tz = to.timezone();
+    let mut due = Vec::new();
+    let mut date = from.with_timezone(&tz).date_naive();
+    while date <= to.date_naive() {
+        if schedule.days.is_empty() || schedule.days.contains(&date.weekday()) {
+            if let Some(at) = tz.from_local_datetime(&date.and_time(time)).earliest() {
+                if at > *from && at <= *to {
+                    due.push(at);
+                }
+            }
+        }
+        let Some(next) = date.succ_opt() else {
+            break;
+        };
+        date = next;
+    }
+    due
+}
+
+/// The schedule's next run after `after`; `None` if it is disabled or
+/// invalid.
+pub fn next_run<Tz: TimeZone>(
+    schedule: &PodSchedule,
+    after: &DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    if !schedule.enabled {
+        return None;
+    }
+    let week_later = after.clone() + chrono::Duration::days(8);
+    due_times(schedule, after, &week_later).into_iter().next()
+}
+
+/// Run due schedules every `TICK` for the life of the app. Runs missed while
+/// the machine slept or Nook wasn't running are run once if the schedule
+/// catches up, and recorded as skipped otherwise.
+pub async fn run(state: AppState, app: AppHandle) {
+    match Scheduler::load() {
+        Ok(scheduler) => state.lock().await.scheduler = scheduler,
+        Err(e) => tracing::warn!("Failed to load schedule history: {}", e),
+    }
+    loop {
+        tick(&state, &app).await;
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+async fn tick(state: &AppState, app: &AppHandle) {
+    let now = Local::now();
+    let checked_until = {
+        let scheduler = &mut state.lock().await.scheduler;
+        let checked_until = scheduler.checked_until.replace(now.timestamp());
+        scheduler.persist();
+        checked_until
+    };
+    // Nothing is due retroactively on the very first launch
+    let Some(from) = checked_until.and_then(|t| Local.timestamp_opt(t, 0).single()) else {
+        return;
+    };
+    let configs = match PodStateConfig::list_all() {
+        Ok(configs) => configs,
+        Err(e) => {
+            tracing::warn!("Failed to load pod schedules: {}", e);
+            return;
+        }
+    };
+
+    for config in configs {
+        for schedule in config.schedules.iter().filter(|s| s.enabled) {
+            let due = due_times(schedule, &from, &now);
+            let Some(last) = due.last() else {
+                continue;
+            };
+            let late = (now.timestamp() - last.timestamp()) > LATE_AFTER_SECS;
+            if late && !schedule.catch_up {
+                let run = ScheduleRun {
+                    due_at: last.timestamp(),
+                    started_at: now.timestamp(),
+                    caught_up: false,
+                    outcome: ScheduleOutcome::Skipped {
+                        reason: format!("Missed {} run(s) while asleep or closed", due.len()),
+                    },
+                };
+                state.lock().await.scheduler.record(&schedule.id, run);
+                continue;
+            }
+            // Several missed runs only run once
+            let app = app.clone();
+            let pod_name = config.name.clone();
+            let schedule = schedule.clone();
+            let due_at = last.timestamp();
+            tokio::spawn(async move {
+                execute(&app, &pod_name, &schedule, due_at, late).await;
+            });
+        }
+    }
+}
+
+async fn execute(
+    app: &AppHandle,
+    pod_name: &str,
+    schedule: &PodSchedule,
+    due_at: i64,
+    caught_up: bool,
+) {
+    tracing::info!(
+        "Running scheduled {:?} of pod {}{}",
+        schedule.action,
+        pod_name,
+        if caught_up { " (caught up)" } else { "" }
+    );
+    let started_at = Utc::now().timestamp();
+    let state = app.state::<AppState>();
+    let outcome = match run_action(state.clone(), app, pod_name, schedule).await {
+        Ok(None) => ScheduleOutcome::Done,
+        Ok(Some(reason)) => ScheduleOutcome::Skipped { reason },
+        Err(error) => {
+            tracing::warn!(
+                "Scheduled {:?} of pod {} failed: {}",
+                schedule.action,
+                pod_name,
+                error
+            );
+            ScheduleOutcome::Failed { error }
+        }
+    };
+    let run = ScheduleRun {
+        due_at,
+        started_at,
+        caught_up,
+        outcome,
+    };
+    state.lock().await.scheduler.record(&schedule.id, run);
+}
+
+/// Returns why nothing was done, if so.
+async fn run_action(
+    state: State<'_, AppState>,
+    app: &AppHandle,
+    pod_name: &str,
+    schedule: &PodSchedule,
+) -> Result<Option<String>, String> {
+    let (id, status) = state
+        .lock()
+        .await
+        .pods
+        .values()
+        .find(|p| p.name == pod_name)
+        .map(|p| (p.id.clone(), p.status.clone()))
+        .ok_or_else(|| format!("Pod not found: {}", pod_name))?;
+    match schedule.action {
+        ScheduleAction::Start => {
+            if matches!(status, PodStatus::Running | PodStatus::Starting) {
+                return Ok(Some("Already running".to_string()));
+            }
+            crate::commands::start_pod(state, app.clone(), id).await?;
+        }
+        ScheduleAction::Stop => {
+            if status == PodStatus::Stopped {
+                return Ok(Some("Already stopped".to_string()));
+            }
+            crate::commands::stop_single_pod(state, app.clone(), id).await?;
+        }
+        ScheduleAction::Rebuild => {
+            crate::commands::rebuild_pod(state, app.clone(), id, Some(schedule.pull)).await?;
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn schedule(time: &str, days: &[Weekday]) -> PodSchedule {
+        PodSchedule {
+            id: "s".to_string(),
+            action: ScheduleAction::Start,
+            time: time.to_string(),
+            days: days.to_vec(),
+            pull: false,
+            catch_up: true,
+            enabled: true,
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_due_times() {
+        let weekdays = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        let s = schedule("08:45", &weekdays);
+        // Friday 08:00 to Tuesday 09:00 spans a weekend
+        let due = due_times(&s, &at("2024-05-03T08:00:00Z"), &at("2024-05-07T09:00:00Z"));
+        assert_eq!(
+            due,
+            vec![
+                at("2024-05-03T08:45:00Z"),
+                at("2024-05-06T08:45:00Z"),
+                at("2024-05-07T08:45:00Z"),
+            ]
+        );
+        // The window is exclusive at the start
+        assert!(due_times(&s, &at("2024-05-03T08:45:00Z"), &at("2024-05-03T09:00:00Z")).is_empty());
+    }
+
+    #[test]
+    fn test_next_run() {
+        let s = schedule("03:00", &[Weekday::Sun]);
+        // Sunday 03:00 itself is past
+        assert_eq!(
+            next_run(&s, &at("2024-05-05T03:00:00Z")),
+            Some(at("2024-05-12T03:00:00Z"))
+        );
+        assert_eq!(
+            next_run(&schedule("19:00", &[]), &at("2024-05-05T20:00:00Z")),
+            Some(at("2024-05-06T19:00:00Z"))
+        );
+        assert_eq!(
+            next_run(&schedule("25:00", &[]), &at("2024-05-05T20:00:00Z")),
+            None
+        );
+        assert!(parse_time("7:5pm").is_err());
+    }
+
+    #[test]
+    fn test_scheduler_roundtrip() {
+        let mut scheduler = Scheduler {
+            checked_until: Some(1_714_725_900),
+            ..Default::default()
+        };
+        let run = |outcome| ScheduleRun {
+            due_at: 1_714_725_900,
+            started_at: 1_714_725_930,
+            caught_up: true,
+            outcome,
+        };
+        scheduler.history.insert(
+            "s1".to_string(),
+            VecDeque::from([
+                run(ScheduleOutcome::Done),
+                run(ScheduleOutcome::Skipped {
+                    reason: "Already running".to_string(),
+                }),
+            ]),
+        );
+
+        let saved = toml::to_string_pretty(&scheduler).unwrap();
+        let loaded: Scheduler = toml::from_str(&saved).unwrap();
+        assert_eq!(loaded.checked_until, Some(1_714_725_900));
+        let history = loaded.history("s1");
+        assert_eq!(history.len(), 2);
+        assert!(history[0].caught_up);
+        assert_eq!(
+            history[1].outcome,
+            ScheduleOutcome::Skipped {
+                reason: "Already running".to_string()
+            }
+        );
+        assert!(toml::from_str::<Scheduler>("").unwrap().history.is_empty());
+    }
+}
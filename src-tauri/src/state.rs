@@ -6,7 +6,16 @@ use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
 use crate::config::settings::Settings;
-use crate::types::{LogEntry, Pod};
+use crate::devcontainer::drift::ConfigWatcher;
+use crate::docker::endpoint::{DockerEndpoint, DEFAULT_ENDPOINT};
+use crate::docker::idle::IdleTracker;
+use crate::docker::restart::RestartTracker;
+use crate::git::GitWatcher;
+use crate::docker::runtime::RuntimeInfo;
+use crate::network::expose::ProxyActivity;
+use crate::scheduler::Scheduler;
+use crate::terminal::session::TerminalSession;
+use crate::types::{DockerConnection, DockerConnectionStatus, LogEntry, Pod};
 
 const MAX_LOG_LINES: usize = 10_000;
 
@@ -59,33 +68,115 @@ impl LogBuffer {
     }
 }
 
-pub struct AppStateInner {
+/// An active Docker endpoint and its supervisor's view of it.
+pub struct EndpointState {
+    pub endpoint: DockerEndpoint,
+    /// Set while connected
     pub docker: Option<Docker>,
+    /// Detected on connect
+    pub runtime: RuntimeInfo,
+    /// Kept current by the connection supervisor
+    pub connection: DockerConnection,
+    /// Stops the endpoint's supervisor
+    pub cancel: CancellationToken,
+}
+
+impl EndpointState {
+    pub fn new(endpoint: DockerEndpoint) -> Self {
+        let connection = DockerConnection {
+            endpoint: endpoint.name.clone(),
+            host: endpoint.host.clone(),
+            runtime: None,
+            status: DockerConnectionStatus::Connecting,
+            error: None,
+            attempts: 0,
+            next_retry_at: None,
+        };
+        Self {
+            endpoint,
+            docker: None,
+            runtime: RuntimeInfo::default(),
+            connection,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+pub struct AppStateInner {
+    /// Active Docker endpoints keyed by name
+    pub endpoints: HashMap<String, EndpointState>,
     pub pods: HashMap<String, Pod>,
     pub pod_locks: HashMap<String, Arc<Mutex<()>>>,
     pub cancellation_tokens: HashMap<String, CancellationToken>,
+    /// Port proxies keyed by (pod id, host port)
     pub port_proxy_tokens: HashMap<(String, u16), CancellationToken>,
+    /// Traffic through each pod's port proxies
+    pub proxy_activity: HashMap<String, Arc<ProxyActivity>>,
     pub build_cancel_tokens: HashMap<String, CancellationToken>,
     pub protocol_cache: HashMap<(u16, String), String>,
     pub log_buffers: HashMap<String, LogBuffer>,
+    pub config_watchers: HashMap<String, ConfigWatcher>,
+    pub git_watchers: HashMap<String, GitWatcher>,
+    pub restart_trackers: HashMap<String, RestartTracker>,
+    pub idle_trackers: HashMap<String, IdleTracker>,
+    /// Terminal sessions inside Nook, keyed by session id
+    pub terminal_sessions: HashMap<String, TerminalSession>,
+    pub scheduler: Scheduler,
     pub settings: Settings,
 }
 
 impl AppStateInner {
     pub fn new(settings: Settings) -> Self {
         Self {
-            docker: None,
+            endpoints: HashMap::new(),
             pods: HashMap::new(),
             pod_locks: HashMap::new(),
             cancellation_tokens: HashMap::new(),
             port_proxy_tokens: HashMap::new(),
+            proxy_activity: HashMap::new(),
             build_cancel_tokens: HashMap::new(),
             protocol_cache: HashMap::new(),
             log_buffers: HashMap::new(),
+            config_watchers: HashMap::new(),
+            git_watchers: HashMap::new(),
+            restart_trackers: HashMap::new(),
+            idle_trackers: HashMap::new(),
+            terminal_sessions: HashMap::new(),
+            scheduler: Scheduler::default(),
             settings,
         }
     }
 
+    /// Name of the endpoint a pod's container lives on.
+    pub fn endpoint_name(&self, pod_id: &str) -> &str {
+        self.pods
+            .get(pod_id)
+            .map(|p| p.endpoint_name())
+            .unwrap_or(DEFAULT_ENDPOINT)
+    }
+
+    /// The endpoint a pod's container lives on, if it is active.
+    pub fn endpoint_for(&self, pod_id: &str) -> Option<&DockerEndpoint> {
+        self.endpoints
+            .get(self.endpoint_name(pod_id))
+            .map(|e| &e.endpoint)
+    }
+
+    /// Docker client for a pod's endpoint, if connected.
+    pub fn docker_for(&self, pod_id: &str) -> Option<Docker> {
+        self.endpoints
+            .get(self.endpoint_name(pod_id))
+            .and_then(|e| e.docker.clone())
+    }
+
+    /// Runtime behind a pod's endpoint; Docker until it is known.
+    pub fn runtime_for(&self, pod_id: &str) -> RuntimeInfo {
+        self.endpoints
+            .get(self.endpoint_name(pod_id))
+            .map(|e| e.runtime)
+            .unwrap_or_default()
+    }
+
     pub fn get_or_create_pod_lock(&mut self, pod_id: &str) -> Arc<Mutex<()>> {
         self.pod_locks
             .entry(pod_id.to_string())
@@ -1,3 +1,4 @@
+pub mod session;
 pub mod shell;
 
 use crate::error::{NookError, NookResult};
@@ -64,15 +65,19 @@ pub fn detect_terminal(user_override: &str) -> NookResult<String> {
     Err(NookError::TerminalNotFound)
 }
 
-/// Launch a terminal with docker exec into a container
+/// Launch a terminal with docker exec into a container. `exec_cli` is the
+/// program and global flags selecting the container's endpoint, e.g.
+/// `docker -H ...` or `podman --url ...`.
 pub fn launch_terminal(
     terminal: &str,
+    exec_cli: &[String],
     container_id: &str,
     shell: &str,
     user: Option<&str>,
     working_dir: Option<&str>,
 ) -> NookResult<()> {
-    let mut docker_args = vec!["docker", "exec", "-it"];
+    let mut docker_args: Vec<&str> = exec_cli.iter().map(String::as_str).collect();
+    docker_args.extend(["exec", "-it"]);
     let user_flag;
     if let Some(u) = user {
         user_flag = u.to_string();
@@ -0,0 +1,324 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::{Stream, StreamExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{NookError, NookResult};
+
+type ExecOutput = Pin<Box<dyn Stream<Item = Result<LogOutput, bollard::errors::Error>> + Send>>;
+type ExecInput = Pin<Box<dyn AsyncWrite + Send>>;
+
+/// How long a closed session's task gets to shut the shell's input and read
+/// its exit code before it is aborted.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a terminal session runs, and as whom and where.
+pub struct ExecTarget<'a> {
+    pub container_id: &'a str,
+    pub shell: &'a str,
+    pub user: Option<&'a str>,
+    pub working_dir: Option<&'a str>,
+}
+
+/// The attached streams of a session whose output isn't read yet.
+struct Attached {
+    docker: Docker,
+    output: ExecOutput,
+    stdin: ExecInput,
+    input: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+/// A shell running in a container behind a TTY, attached to Nook. Dropping
+/// it ends the session like `close`.
+pub struct TerminalSession {
+    pub pod_id: String,
+    pub exec_id: String,
+    input: mpsc::UnboundedSender<Vec<u8>>,
+    cancel: CancellationToken,
+    attached: Option<Attached>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl TerminalSession {
+    /// Start `target.shell` with a TTY of `size` (columns, rows). Nothing is
+    /// read or written until `run`.
+    pub async fn start(
+        docker: &Docker,
+        pod_id: &str,
+        target: &ExecTarget<'_>,
+        size: (u16, u16),
+    ) -> NookResult<Self> {
+        let exec = docker
+            .create_exec(
+                target.container_id,
+                CreateExecOptions::<&str> {
+                    cmd: Some(vec![target.shell]),
+                    env: Some(vec!["TERM=xterm-256color"]),
+                    attach_stdin: Some(true),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    tty: Some(true),
+                    user: target.user,
+                    working_dir: target.working_dir,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| match e {
+                bollard::errors::Error::DockerResponseServerError {
+                    status_code: 404, ..
+                } => NookError::ContainerNotFound(target.container_id.to_string()),
+                other => NookError::Docker(other),
+            })?;
+        let options = StartExecOptions {
+            detach: false,
+            tty: true,
+            output_capacity: None,
+        };
+        let StartExecResults::Attached { output, input } =
+            docker.start_exec(&exec.id, Some(options)).await?
+        else {
+            return Err(NookError::Other("Terminal session detached".to_string()));
+        };
+        // The TTY only exists once the exec runs
+        if let Err(e) = resize(docker, &exec.id, size.0, size.1).await {
+            tracing::warn!("Failed to size terminal session: {}", e);
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            pod_id: pod_id.to_string(),
+            exec_id: exec.id,
+            input: tx,
+            cancel: CancellationToken::new(),
+            attached: Some(Attached {
+                docker: docker.clone(),
+                output,
+                stdin: input,
+                input: rx,
+            }),
+            task: None,
+        })
+    }
+
+    /// Forward the shell's output to `on_output` as it arrives; `on_exit`
+    /// gets its exit code once it ends or the session is closed. Only the
+    /// first call has an effect.
+    pub fn run(
+        &mut self,
+        on_output: impl FnMut(String) + Send + 'static,
+        on_exit: impl FnOnce(Option<i64>) + Send + 'static,
+    ) {
+        let Some(attached) = self.attached.take() else {
+            return;
+        };
+        let exec_id = self.exec_id.clone();
+        let cancel = self.cancel.clone();
+        self.task = Some(tokio::spawn(async move {
+            let Attached {
+                docker,
+                output,
+                stdin,
+                input,
+            } = attached;
+            pump(output, stdin, input, cancel, on_output).await;
+            let exit_code = docker
+                .inspect_exec(&exec_id)
+                .await
+                .ok()
+                .and_then(|e| e.exit_code);
+            on_exit(exit_code);
+        }));
+    }
+
+    /// Send keystrokes or pasted text to the shell.
+    pub fn write(&self, data: Vec<u8>) -> NookResult<()> {
+        self.input
+            .send(data)
+            .map_err(|_| NookError::Other("Terminal session has ended".to_string()))
+    }
+
+    /// Stop forwarding and close the shell's input, which ends most shells.
+    pub fn close(self) {
+        drop(self);
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+        // Aborted if the daemon stops answering while it winds down
+        let Some(task) = self.task.take() else {
+            return;
+        };
+        let abort = task.abort_handle();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if tokio::time::timeout(CLOSE_TIMEOUT, task).await.is_err() {
+                    abort.abort();
+                }
+            });
+        }
+    }
+}
+
+/// Resize a session's TTY to `cols` by `rows`.
+pub async fn resize(docker: &Docker, exec_id: &str, cols: u16, rows: u16) -> NookResult<()> {
+    let options = ResizeExecOptions {
+        height: rows,
+        width: cols,
+    };
+    docker.resize_exec(exec_id, options).await?;
+    Ok(())
+}
+
+/// Forward `input` to the shell and its output to `on_output` until either
+/// side ends or the session is cancelled.
+async fn pump(
+    mut output: ExecOutput,
+    mut stdin: ExecInput,
+    mut input: mpsc::UnboundedReceiver<Vec<u8>>,
+    cancel: CancellationToken,
+    mut on_output: impl FnMut(String),
+) {
+    let mut carry = Vec::new();
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            chunk = output.next() => match chunk {
+                Some(Ok(chunk)) => {
+                    let text = decode_utf8(&mut carry, &chunk.into_bytes());
+                    if !text.is_empty() {
+                        on_output(text);
+                    }
+                }
+                Some(Err(e)) => {
+                    tracing::warn!("Terminal session output failed: {}", e);
+                    break;
+                }
+                None => break,
+            },
+            data = input.recv() => {
+                let Some(data) = data else {
+                    break;
+                };
+                let written = async {
+                    stdin.write_all(&data).await?;
+                    stdin.flush().await
+                };
+                if let Err(e) = written.await {
+                    tracing::warn!("Terminal session input failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+    let _ = stdin.shutdown().await;
+}
+
+/// Text of `carry` followed by `chunk`. A character cut off at the end of
+/// the chunk stays in `carry` for the next one.
+fn decode_utf8(carry: &mut Vec<u8>, chunk: &[u8]) -> String {
+    carry.extend_from_slice(chunk);
+    let valid = match std::str::from_utf8(carry) {
+        Ok(_) => carry.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => return String::from_utf8_lossy(&std::mem::take(carry)).into_owned(),
+    };
+    let rest = carry.split_off(valid);
+    String::from_utf8(std::mem::replace(carry, rest)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::docker::stand_in::{self, Reply};
+
+    #[tokio::test]
+    async fn test_session() {
+        // The exec echoes its input back, as a stand-in for a shell
+        let daemon = stand_in::serve(|request| {
+            if request.ends_with("/start") {
+                Reply::Echo
+            } else if request.ends_with("/exec") {
+                Reply::Json("201 Created", r#"{"Id":"exec-1"}"#.to_string())
+            } else if request.ends_with("/json") {
+                Reply::ok(r#"{"ID":"exec-1","Running":false,"ExitCode":0}"#)
+            } else {
+                Reply::ok("")
+            }
+        });
+        let docker = &daemon.docker;
+        let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        let target = ExecTarget {
+            container_id: "abc123",
+            shell: "/bin/zsh",
+            user: Some("vscode"),
+            working_dir: Some("/workspaces/my app"),
+        };
+        let mut session = TerminalSession::start(docker, "pod-1", &target, (120, 40))
+            .await
+            .unwrap();
+        session.run(
+            move |text| {
+                let _ = output_tx.send(text);
+            },
+            move |exit_code| {
+                let _ = exit_tx.send(exit_code);
+            },
+        );
+        assert_eq!(session.exec_id, "exec-1");
+
+        // "é" split across two writes still arrives whole
+        session.write(b"echo caf\xc3".to_vec()).unwrap();
+        session.write(vec![0xa9, b'\r']).unwrap();
+        let mut echoed = String::new();
+        while !echoed.ends_with('\r') {
+            echoed.push_str(&output_rx.recv().await.unwrap());
+        }
+        assert_eq!(echoed, "echo café\r");
+
+        resize(docker, &session.exec_id, 100, 30).await.unwrap();
+        session.close();
+        assert_eq!(exit_rx.await.unwrap(), Some(0));
+
+        let requests = daemon.requests();
+        let create = requests
+            .iter()
+            .find(|r| r.starts_with("POST /containers/abc123/exec"))
+            .unwrap();
+        for expected in [
+            r#""Tty":true"#,
+            r#""AttachStdin":true"#,
+            r#""Cmd":["/bin/zsh"]"#,
+            r#""WorkingDir":"/workspaces/my app""#,
+        ] {
+            assert!(create.contains(expected), "{} not in {}", expected, create);
+        }
+        assert!(requests
+            .iter()
+            .any(|r| r.starts_with("POST /exec/exec-1/resize?h=40&w=120")));
+        assert!(requests
+            .iter()
+            .any(|r| r.starts_with("POST /exec/exec-1/resize?h=30&w=100")));
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let mut carry = Vec::new();
+        assert_eq!(decode_utf8(&mut carry, b"ls\r\n"), "ls\r\n");
+        assert_eq!(decode_utf8(&mut carry, &[b'a', 0xe2, 0x82]), "a");
+        assert_eq!(decode_utf8(&mut carry, &[0xac, b'b']), "\u{20ac}b");
+        assert!(carry.is_empty());
+        assert_eq!(decode_utf8(&mut carry, &[0xff, b'c']), "\u{fffd}c");
+    }
+}
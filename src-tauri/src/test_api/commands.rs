@@ -26,6 +26,8 @@ pub async fn test_inject_pods(
             pod_id: pod_id.clone(),
             status: pod.status.clone(),
             error_message: pod.error_message.clone(),
+            exit: None,
+            health: None,
         };
         state.pods.insert(pod_id, pod);
         let _ = app.emit("pod-status-changed", &status_event);
@@ -51,6 +53,9 @@ pub async fn test_inject_detected_port(
         process_name: process_name.unwrap_or_default(),
         detection_method: DetectionMethod::WellKnown,
         confidence: Confidence::High,
+        label: None,
+        action: None,
+        service: None,
     };
 
     if let Some(pod) = state.pods.get_mut(&pod_id) {
@@ -85,6 +90,7 @@ pub async fn test_set_stats(
 
     let update = PodStatsUpdate {
         pod_id,
+        service: None,
         cpu_percent,
         memory_used,
         memory_limit,
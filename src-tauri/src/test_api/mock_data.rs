@@ -2,7 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{DetectedPort, ExposedPort, PodStatus, Process};
+use crate::types::{
+    ConfigDrift, ContainerExit, DetectedPort, ExposedPort, GitInfo, HookRun, PodService, PodStatus,
+    Process,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,6 +43,26 @@ pub struct MockPod {
     pub remote_workspace_folder: Option<String>,
     #[serde(default)]
     pub container_name: Option<String>,
+    #[serde(default)]
+    pub config_path: Option<String>,
+    #[serde(default)]
+    pub compose_project: Option<String>,
+    #[serde(default)]
+    pub services: Vec<PodService>,
+    #[serde(default)]
+    pub hook_runs: Vec<HookRun>,
+    #[serde(default)]
+    pub warning: Option<String>,
+    #[serde(default)]
+    pub config_drift: Option<ConfigDrift>,
+    #[serde(default)]
+    pub health: Option<String>,
+    #[serde(default)]
+    pub last_exit: Option<ContainerExit>,
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub git: Option<GitInfo>,
 }
 
 impl From<MockPod> for crate::types::Pod {
@@ -65,6 +88,16 @@ impl From<MockPod> for crate::types::Pod {
             remote_user: mock.remote_user,
             remote_workspace_folder: mock.remote_workspace_folder,
             container_name: mock.container_name,
+            config_path: mock.config_path,
+            compose_project: mock.compose_project,
+            services: mock.services,
+            hook_runs: mock.hook_runs,
+            warning: mock.warning,
+            config_drift: mock.config_drift,
+            health: mock.health,
+            last_exit: mock.last_exit,
+            endpoint: mock.endpoint,
+            git: mock.git,
         }
     }
 }
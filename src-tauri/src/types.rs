@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::pod_state::PodSchedule;
+use crate::config::settings::PortAction;
+use crate::docker::runtime::ContainerRuntime;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum PodStatus {
@@ -34,6 +38,137 @@ pub struct Pod {
     pub remote_user: Option<String>,
     pub remote_workspace_folder: Option<String>,
     pub container_name: Option<String>,
+    /// devcontainer.json the pod is bound to, relative to `project_path`.
+    /// `None` means the default location.
+    pub config_path: Option<String>,
+    /// Docker Compose project (`com.docker.compose.project`) for compose-based pods
+    pub compose_project: Option<String>,
+    /// Other containers of the compose project (db, redis, ...)
+    pub services: Vec<PodService>,
+    /// Latest run of each lifecycle hook since the pod was started
+    pub hook_runs: Vec<HookRun>,
+    /// Set while a lifecycle hook's latest run failed; the pod keeps running
+    pub warning: Option<String>,
+    /// Set when the devcontainer config changed since the container was built
+    pub config_drift: Option<ConfigDrift>,
+    /// Docker health check status (`starting`, `healthy`, `unhealthy`) for
+    /// containers with a HEALTHCHECK
+    pub health: Option<String>,
+    /// How the container last exited, when it stopped outside of Nook
+    pub last_exit: Option<ContainerExit>,
+    /// Docker endpoint the container lives on; empty means the default one
+    pub endpoint: String,
+    /// Branch and state of the project's git working tree, if it is in one
+    pub git: Option<GitInfo>,
+}
+
+impl Pod {
+    /// Name of the endpoint the pod's container lives on.
+    pub fn endpoint_name(&self) -> &str {
+        if self.endpoint.is_empty() {
+            crate::docker::endpoint::DEFAULT_ENDPOINT
+        } else {
+            &self.endpoint
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LifecycleHook {
+    OnCreate,
+    UpdateContent,
+    PostCreate,
+    PostStart,
+    PostAttach,
+}
+
+impl LifecycleHook {
+    /// The devcontainer.json key holding the hook's command.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            LifecycleHook::OnCreate => "onCreateCommand",
+            LifecycleHook::UpdateContent => "updateContentCommand",
+            LifecycleHook::PostCreate => "postCreateCommand",
+            LifecycleHook::PostStart => "postStartCommand",
+            LifecycleHook::PostAttach => "postAttachCommand",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    pub hook: LifecycleHook,
+    pub exit_code: i64,
+    pub finished_at: i64,
+}
+
+/// Config changes since a pod's container was built; a rebuild is recommended.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDrift {
+    /// One line per changed file or feature, e.g. `Dockerfile changed`
+    pub changes: Vec<String>,
+    pub detected_at: i64,
+}
+
+/// Git state of a pod's project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInfo {
+    /// Checked out branch; `None` on a detached HEAD
+    pub branch: Option<String>,
+    /// Commits ahead of the branch's upstream; 0 without one
+    pub ahead: u32,
+    /// Commits behind the branch's upstream; 0 without one
+    pub behind: u32,
+    /// Uncommitted changes or untracked files
+    pub dirty: bool,
+    /// `None` before the first commit
+    pub last_commit: Option<GitCommit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    pub hash: String,
+    pub summary: String,
+    pub author: String,
+    pub committed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerExit {
+    pub exit_code: i64,
+    pub oom_killed: bool,
+    pub finished_at: i64,
+}
+
+/// One exit of a pod's container seen by Nook, and whether it was restarted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartRecord {
+    #[serde(flatten)]
+    pub exit: ContainerExit,
+    /// Backoff before the restart; `None` when the pod was left stopped
+    pub restart_delay_secs: Option<u64>,
+}
+
+/// A sidecar container of a compose-based pod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodService {
+    /// Compose service name (`com.docker.compose.service`)
+    pub name: String,
+    pub container_id: String,
+    pub container_name: Option<String>,
+    pub image: String,
+    pub status: PodStatus,
+    pub cpu_percent: f64,
+    pub memory_used: u64,
+    pub memory_limit: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -52,6 +187,10 @@ pub struct ExposedPort {
     pub protocol: String,
     pub status: PortStatus,
     pub auto_expose: bool,
+    /// Label from devcontainer.json `portsAttributes`
+    pub label: Option<String>,
+    /// Compose service the port belongs to, for `service:port` forwards
+    pub service: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,6 +200,8 @@ pub enum DetectionMethod {
     ProcessName,
     Nmap,
     BannerGrab,
+    /// Protocol declared in devcontainer.json `portsAttributes`
+    Config,
     Unknown,
 }
 
@@ -79,6 +220,12 @@ pub struct DetectedPort {
     pub process_name: String,
     pub detection_method: DetectionMethod,
     pub confidence: Confidence,
+    /// Label from devcontainer.json `portsAttributes`
+    pub label: Option<String>,
+    /// Action from `portsAttributes.onAutoForward`; `None` uses the global setting
+    pub action: Option<PortAction>,
+    /// Compose service the port was found on; `None` for the pod's own container
+    pub service: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +244,8 @@ pub struct Process {
 pub enum LogSource {
     Build,
     Container,
+    /// Output of devcontainer lifecycle hooks (postStartCommand, ...)
+    Lifecycle,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -113,6 +262,8 @@ pub struct LogEntry {
     pub message: String,
     pub source: LogSource,
     pub level: LogLevel,
+    /// Compose service for sidecar container logs
+    pub service: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -128,6 +279,8 @@ pub struct LogBatchEvent {
 #[serde(rename_all = "camelCase")]
 pub struct PodStatsUpdate {
     pub pod_id: String,
+    /// Set for stats of a compose sidecar rather than the pod's own container
+    pub service: Option<String>,
     pub cpu_percent: f64,
     pub memory_used: u64,
     pub memory_limit: u64,
@@ -140,6 +293,13 @@ pub struct PortDetectedEvent {
     pub port: DetectedPort,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortExposedEvent {
+    pub pod_id: String,
+    pub port: ExposedPort,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessListUpdate {
@@ -147,12 +307,219 @@ pub struct ProcessListUpdate {
     pub processes: Vec<Process>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodServicesUpdate {
+    pub pod_id: String,
+    pub services: Vec<PodService>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodHookEvent {
+    pub pod_id: String,
+    pub run: HookRun,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DockerConnectionStatus {
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+/// State of the connection to one Docker endpoint; emitted as
+/// `docker-connection-changed`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerConnection {
+    /// Endpoint name (`default`, a Docker context, ...)
+    pub endpoint: String,
+    /// Daemon address, e.g. `unix:///var/run/docker.sock`
+    pub host: String,
+    /// Docker or Podman, once connected
+    pub runtime: Option<ContainerRuntime>,
+    pub status: DockerConnectionStatus,
+    /// Why the last connection attempt failed
+    pub error: Option<String>,
+    /// Failed attempts since the last successful connection
+    pub attempts: u32,
+    /// When the next attempt is due, while disconnected
+    pub next_retry_at: Option<i64>,
+}
+
+/// Emitted as `pod-config-drift`; `drift` is `None` once the config matches again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodConfigDriftEvent {
+    pub pod_id: String,
+    pub drift: Option<ConfigDrift>,
+}
+
+/// Output of a terminal session; emitted as `terminal-output`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalOutput {
+    pub session_id: String,
+    pub data: String,
+}
+
+/// End of a terminal session; emitted as `terminal-exit`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalExit {
+    pub session_id: String,
+    /// `None` when it couldn't be read
+    pub exit_code: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodGitEvent {
+    pub pod_id: String,
+    pub git: Option<GitInfo>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PodStatusChanged {
     pub pod_id: String,
     pub status: PodStatus,
     pub error_message: Option<String>,
+    /// Set when the container exited on its own or was stopped outside Nook
+    pub exit: Option<ContainerExit>,
+    pub health: Option<String>,
+}
+
+/// Emitted as `pod-stop-progress` while a pod waits for its container to
+/// shut down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodStopProgress {
+    pub pod_id: String,
+    pub signal: String,
+    pub waited_secs: u64,
+    pub timeout_secs: u64,
+    /// Set once the timeout passed and the container was sent `SIGKILL`
+    pub escalated: bool,
+}
+
+/// Emitted as `pod-idle` before an idle pod is stopped, and once it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodIdleNotice {
+    pub pod_id: String,
+    pub idle_secs: u64,
+    /// Seconds left before the pod is stopped; 0 once `stopped`
+    pub stops_in_secs: u64,
+    pub stopped: bool,
+}
+
+/// A volume or bind mount of a pod's container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodMount {
+    /// `volume`, `bind` or `tmpfs`
+    pub kind: String,
+    /// Volume name; `None` for bind and tmpfs mounts
+    pub name: Option<String>,
+    /// Host path of a bind mount, or where Docker keeps a volume
+    pub source: String,
+    pub destination: String,
+    pub read_only: bool,
+}
+
+/// One volume's archive within a `VolumeBackup`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackedUpVolume {
+    pub name: String,
+    /// Where the volume was mounted; restores go to the mount at the same
+    /// path
+    pub destination: String,
+    /// Archive file name within the backup's directory
+    pub file: String,
+    pub size: u64,
+}
+
+/// The named volumes of a pod saved as tar archives at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeBackup {
+    pub id: String,
+    pub pod_name: String,
+    /// Unix seconds
+    pub created_at: i64,
+    pub volumes: Vec<BackedUpVolume>,
+}
+
+/// Emitted as `volume-transfer-progress` while a volume is backed up or
+/// restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeTransferProgress {
+    pub pod_id: String,
+    pub backup_id: String,
+    pub volume: String,
+    pub restoring: bool,
+    pub bytes: u64,
+    /// Archive size, known when restoring
+    pub total: Option<u64>,
+    pub done: bool,
+}
+
+/// How a scheduled pod action went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum ScheduleOutcome {
+    Done,
+    /// Nothing to do, e.g. starting a pod that is already running
+    Skipped { reason: String },
+    Failed { error: String },
+}
+
+/// One execution of a pod schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRun {
+    /// Unix time the run was due
+    pub due_at: i64,
+    pub started_at: i64,
+    /// Run late, after the machine woke up
+    pub caught_up: bool,
+    pub outcome: ScheduleOutcome,
+}
+
+/// A pod schedule with its next run and recent runs, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodScheduleStatus {
+    pub schedule: PodSchedule,
+    /// Unix time; `None` if the schedule is disabled or invalid
+    pub next_run: Option<i64>,
+    pub history: Vec<ScheduleRun>,
+}
+
+/// Outcome of a group action for one of the group's pods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPodResult {
+    pub pod_name: String,
+    /// `None` if no pod has this name any more
+    pub pod_id: Option<String>,
+    /// `None` if the action succeeded or had nothing to do
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerConfigInfo {
+    /// Path relative to the project root, e.g. `.devcontainer/api/devcontainer.json`
+    pub config_path: String,
+    /// Subfolder name for `.devcontainer/<name>/devcontainer.json` configs
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,9 +2,20 @@ import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import type {
   DependencyCheck,
+  DevcontainerConfigInfo,
+  DevcontainerPreview,
+  DockerConnection,
+  DockerEndpoint,
   ExposedPort,
+  GroupPodResult,
   LogEntry,
   Pod,
+  PodGroup,
+  PodMount,
+  PodSnapshot,
+  RestartRecord,
+  ScheduleRun,
+  VolumeBackup,
   Settings,
 } from "../types";
 
@@ -12,16 +23,56 @@ export async function listPods(): Promise<Pod[]> {
   return invoke<Pod[]>("list_pods");
 }
 
-export async function addPod(path: string): Promise<Pod> {
-  return invoke<Pod>("add_pod", { path });
+export async function listDevcontainerConfigs(
+  path: string,
+): Promise<DevcontainerConfigInfo[]> {
+  return invoke<DevcontainerConfigInfo[]>("list_devcontainer_configs", { path });
+}
+
+export async function previewDevcontainer(
+  path: string,
+  configPath?: string,
+): Promise<DevcontainerPreview> {
+  return invoke<DevcontainerPreview>("preview_devcontainer", { path, configPath });
+}
+
+export async function addPod(path: string, configPath?: string): Promise<Pod> {
+  return invoke<Pod>("add_pod", { path, configPath });
+}
+
+export async function clonePod(id: string, path: string, branch?: string): Promise<Pod> {
+  return invoke<Pod>("clone_pod", { id, path, branch });
+}
+
+export async function addPodFromBranch(
+  path: string,
+  branch: string,
+  worktreePath: string,
+  configPath?: string,
+): Promise<Pod> {
+  return invoke<Pod>("add_pod_from_branch", { path, branch, worktreePath, configPath });
+}
+
+export async function refreshGit(): Promise<void> {
+  return invoke("refresh_git");
 }
 
 export async function startPod(id: string): Promise<void> {
   return invoke("start_pod", { id });
 }
 
-export async function stopPod(id: string): Promise<void> {
-  return invoke("stop_pod", { id });
+/** Start several pods and everything they depend on, dependencies first */
+export async function startPods(ids: string[]): Promise<void> {
+  return invoke("start_pods", { ids });
+}
+
+export async function stopPod(id: string, stopDependents = false): Promise<void> {
+  return invoke("stop_pod", { id, stopDependents });
+}
+
+/** Ids of running pods that depend on the pod, in stop order */
+export async function getPodDependents(id: string): Promise<string[]> {
+  return invoke<string[]>("get_pod_dependents", { id });
 }
 
 export async function removePod(
@@ -35,26 +86,45 @@ export async function openTerminal(id: string): Promise<void> {
   return invoke("open_terminal", { id });
 }
 
+export async function openTerminalSession(id: string, cols: number, rows: number): Promise<string> {
+  return invoke<string>("open_terminal_session", { id, cols, rows });
+}
+
+export async function writeTerminal(sessionId: string, data: string): Promise<void> {
+  return invoke("write_terminal", { sessionId, data });
+}
+
+export async function resizeTerminal(sessionId: string, cols: number, rows: number): Promise<void> {
+  return invoke("resize_terminal", { sessionId, cols, rows });
+}
+
+export async function closeTerminal(sessionId: string): Promise<void> {
+  return invoke("close_terminal", { sessionId });
+}
+
 export async function exposePort(
   podId: string,
   containerPort: number,
   hostPort: number,
+  service?: string | null,
 ): Promise<ExposedPort> {
-  return invoke<ExposedPort>("expose_port", { podId, containerPort, hostPort });
+  return invoke<ExposedPort>("expose_port", { podId, containerPort, hostPort, service });
 }
 
 export async function unexposePort(
   podId: string,
   containerPort: number,
+  service?: string | null,
 ): Promise<void> {
-  return invoke("unexpose_port", { podId, containerPort });
+  return invoke("unexpose_port", { podId, containerPort, service });
 }
 
 export async function ignorePort(
   podId: string,
   containerPort: number,
+  service?: string | null,
 ): Promise<void> {
-  return invoke("ignore_port", { podId, containerPort });
+  return invoke("ignore_port", { podId, containerPort, service });
 }
 
 export async function getSettings(): Promise<Settings> {
@@ -85,12 +155,16 @@ export async function forceStopPod(id: string): Promise<void> {
   return invoke("force_stop_pod", { id });
 }
 
-export async function rebuildPod(id: string): Promise<void> {
-  return invoke("rebuild_pod", { id });
+export async function rebuildPod(id: string, pull = false): Promise<void> {
+  return invoke("rebuild_pod", { id, pull });
+}
+
+export async function checkDockerHealth(): Promise<DockerConnection[]> {
+  return invoke<DockerConnection[]>("check_docker_health");
 }
 
-export async function checkDockerHealth(): Promise<boolean> {
-  return invoke<boolean>("check_docker_health");
+export async function listDockerEndpoints(): Promise<DockerEndpoint[]> {
+  return invoke<DockerEndpoint[]>("list_docker_endpoints");
 }
 
 export async function getPodLogs(
@@ -101,6 +175,78 @@ export async function getPodLogs(
   return invoke<LogEntry[]>("get_pod_logs", { id, tail, filter });
 }
 
+export async function getPodSchedules(id: string): Promise<PodScheduleStatus[]> {
+  return invoke<PodScheduleStatus[]>("get_pod_schedules", { id });
+}
+
+export async function snapshotPod(id: string, note: string): Promise<PodSnapshot> {
+  return invoke<PodSnapshot>("snapshot_pod", { id, note });
+}
+
+export async function listSnapshots(id: string): Promise<PodSnapshot[]> {
+  return invoke<PodSnapshot[]>("list_snapshots", { id });
+}
+
+export async function restoreSnapshot(id: string, snapshotId: string): Promise<void> {
+  return invoke("restore_snapshot", { id, snapshotId });
+}
+
+export async function deleteSnapshot(id: string, snapshotId: string): Promise<void> {
+  return invoke("delete_snapshot", { id, snapshotId });
+}
+
+export async function listPodVolumes(id: string): Promise<PodMount[]> {
+  return invoke<PodMount[]>("list_pod_volumes", { id });
+}
+
+export async function backupPodVolumes(id: string): Promise<VolumeBackup> {
+  return invoke<VolumeBackup>("backup_pod_volumes", { id });
+}
+
+/** Backups of every pod, newest first */
+export async function listVolumeBackups(): Promise<VolumeBackup[]> {
+  return invoke<VolumeBackup[]>("list_volume_backups");
+}
+
+/** Restore a backup, possibly of another pod, into the pod's volumes */
+export async function restorePodVolumes(
+  id: string,
+  podName: string,
+  backupId: string,
+): Promise<void> {
+  return invoke("restore_pod_volumes", { id, podName, backupId });
+}
+
+export async function deleteVolumeBackup(podName: string, backupId: string): Promise<void> {
+  return invoke("delete_volume_backup", { podName, backupId });
+}
+
+export async function getRestartHistory(id: string): Promise<RestartRecord[]> {
+  return invoke<RestartRecord[]>("get_restart_history", { id });
+}
+
+export async function listGroups(): Promise<PodGroup[]> {
+  return invoke<PodGroup[]>("list_groups");
+}
+
+export async function saveGroup(group: PodGroup): Promise<void> {
+  return invoke("save_group", { group });
+}
+
+export async function deleteGroup(name: string): Promise<void> {
+  return invoke("delete_group", { name });
+}
+
+export type GroupAction = "start" | "stop" | "rebuild" | "expose";
+
+/** Run an action on every pod of a group; one result per pod */
+export async function runGroupAction(
+  name: string,
+  action: GroupAction,
+): Promise<GroupPodResult[]> {
+  return invoke<GroupPodResult[]>(`${action}_group`, { name });
+}
+
 export async function clearPodLogs(id: string): Promise<void> {
   return invoke("clear_pod_logs", { id });
 }
@@ -122,8 +268,63 @@ export interface PodStateConfig {
   workingDir: string;
   bindAddress: string;
   remoteUser: string;
+  configPath: string;
+  engine: DevcontainerEngine;
+  configHash: string;
+  /** Endpoint new containers are created on; empty means the default one */
+  endpoint: string;
+  /** Empty uses the global stop signal */
+  stopSignal: string;
+  /** Unset uses the global stop timeout */
+  stopTimeout?: number | null;
+  restartPolicy: RestartPolicy;
+  /** OnFailure restarts in a row before giving up; 0 means no limit */
+  restartMaxRetries: number;
+  /** Start the pod when Nook launches */
+  autostart: boolean;
+  /** Stop the running pod after this many idle minutes; 0 means never */
+  idleStopMinutes: number;
+  /** Names of pods started before this one */
+  dependsOn: string[];
+  /** What dependent pods wait for after starting this one */
+  readiness?: Readiness | null;
+  schedules: PodSchedule[];
 }
 
+export type ScheduleAction = "start" | "stop" | "rebuild";
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+/** A pod action repeated at a local time of day */
+export interface PodSchedule {
+  /** Empty for a new schedule; assigned on save */
+  id: string;
+  action: ScheduleAction;
+  /** HH:MM */
+  time: string;
+  /** Empty means every day */
+  days: Weekday[];
+  /** Pull base images again before a rebuild */
+  pull: boolean;
+  /** Run once on wake-up if runs were missed while asleep */
+  catchUp: boolean;
+  enabled: boolean;
+}
+
+export interface PodScheduleStatus {
+  schedule: PodSchedule;
+  /** Unix seconds; null if disabled or invalid */
+  nextRun: number | null;
+  history: ScheduleRun[];
+}
+
+export type Readiness =
+  | { type: "port"; port: number }
+  | { type: "command"; command: string };
+
+export type DevcontainerEngine = "cli" | "native";
+
+export type RestartPolicy = "never" | "onFailure" | "always";
+
 export async function getPodSettings(id: string): Promise<PodStateConfig> {
   return invoke<PodStateConfig>("get_pod_settings", { id });
 }
@@ -22,6 +22,22 @@ export interface MockPod {
   remoteUser?: string | null;
   remoteWorkspaceFolder?: string | null;
   containerName?: string | null;
+  configPath?: string | null;
+  composeProject?: string | null;
+  services?: unknown[];
+  hookRuns?: unknown[];
+  warning?: string | null;
+  configDrift?: { changes: string[]; detectedAt: number } | null;
+  health?: string | null;
+  lastExit?: { exitCode: number; oomKilled: boolean; finishedAt: number } | null;
+  endpoint?: string;
+  git?: {
+    branch: string | null;
+    ahead: number;
+    behind: number;
+    dirty: boolean;
+    lastCommit: { hash: string; summary: string; author: string; committedAt: number } | null;
+  } | null;
 }
 
 export async function testInjectPods(pods: MockPod[]): Promise<void> {
@@ -6,6 +6,13 @@ import type {
   PodStatusChanged,
   ProcessListUpdate,
   PortDetectedEvent,
+  PortExposedEvent,
+  PodServicesUpdate,
+  PodHookEvent,
+  PodConfigDriftEvent,
+  PodGitEvent,
+  PodStopProgress,
+  DockerConnection,
 } from "../types";
 
 const MAX_HISTORY_POINTS = 60;
@@ -15,6 +22,8 @@ let pods = $state<Map<string, Pod>>(new Map());
 let cpuHistory = $state<Map<string, number[]>>(new Map());
 let ramHistory = $state<Map<string, number[]>>(new Map());
 let initialized = $state(false);
+// Shutdown progress of stopping pods, cleared on their next status change
+let stopProgress = $state<Map<string, PodStopProgress>>(new Map());
 
 export function getAllPods(): Pod[] {
   return Array.from(pods.values());
@@ -42,6 +51,10 @@ export function getRamHistory(podId: string): number[] {
   return ramHistory.get(podId) ?? [];
 }
 
+export function getStopProgress(podId: string): PodStopProgress | undefined {
+  return stopProgress.get(podId);
+}
+
 export function isInitialized(): boolean {
   return initialized;
 }
@@ -94,7 +107,8 @@ export function removePodFromStore(id: string): void {
 
 let unlisteners: Array<() => void> = [];
 
-// Throttle stats updates: batch into a single Map swap per animation frame
+// Throttle stats updates: batch into a single Map swap per animation frame.
+// Keyed by pod id, or pod id + service for compose sidecars.
 let pendingStats = new Map<string, PodStatsUpdate>();
 let statsRafId: number | null = null;
 
@@ -106,9 +120,16 @@ function flushStats() {
   const newCpu = new Map(cpuHistory);
   const newRam = new Map(ramHistory);
 
-  for (const [podId, { cpuPercent, memoryUsed, memoryLimit }] of pendingStats) {
+  for (const { podId, service, cpuPercent, memoryUsed, memoryLimit } of pendingStats.values()) {
     const pod = newPods.get(podId);
-    if (pod) {
+    if (pod && service) {
+      newPods.set(podId, {
+        ...pod,
+        services: pod.services.map((s) =>
+          s.name === service ? { ...s, cpuPercent, memoryUsed, memoryLimit } : s,
+        ),
+      });
+    } else if (pod) {
       newPods.set(podId, { ...pod, cpuPercent, memoryUsed, memoryLimit });
       pushHistory(newCpu, podId, cpuPercent);
       pushHistory(newRam, podId, memoryUsed);
@@ -129,8 +150,8 @@ export async function initPodListeners(): Promise<void> {
 
   unlisteners.push(
     await listen<PodStatsUpdate>("pod-stats-update", (event) => {
-      const { podId } = event.payload;
-      pendingStats.set(podId, event.payload);
+      const { podId, service } = event.payload;
+      pendingStats.set(service ? `${podId}/${service}` : podId, event.payload);
       if (statsRafId === null) {
         statsRafId = requestAnimationFrame(flushStats);
       }
@@ -139,11 +160,76 @@ export async function initPodListeners(): Promise<void> {
 
   unlisteners.push(
     await listen<PodStatusChanged>("pod-status-changed", (event) => {
-      const { podId, status, errorMessage } = event.payload;
+      const { podId, status, errorMessage, exit, health } = event.payload;
+      const pod = pods.get(podId);
+      if (pod) {
+        const newMap = new Map(pods);
+        // Hook runs and the last exit are reset whenever the pod starts
+        const hooks = status === "starting" ? { hookRuns: [], warning: null, lastExit: null } : {};
+        const lastExit = exit ? { lastExit: exit } : {};
+        newMap.set(podId, { ...pod, status, errorMessage, health, ...hooks, ...lastExit });
+        pods = newMap;
+      }
+      if (stopProgress.has(podId)) {
+        const newProgress = new Map(stopProgress);
+        newProgress.delete(podId);
+        stopProgress = newProgress;
+      }
+    }),
+  );
+
+  unlisteners.push(
+    await listen<PodStopProgress>("pod-stop-progress", (event) => {
+      const newProgress = new Map(stopProgress);
+      newProgress.set(event.payload.podId, event.payload);
+      stopProgress = newProgress;
+    }),
+  );
+
+  unlisteners.push(
+    await listen<PodHookEvent>("pod-hook-finished", (event) => {
+      const { podId, run, warning } = event.payload;
+      const pod = pods.get(podId);
+      if (pod) {
+        const newMap = new Map(pods);
+        newMap.set(podId, {
+          ...pod,
+          hookRuns: [...pod.hookRuns.filter((r) => r.hook !== run.hook), run],
+          warning,
+        });
+        pods = newMap;
+      }
+    }),
+  );
+
+  // The backend rebuilds the pod list from Docker on every (re)connect
+  unlisteners.push(
+    await listen<DockerConnection>("docker-connection-changed", (event) => {
+      if (event.payload.status === "connected") {
+        loadPods();
+      }
+    }),
+  );
+
+  unlisteners.push(
+    await listen<PodConfigDriftEvent>("pod-config-drift", (event) => {
+      const { podId, drift } = event.payload;
+      const pod = pods.get(podId);
+      if (pod) {
+        const newMap = new Map(pods);
+        newMap.set(podId, { ...pod, configDrift: drift });
+        pods = newMap;
+      }
+    }),
+  );
+
+  unlisteners.push(
+    await listen<PodGitEvent>("pod-git", (event) => {
+      const { podId, git } = event.payload;
       const pod = pods.get(podId);
       if (pod) {
         const newMap = new Map(pods);
-        newMap.set(podId, { ...pod, status, errorMessage });
+        newMap.set(podId, { ...pod, git });
         pods = newMap;
       }
     }),
@@ -175,6 +261,41 @@ export async function initPodListeners(): Promise<void> {
       }
     }),
   );
+
+  unlisteners.push(
+    await listen<PortExposedEvent>("port-exposed", (event) => {
+      const { podId, port } = event.payload;
+      const pod = pods.get(podId);
+      if (pod) {
+        const newMap = new Map(pods);
+        newMap.set(podId, {
+          ...pod,
+          exposedPorts: [
+            ...pod.exposedPorts.filter(
+              (p) => p.containerPort !== port.containerPort || p.service !== port.service,
+            ),
+            port,
+          ],
+          detectedPorts: pod.detectedPorts.filter(
+            (p) => p.containerPort !== port.containerPort || p.service !== port.service,
+          ),
+        });
+        pods = newMap;
+      }
+    }),
+  );
+
+  unlisteners.push(
+    await listen<PodServicesUpdate>("pod-services-update", (event) => {
+      const { podId, services } = event.payload;
+      const pod = pods.get(podId);
+      if (pod) {
+        const newMap = new Map(pods);
+        newMap.set(podId, { ...pod, services });
+        pods = newMap;
+      }
+    }),
+  );
 }
 
 export function destroyPodListeners(): void {
@@ -10,9 +10,22 @@ const defaultSettings: Settings = {
   portsScanInterval: 3000,
   processScanInterval: 5000,
   dockerSocketPath: "",
+  dockerEndpoints: [],
   onboardingComplete: false,
+  launchAtLogin: false,
   logLevel: "info",
   portAction: "prompt",
+  devcontainerCliPath: "",
+  devcontainerExtraArgs: [],
+  devcontainerBuildTimeout: 600,
+  stopSignal: "SIGTERM",
+  stopTimeout: 10,
+  crashLoopExits: 5,
+  crashLoopWindow: 120,
+  idleCpuThreshold: 2,
+  idleWarning: 300,
+  snapshotRetention: 5,
+  backupDir: "",
 };
 
 let settings = $state<Settings>({ ...defaultSettings });
@@ -26,6 +26,215 @@ export interface Pod {
   remoteUser: string | null;
   remoteWorkspaceFolder: string | null;
   containerName: string | null;
+  configPath: string | null;
+  /** Docker Compose project for compose-based pods */
+  composeProject: string | null;
+  /** Sidecar containers of the compose project */
+  services: PodService[];
+  /** Latest run of each lifecycle hook since the pod was started */
+  hookRuns: HookRun[];
+  /** Set while a lifecycle hook's latest run failed */
+  warning: string | null;
+  /** Set when the devcontainer config changed since the container was built */
+  configDrift: ConfigDrift | null;
+  /** Docker health check status for containers with a HEALTHCHECK */
+  health: string | null;
+  /** How the container last exited, when it stopped outside of Nook */
+  lastExit: ContainerExit | null;
+  /** Docker endpoint the container lives on; empty means the default one */
+  endpoint: string;
+  /** Branch and state of the project's git working tree, if it is in one */
+  git: GitInfo | null;
+}
+
+export interface ContainerExit {
+  exitCode: number;
+  oomKilled: boolean;
+  finishedAt: number;
+}
+
+/** An exit of a pod's container seen by Nook, oldest first in the history */
+export interface RestartRecord extends ContainerExit {
+  /** Backoff before the restart; null when the pod was left stopped */
+  restartDelaySecs: number | null;
+}
+
+/** A named set of pods with bulk start, stop, rebuild and expose */
+export interface PodGroup {
+  name: string;
+  /** Pod names */
+  pods: string[];
+}
+
+/** A volume or bind mount of a pod's container */
+export interface PodMount {
+  kind: "volume" | "bind" | "tmpfs" | string;
+  /** Volume name; null for bind and tmpfs mounts */
+  name: string | null;
+  source: string;
+  destination: string;
+  readOnly: boolean;
+}
+
+export interface BackedUpVolume {
+  name: string;
+  destination: string;
+  file: string;
+  size: number;
+}
+
+/** A pod's named volumes saved as tar archives */
+export interface VolumeBackup {
+  id: string;
+  podName: string;
+  /** Unix seconds */
+  createdAt: number;
+  volumes: BackedUpVolume[];
+}
+
+/** Payload of `volume-transfer-progress` */
+export interface VolumeTransferProgress {
+  podId: string;
+  backupId: string;
+  volume: string;
+  restoring: boolean;
+  bytes: number;
+  /** Archive size, known when restoring */
+  total: number | null;
+  done: boolean;
+}
+
+/** An image committed from a pod's container */
+export interface PodSnapshot {
+  id: string;
+  image: string;
+  /** Unix seconds */
+  createdAt: number;
+  note: string;
+}
+
+export type ScheduleOutcome =
+  | { status: "done" }
+  | { status: "skipped"; reason: string }
+  | { status: "failed"; error: string };
+
+/** One execution of a pod schedule; times are Unix seconds */
+export interface ScheduleRun {
+  dueAt: number;
+  startedAt: number;
+  /** Run late, after the machine woke up */
+  caughtUp: boolean;
+  outcome: ScheduleOutcome;
+}
+
+/** Payload of `pod-idle`: a warning before an idle pod stops, or that it stopped */
+export interface PodIdleNotice {
+  podId: string;
+  idleSecs: number;
+  stopsInSecs: number;
+  stopped: boolean;
+}
+
+/** Outcome of a group action for one pod; error is null on success */
+export interface GroupPodResult {
+  podName: string;
+  podId: string | null;
+  error: string | null;
+}
+
+export interface GitInfo {
+  /** Checked out branch; null on a detached HEAD */
+  branch: string | null;
+  /** Commits ahead of and behind the branch's upstream; 0 without one */
+  ahead: number;
+  behind: number;
+  /** Uncommitted changes or untracked files */
+  dirty: boolean;
+  lastCommit: GitCommit | null;
+}
+
+export interface GitCommit {
+  hash: string;
+  summary: string;
+  author: string;
+  committedAt: number;
+}
+
+export interface ConfigDrift {
+  /** One line per changed file or feature, e.g. "Dockerfile changed" */
+  changes: string[];
+  detectedAt: number;
+}
+
+export type DockerConnectionStatus = "connecting" | "connected" | "disconnected";
+
+/** Payload of `docker-connection-changed`, one per active endpoint */
+export interface DockerConnection {
+  /** Endpoint name: "default", a Docker context, "rootless" or "podman" */
+  endpoint: string;
+  /** Daemon address, e.g. unix:///var/run/docker.sock */
+  host: string;
+  /** Docker or Podman, once connected */
+  runtime: "docker" | "podman" | null;
+  status: DockerConnectionStatus;
+  /** Why the last connection attempt failed */
+  error: string | null;
+  attempts: number;
+  /** Unix time of the next attempt, while disconnected */
+  nextRetryAt: number | null;
+}
+
+export interface PodConfigDriftEvent {
+  podId: string;
+  drift: ConfigDrift | null;
+}
+
+/** Payload of `terminal-output` */
+export interface TerminalOutput {
+  sessionId: string;
+  data: string;
+}
+
+/** Payload of `terminal-exit` */
+export interface TerminalExit {
+  sessionId: string;
+  /** null when it couldn't be read */
+  exitCode: number | null;
+}
+
+export interface PodGitEvent {
+  podId: string;
+  git: GitInfo | null;
+}
+
+export type LifecycleHook =
+  | "onCreate"
+  | "updateContent"
+  | "postCreate"
+  | "postStart"
+  | "postAttach";
+
+export interface HookRun {
+  hook: LifecycleHook;
+  exitCode: number;
+  finishedAt: number;
+}
+
+export interface PodHookEvent {
+  podId: string;
+  run: HookRun;
+  warning: string | null;
+}
+
+export interface PodService {
+  name: string;
+  containerId: string;
+  containerName: string | null;
+  image: string;
+  status: PodStatus;
+  cpuPercent: number;
+  memoryUsed: number;
+  memoryLimit: number;
 }
 
 export type PortStatus =
@@ -39,6 +248,10 @@ export interface ExposedPort {
   protocol: string;
   status: PortStatus;
   autoExpose: boolean;
+  /** Label from devcontainer.json portsAttributes */
+  label: string | null;
+  /** Compose service for `service:port` forwards */
+  service: string | null;
 }
 
 export type DetectionMethod =
@@ -46,6 +259,7 @@ export type DetectionMethod =
   | "processName"
   | "nmap"
   | "bannerGrab"
+  | "config"
   | "unknown";
 
 export type Confidence = "high" | "low";
@@ -56,6 +270,12 @@ export interface DetectedPort {
   processName: string;
   detectionMethod: DetectionMethod;
   confidence: Confidence;
+  /** Label from devcontainer.json portsAttributes */
+  label: string | null;
+  /** From portsAttributes.onAutoForward; null uses the global setting */
+  action: PortAction | null;
+  /** Compose service the port was found on; null for the pod's own container */
+  service: string | null;
 }
 
 export interface Process {
@@ -69,6 +289,8 @@ export interface Process {
 
 export interface PodStatsUpdate {
   podId: string;
+  /** Set for stats of a compose sidecar */
+  service: string | null;
   cpuPercent: number;
   memoryUsed: number;
   memoryLimit: number;
@@ -79,6 +301,16 @@ export interface PortDetectedEvent {
   port: DetectedPort;
 }
 
+export interface PodServicesUpdate {
+  podId: string;
+  services: PodService[];
+}
+
+export interface PortExposedEvent {
+  podId: string;
+  port: ExposedPort;
+}
+
 export interface ProcessListUpdate {
   podId: string;
   processes: Process[];
@@ -88,6 +320,19 @@ export interface PodStatusChanged {
   podId: string;
   status: PodStatus;
   errorMessage: string | null;
+  /** Set when the container exited on its own or was stopped outside Nook */
+  exit: ContainerExit | null;
+  health: string | null;
+}
+
+/** Emitted every second while a stopping pod waits for its container to exit */
+export interface PodStopProgress {
+  podId: string;
+  signal: string;
+  waitedSecs: number;
+  timeoutSecs: number;
+  /** Set once the timeout passed and the container was killed */
+  escalated: boolean;
 }
 
 export interface NotExposeFilter {
@@ -106,9 +351,57 @@ export interface Settings {
   portsScanInterval: number;
   processScanInterval: number;
   dockerSocketPath: string;
+  /** Endpoints connected alongside the default one */
+  dockerEndpoints: string[];
   onboardingComplete: boolean;
+  /** Start Nook minimized at login; pods flagged autostart come up with it */
+  launchAtLogin: boolean;
   logLevel: string;
   portAction: PortAction;
+  devcontainerCliPath: string;
+  devcontainerExtraArgs: string[];
+  devcontainerBuildTimeout: number;
+  /** Signal that asks a container to shut down, unless its pod sets one */
+  stopSignal: string;
+  /** Seconds to wait after the stop signal before killing the container */
+  stopTimeout: number;
+  /** Exits within crashLoopWindow seconds that stop auto-restarts; 0 disables the check */
+  crashLoopExits: number;
+  crashLoopWindow: number;
+  /** CPU percentage below which a pod's containers count as idle */
+  idleCpuThreshold: number;
+  /** Seconds of warning before an idle pod is stopped */
+  idleWarning: number;
+  /** Snapshots kept per pod; 0 keeps all */
+  snapshotRetention: number;
+  /** Where volume backups go; empty means Nook's data directory */
+  backupDir: string;
+}
+
+export interface DockerEndpoint {
+  name: string;
+  host: string;
+  tlsDir: string | null;
+  context: string | null;
+}
+
+export interface DevcontainerConfigInfo {
+  configPath: string;
+  name: string | null;
+}
+
+export interface ValidationIssue {
+  line: number;
+  column: number | null;
+  severity: "error" | "warning";
+  message: string;
+}
+
+export interface DevcontainerPreview {
+  configFile: string | null;
+  /** Resolved devcontainer.json (variables substituted), null if it failed to parse */
+  config: Record<string, unknown> | null;
+  issues: ValidationIssue[];
 }
 
 export interface DependencyCheck {
@@ -120,7 +413,7 @@ export interface DependencyCheck {
 
 // Log types
 
-export type LogSource = "build" | "container";
+export type LogSource = "build" | "container" | "lifecycle";
 export type LogLevel = "stdout" | "stderr";
 
 export interface LogEntry {
@@ -128,6 +421,8 @@ export interface LogEntry {
   message: string;
   source: LogSource;
   level: LogLevel;
+  /** Compose service for sidecar logs */
+  service: string | null;
 }
 
 export interface LogBatchEvent {